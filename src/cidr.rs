@@ -0,0 +1,129 @@
+use std::{
+    fmt,
+    net::{Ipv4Addr, Ipv6Addr},
+};
+
+/// An IPv4 or IPv6 CIDR block such as `10.0.0.0/16`. IPv4 addresses are kept
+/// in the low 32 bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    address: u128,
+    prefix: u8,
+    bits: u8,
+}
+
+impl Cidr {
+    /// Parses an IPv4 block such as `10.0.0.0/16`.
+    pub fn ipv4(source: &str) -> Option<Cidr> {
+        let (address, prefix) = source.split_once('/')?;
+        let address: Ipv4Addr = address.parse().ok()?;
+        Cidr::new(u32::from(address).into(), prefix, 32)
+    }
+
+    /// Parses an IPv6 block such as `2001:db8::/32`.
+    pub fn ipv6(source: &str) -> Option<Cidr> {
+        let (address, prefix) = source.split_once('/')?;
+        let address: Ipv6Addr = address.parse().ok()?;
+        Cidr::new(address.into(), prefix, 128)
+    }
+
+    fn new(address: u128, prefix: &str, bits: u8) -> Option<Cidr> {
+        if prefix.is_empty() || !prefix.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+        let prefix: u8 = prefix.parse().ok()?;
+        (prefix <= bits).then_some(Cidr {
+            address,
+            prefix,
+            bits,
+        })
+    }
+
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    /// Whether bits past the prefix are set, as in `10.0.0.1/16`.
+    pub fn has_host_bits(&self) -> bool {
+        self.address & !self.mask() != 0
+    }
+
+    /// The block with its host bits cleared.
+    pub fn network(&self) -> Cidr {
+        Cidr {
+            address: self.address & self.mask(),
+            ..*self
+        }
+    }
+
+    /// Whether every address in `other` is also in this block.
+    pub fn contains(&self, other: &Cidr) -> bool {
+        self.bits == other.bits
+            && self.prefix <= other.prefix
+            && self.address & self.mask() == other.address & self.mask()
+    }
+
+    pub fn overlaps(&self, other: &Cidr) -> bool {
+        self.contains(other) || other.contains(self)
+    }
+
+    fn mask(&self) -> u128 {
+        let host_bits = u32::from(self.bits - self.prefix);
+        let all = u128::MAX >> (128 - u32::from(self.bits));
+        all.checked_shl(host_bits).unwrap_or(0) & all
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.bits {
+            32 => write!(f, "{}/{}", Ipv4Addr::from(self.address as u32), self.prefix),
+            _ => write!(f, "{}/{}", Ipv6Addr::from(self.address), self.prefix),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cidr;
+
+    #[test]
+    fn test_parse() {
+        for valid in ["0.0.0.0/0", "10.0.0.0/16", "192.168.1.1/32"] {
+            assert_eq!(valid, Cidr::ipv4(valid).unwrap().to_string());
+        }
+        for invalid in [
+            "10.0.0.0",
+            "10.0.0/16",
+            "10.0.0.0/33",
+            "10.0.0.0/+8",
+            "::/0",
+        ] {
+            assert_eq!(None, Cidr::ipv4(invalid), "{}", invalid);
+        }
+        assert_eq!("::/0", Cidr::ipv6("::/0").unwrap().to_string());
+        assert_eq!(None, Cidr::ipv6("2001:db8::/129"));
+    }
+
+    #[test]
+    fn test_contains_and_overlaps() {
+        let vpc = Cidr::ipv4("10.0.0.0/16").unwrap();
+        let subnet = Cidr::ipv4("10.0.1.0/24").unwrap();
+        let outside = Cidr::ipv4("10.1.0.0/24").unwrap();
+
+        assert!(vpc.contains(&subnet));
+        assert!(!subnet.contains(&vpc));
+        assert!(subnet.overlaps(&vpc));
+        assert!(!vpc.overlaps(&outside));
+        assert!(Cidr::ipv4("0.0.0.0/0").unwrap().contains(&outside));
+        assert!(Cidr::ipv6("::/0")
+            .unwrap()
+            .contains(&Cidr::ipv6("2001:db8::/32").unwrap()));
+        assert!(!vpc.overlaps(&Cidr::ipv6("::/0").unwrap()));
+
+        let host = Cidr::ipv4("10.0.0.1/16").unwrap();
+        assert!(host.has_host_bits());
+        assert_eq!(vpc, host.network());
+        assert!(!vpc.has_host_bits());
+    }
+}
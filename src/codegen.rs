@@ -0,0 +1,574 @@
+use crate::{
+    resources,
+    spec::{Property, PropertyType, Specification},
+};
+use indexmap::IndexMap;
+use std::collections::HashSet;
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual",
+    "where", "while", "yield",
+];
+
+/// Generates Rust models for the resource types of a specification that have
+/// no hand-written model: a properties struct for each, a struct for each
+/// property type they use, and a `GeneratedResource` enum tagged by `Type`
+/// for [`crate::resources`] to fall back on. Primitives, and lists and maps
+/// of them, are `Value`s, and property types and lists and maps of them are
+/// `Node`s, so that intrinsic functions parse wherever CloudFormation allows
+/// them.
+pub fn generate(specification: &Specification) -> String {
+    let mut resource_types: Vec<&str> = specification
+        .resource_types
+        .keys()
+        .map(String::as_str)
+        .filter(|resource_type| !resources::is_modelled(resource_type))
+        .collect();
+    resource_types.sort_unstable();
+
+    let mut generator = Generator {
+        specification,
+        names: IndexMap::new(),
+        taken: HashSet::new(),
+        pending: Vec::new(),
+        structs: Vec::new(),
+        uses_tag: false,
+        uses_node: false,
+        uses_value: false,
+        uses_map: false,
+    };
+    // Resource types claim their names first, so a property type that would
+    // clash with one is the one renamed.
+    let variants: Vec<(&str, String)> = resource_types
+        .iter()
+        .map(|resource_type| (*resource_type, generator.reserve(type_name(resource_type))))
+        .collect();
+
+    let mut enum_variants = String::new();
+    for (resource_type, name) in &variants {
+        let properties = &specification.resource_types[*resource_type].properties;
+        generator.generate_struct(
+            name,
+            &format!("Properties of `{}`.", resource_type),
+            properties,
+            resource_type,
+            None,
+        );
+        while let Some((key, resource_type)) = generator.pending.pop() {
+            let name = generator.names[&key].clone();
+            if let Some((
+                _,
+                PropertyType {
+                    properties: Some(properties),
+                    ..
+                },
+            )) = specification.property_type(&resource_type, &key)
+            {
+                generator.generate_struct(
+                    &name,
+                    &format!("`{}`.", key),
+                    properties,
+                    &resource_type,
+                    Some(&key),
+                );
+            }
+        }
+
+        let properties = match properties.values().any(|property| property.required) {
+            true => name.clone(),
+            false => format!("Option<{}>", name),
+        };
+        enum_variants.push_str(&format!(
+            "    #[serde(rename = \"{}\")]\n    {}(ResourceContainer<{}>),\n",
+            resource_type, name, properties
+        ));
+    }
+
+    let mut source = String::from(
+        "// @generated by `cargo xtask codegen` from a CloudFormation resource specification.\n\
+         // Do not edit by hand.\n\n",
+    );
+    if !variants.is_empty() {
+        source.push_str("use super::ResourceContainer;\n");
+    }
+    let values = match (generator.uses_node, generator.uses_value) {
+        (true, true) => Some("value::{Node, Value}"),
+        (true, false) => Some("value::Node"),
+        (false, true) => Some("value::Value"),
+        (false, false) => None,
+    };
+    let imports: Vec<&str> = generator
+        .uses_tag
+        .then_some("tag::Tag")
+        .into_iter()
+        .chain(values)
+        .collect();
+    match imports.as_slice() {
+        [] => {}
+        [import] => source.push_str(&format!("use crate::{};\n", import)),
+        _ => source.push_str(&format!("use crate::{{{}}};\n", imports.join(", "))),
+    }
+    if generator.uses_map {
+        source.push_str("use indexmap::IndexMap;\n");
+    }
+    source.push_str("use serde::{Deserialize, Serialize};\n\n");
+
+    source.push_str(
+        "/// Resource types modelled from the resource specification instead of by hand.\n\
+         #[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]\n\
+         #[serde(tag = \"Type\")]\n\
+         #[allow(clippy::large_enum_variant)]\n",
+    );
+    match enum_variants.is_empty() {
+        true => source.push_str("pub enum GeneratedResource {}\n"),
+        false => source.push_str(&format!(
+            "pub enum GeneratedResource {{\n{}}}\n",
+            enum_variants
+        )),
+    }
+    for definition in &generator.structs {
+        source.push('\n');
+        source.push_str(definition);
+    }
+    source
+}
+
+struct Generator<'a> {
+    specification: &'a Specification,
+    /// The struct names of the property types generated so far, by key.
+    names: IndexMap<String, String>,
+    taken: HashSet<String>,
+    /// Property types that have a name but no struct yet, with the resource
+    /// type they were reached from.
+    pending: Vec<(String, String)>,
+    structs: Vec<String>,
+    uses_tag: bool,
+    uses_node: bool,
+    uses_value: bool,
+    uses_map: bool,
+}
+
+impl<'a> Generator<'a> {
+    /// Claims a struct name, adding `Property` until it is unique.
+    fn reserve(&mut self, mut name: String) -> String {
+        while !self.taken.insert(name.clone()) {
+            name.push_str("Property");
+        }
+        name
+    }
+
+    /// Generates a struct with a field for each property. `key` names the
+    /// property type being generated, so fields that lead back to it are
+    /// boxed.
+    fn generate_struct(
+        &mut self,
+        name: &str,
+        doc: &str,
+        properties: &IndexMap<String, Property>,
+        resource_type: &str,
+        key: Option<&str>,
+    ) {
+        let mut fields = String::new();
+        for (property_name, property) in properties {
+            let (field, renamed) = field_name(property_name);
+            let mut field_type = self.rust_type(property, resource_type);
+            let recursive = key.is_some_and(|key| {
+                self.struct_key(property, resource_type)
+                    .is_some_and(|target| {
+                        self.reaches(target, key, resource_type, &mut HashSet::new())
+                    })
+            });
+            if recursive {
+                field_type = format!("Box<{}>", field_type);
+            }
+
+            let mut attributes = Vec::new();
+            if renamed {
+                attributes.push(format!("rename = \"{}\"", property_name));
+            }
+            if !property.required {
+                attributes.push("skip_serializing_if = \"Option::is_none\"".to_string());
+                field_type = format!("Option<{}>", field_type);
+            }
+            if !attributes.is_empty() {
+                fields.push_str(&format!("    #[serde({})]\n", attributes.join(", ")));
+            }
+            fields.push_str(&format!("    pub {}: {},\n", field, field_type));
+        }
+
+        let body = match fields.is_empty() {
+            true => "{}".to_string(),
+            false => format!("{{\n{}}}", fields),
+        };
+        self.structs.push(format!(
+            "/// {}\n\
+             #[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]\n\
+             #[serde(rename_all = \"PascalCase\")]\n\
+             pub struct {} {}\n",
+            doc, name, body
+        ));
+    }
+
+    fn rust_type(&mut self, property: &Property, resource_type: &str) -> String {
+        if property.primitive_type.is_some() {
+            return self.value();
+        }
+        match property.property_type.as_deref() {
+            Some("List") => match property.item_type.as_deref() {
+                Some(item) => match self.named_type(item, resource_type) {
+                    Some(item) if item != "Value" => self.node(format!("Vec<{}>", item)),
+                    _ => self.value(),
+                },
+                None => self.value(),
+            },
+            Some("Map") => match property.item_type.as_deref() {
+                Some(item) => match self.named_type(item, resource_type) {
+                    Some(item) if item != "Value" => {
+                        self.uses_map = true;
+                        self.node(format!("IndexMap<String, {}>", item))
+                    }
+                    _ => self.value(),
+                },
+                None => self.value(),
+            },
+            Some(name) => self
+                .named_type(name, resource_type)
+                .unwrap_or_else(|| self.value()),
+            None => self.value(),
+        }
+    }
+
+    fn value(&mut self) -> String {
+        self.uses_value = true;
+        "Value".to_string()
+    }
+
+    fn node(&mut self, inner: String) -> String {
+        self.uses_node = true;
+        format!("Node<{}>", inner)
+    }
+
+    /// The Rust type of a property type, named on first use and generated
+    /// later, as a `Node`. `Tag` uses the crate's own model.
+    fn named_type(&mut self, name: &str, resource_type: &str) -> Option<String> {
+        let specification = self.specification;
+        let (key, property_type) = specification.property_type(resource_type, name)?;
+        if key == "Tag" {
+            self.uses_tag = true;
+            return Some(self.node("Tag".to_string()));
+        }
+        if property_type.properties.is_none() {
+            return Some(self.rust_type(&property_type.alias, resource_type));
+        }
+        if let Some(name) = self.names.get(key) {
+            let name = name.clone();
+            return Some(self.node(name));
+        }
+        let name = self.reserve(type_name(key));
+        self.names.insert(key.to_string(), name.clone());
+        self.pending
+            .push((key.to_string(), resource_type.to_string()));
+        Some(self.node(name))
+    }
+
+    /// The key of the property type a property holds directly, outside of a
+    /// list or map.
+    fn struct_key(&self, property: &Property, resource_type: &str) -> Option<&'a str> {
+        if property.primitive_type.is_some() {
+            return None;
+        }
+        let name = property
+            .property_type
+            .as_deref()
+            .filter(|name| !matches!(*name, "List" | "Map"))?;
+        match self.specification.property_type(resource_type, name)? {
+            (
+                key,
+                PropertyType {
+                    properties: Some(_),
+                    ..
+                },
+            ) => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Whether the property type `from` holds `to`, directly or through
+    /// other property types, which would make its struct infinitely large.
+    fn reaches(
+        &self,
+        from: &str,
+        to: &str,
+        resource_type: &str,
+        seen: &mut HashSet<String>,
+    ) -> bool {
+        if from == to {
+            return true;
+        }
+        if !seen.insert(from.to_string()) {
+            return false;
+        }
+        let Some(PropertyType {
+            properties: Some(properties),
+            ..
+        }) = self.specification.property_types.get(from)
+        else {
+            return false;
+        };
+        properties.values().any(|property| {
+            self.struct_key(property, resource_type)
+                .is_some_and(|next| self.reaches(next, to, resource_type, seen))
+        })
+    }
+}
+
+/// The struct name for a resource type or property type key, such as
+/// `LogsMetricFilterMetricTransformation` for
+/// `AWS::Logs::MetricFilter.MetricTransformation`.
+fn type_name(key: &str) -> String {
+    let (resource_type, property_type) = match key.split_once('.') {
+        Some((resource_type, property_type)) => (resource_type, Some(property_type)),
+        None => (key, None),
+    };
+    let mut segments: Vec<&str> = resource_type.split("::").collect();
+    if segments.len() > 1 && segments[0] == "AWS" {
+        segments.remove(0);
+    }
+    segments.extend(property_type);
+    segments.into_iter().map(pascal_case).collect()
+}
+
+/// Capitalizes each alphanumeric word, writing acronyms such as `EC2` as
+/// `Ec2` the way the hand-written models do.
+fn pascal_case(segment: &str) -> String {
+    let mut name = String::new();
+    for word in segment.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = word.chars();
+        let Some(first) = chars.next() else {
+            continue;
+        };
+        name.push(first.to_ascii_uppercase());
+        if word.chars().all(|c| !c.is_ascii_lowercase()) {
+            name.extend(chars.map(|c| c.to_ascii_lowercase()));
+        } else {
+            name.extend(chars);
+        }
+    }
+    name
+}
+
+/// The field name for a property, and whether it needs an explicit
+/// `rename` because `rename_all = "PascalCase"` would not give the property
+/// name back.
+fn field_name(property: &str) -> (String, bool) {
+    let chars: Vec<char> = property.chars().collect();
+    let mut field = String::new();
+    for (index, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !field.is_empty() && !field.ends_with('_') {
+                field.push('_');
+            }
+            continue;
+        }
+        if c.is_ascii_uppercase() && index > 0 && !field.ends_with('_') {
+            let previous = chars[index - 1];
+            let next_lowercase = chars.get(index + 1).is_some_and(char::is_ascii_lowercase);
+            if previous.is_ascii_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_ascii_uppercase() && next_lowercase)
+            {
+                field.push('_');
+            }
+        }
+        field.push(c.to_ascii_lowercase());
+    }
+    let mut field = field.trim_end_matches('_').to_string();
+    if field.starts_with(|c: char| c.is_ascii_digit()) {
+        field.insert(0, '_');
+    }
+
+    // The same conversion serde applies for `rename_all = "PascalCase"`.
+    let mut pascal = String::new();
+    let mut capitalize = true;
+    for c in field.chars() {
+        if c == '_' {
+            capitalize = true;
+        } else if capitalize {
+            pascal.push(c.to_ascii_uppercase());
+            capitalize = false;
+        } else {
+            pascal.push(c);
+        }
+    }
+    let renamed = pascal != property;
+
+    match field.as_str() {
+        "self" | "super" | "crate" => (format!("{}_", field), true),
+        _ if KEYWORDS.contains(&field.as_str()) => (format!("r#{}", field), renamed),
+        _ => (field, renamed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::generate;
+    use crate::{
+        document::{self, Format},
+        resources::ResourceContainer,
+        spec::Specification,
+        value::{Node, Value},
+    };
+    use indexmap::IndexMap;
+
+    /// The models [`SPECIFICATION`] generates, compiled so templates can be
+    /// read through them.
+    #[allow(dead_code)]
+    mod generated {
+        include!("codegen/generated.rs");
+    }
+
+    const SPECIFICATION: &str = r#"{
+    "PropertyTypes": {
+        "AWS::Logs::MetricFilter.MetricTransformation": {
+            "Properties": {
+                "Dimensions": {"Type": "List", "ItemType": "Dimension", "Required": false},
+                "MetricName": {"PrimitiveType": "String", "Required": true},
+                "MetricValue": {"PrimitiveType": "String", "Required": true}
+            }
+        },
+        "AWS::Logs::MetricFilter.Dimension": {
+            "Properties": {
+                "Key": {"PrimitiveType": "String", "Required": true},
+                "Value": {"PrimitiveType": "String", "Required": true}
+            }
+        },
+        "AWS::WAFv2::RuleGroup.Statement": {
+            "Properties": {
+                "NotStatement": {"Type": "NotStatement", "Required": false},
+                "SearchString": {"PrimitiveType": "String", "Required": false}
+            }
+        },
+        "AWS::WAFv2::RuleGroup.NotStatement": {
+            "Properties": {
+                "Statement": {"Type": "Statement", "Required": true}
+            }
+        },
+        "Tag": {
+            "Properties": {
+                "Key": {"PrimitiveType": "String", "Required": true},
+                "Value": {"PrimitiveType": "String", "Required": true}
+            }
+        }
+    },
+    "ResourceTypes": {
+        "AWS::Logs::MetricFilter": {
+            "Properties": {
+                "FilterPattern": {"PrimitiveType": "String", "Required": true},
+                "LogGroupName": {"PrimitiveType": "String", "Required": true},
+                "MetricTransformations": {
+                    "Type": "List",
+                    "ItemType": "MetricTransformation",
+                    "Required": true
+                }
+            }
+        },
+        "AWS::SNS::Topic": {
+            "Properties": {
+                "TopicName": {"PrimitiveType": "String", "Required": false}
+            }
+        },
+        "AWS::WAFv2::RuleGroup": {
+            "Properties": {
+                "Capacity": {"PrimitiveType": "Integer", "Required": false},
+                "Rules": {"Type": "Map", "ItemType": "Statement", "Required": false},
+                "Tags": {"Type": "List", "ItemType": "Tag", "Required": false},
+                "Type": {"PrimitiveType": "String", "Required": false},
+                "VPCConfig": {"PrimitiveType": "Json", "Required": false}
+            }
+        }
+    }
+}"#;
+
+    #[test]
+    fn test_generate() {
+        let mut specification = Specification::default();
+        specification.add(SPECIFICATION).unwrap();
+        assert_eq!(
+            include_str!("codegen/generated.rs"),
+            generate(&specification)
+        );
+    }
+
+    #[test]
+    fn test_generated_models_accept_intrinsics() {
+        let yaml = r#"
+Errors:
+  Type: AWS::Logs::MetricFilter
+  Properties:
+    FilterPattern: ERROR
+    LogGroupName: !Ref Logs
+    MetricTransformations:
+      - !If
+        - HasDimensions
+        - MetricName: Errors
+          MetricValue: "1"
+          Dimensions: !Ref Dimensions
+        - !Ref AWS::NoValue
+      - MetricName: Warnings
+        MetricValue: "1"
+Rules:
+  Type: AWS::WAFv2::RuleGroup
+  Properties:
+    Tags: !Ref Tags
+    Rules:
+      Block:
+        NotStatement:
+          Statement:
+            SearchString: !Sub "${Prefix}-bot"
+"#;
+        let document = document::parse(yaml, Format::Yaml).unwrap();
+        let resources: IndexMap<String, generated::GeneratedResource> =
+            serde_json::from_value(serde_json::to_value(document).unwrap()).unwrap();
+
+        let generated::GeneratedResource::LogsMetricFilter(ResourceContainer {
+            properties: filter,
+            ..
+        }) = &resources["Errors"]
+        else {
+            panic!("expected a metric filter");
+        };
+        let transformations = filter.metric_transformations.literal().unwrap();
+        assert!(matches!(
+            transformations[0],
+            Node::Computed(Value::If { .. })
+        ));
+        let warnings = transformations[1].literal().unwrap();
+        assert_eq!(Value::String("Warnings".to_string()), warnings.metric_name);
+        assert_eq!(None, warnings.dimensions);
+
+        let generated::GeneratedResource::WAFv2RuleGroup(ResourceContainer {
+            properties: Some(rules),
+            ..
+        }) = &resources["Rules"]
+        else {
+            panic!("expected a rule group");
+        };
+        assert!(matches!(
+            rules.tags,
+            Some(Node::Computed(Value::Ref { .. }))
+        ));
+        let block = &rules.rules.as_ref().unwrap().literal().unwrap()["Block"];
+        let statement = &block.literal().unwrap().not_statement.as_ref().unwrap();
+        let search = &statement
+            .literal()
+            .unwrap()
+            .statement
+            .literal()
+            .unwrap()
+            .search_string;
+        assert!(matches!(search, Some(Value::Sub { .. })));
+    }
+}
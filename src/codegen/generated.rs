@@ -0,0 +1,78 @@
+// @generated by `cargo xtask codegen` from a CloudFormation resource specification.
+// Do not edit by hand.
+
+use super::ResourceContainer;
+use crate::{tag::Tag, value::{Node, Value}};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// Resource types modelled from the resource specification instead of by hand.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(tag = "Type")]
+#[allow(clippy::large_enum_variant)]
+pub enum GeneratedResource {
+    #[serde(rename = "AWS::Logs::MetricFilter")]
+    LogsMetricFilter(ResourceContainer<LogsMetricFilter>),
+    #[serde(rename = "AWS::WAFv2::RuleGroup")]
+    WAFv2RuleGroup(ResourceContainer<Option<WAFv2RuleGroup>>),
+}
+
+/// Properties of `AWS::Logs::MetricFilter`.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct LogsMetricFilter {
+    pub filter_pattern: Value,
+    pub log_group_name: Value,
+    pub metric_transformations: Node<Vec<Node<LogsMetricFilterMetricTransformation>>>,
+}
+
+/// `AWS::Logs::MetricFilter.MetricTransformation`.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct LogsMetricFilterMetricTransformation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<Node<Vec<Node<LogsMetricFilterDimension>>>>,
+    pub metric_name: Value,
+    pub metric_value: Value,
+}
+
+/// `AWS::Logs::MetricFilter.Dimension`.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct LogsMetricFilterDimension {
+    pub key: Value,
+    pub value: Value,
+}
+
+/// Properties of `AWS::WAFv2::RuleGroup`.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct WAFv2RuleGroup {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rules: Option<Node<IndexMap<String, Node<WAFv2RuleGroupStatement>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<Value>,
+    #[serde(rename = "VPCConfig", skip_serializing_if = "Option::is_none")]
+    pub vpc_config: Option<Value>,
+}
+
+/// `AWS::WAFv2::RuleGroup.Statement`.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct WAFv2RuleGroupStatement {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not_statement: Option<Box<Node<WAFv2RuleGroupNotStatement>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search_string: Option<Value>,
+}
+
+/// `AWS::WAFv2::RuleGroup.NotStatement`.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct WAFv2RuleGroupNotStatement {
+    pub statement: Box<Node<WAFv2RuleGroupStatement>>,
+}
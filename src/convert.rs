@@ -0,0 +1,149 @@
+use crate::document::{self, Format};
+use anyhow::Result;
+
+/// Converts a template between JSON and YAML. Intrinsic functions are written
+/// in short form in YAML (`!Ref`, `!Sub`, `!GetAtt Resource.Attribute`) and in
+/// long form in JSON. Keys keep their document order.
+pub fn convert(source: &str, from: Format, to: Format) -> Result<String> {
+    let document = document::parse(source, from)?;
+    Ok(document::to_string(&document, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::convert;
+    use crate::document::Format;
+
+    #[test]
+    fn test_convert_json_to_yaml() {
+        let json = r#"
+{
+    "Resources": {
+        "InstanceSecurityGroup": {
+            "Type": "AWS::EC2::SecurityGroup",
+            "Properties": {
+                "GroupDescription": "Enable SSH access via port 22",
+                "VpcId": {"Fn::ImportValue": {"Fn::Sub": "${NetworkStack}-VPCID"}},
+                "SecurityGroupIngress": [
+                    {
+                        "IpProtocol": "tcp",
+                        "FromPort": "22",
+                        "ToPort": 22,
+                        "CidrIp": {"Ref": "SSHLocation"}
+                    }
+                ]
+            }
+        }
+    },
+    "Outputs": {
+        "GroupId": {
+            "Value": {"Fn::GetAtt": ["InstanceSecurityGroup", "GroupId"]},
+            "Condition": "IsProduction"
+        },
+        "Zone": {
+            "Value": {"Fn::Select": ["0", {"Fn::GetAZs": ""}]}
+        }
+    }
+}"#;
+        let expected = r#"Resources:
+  InstanceSecurityGroup:
+    Type: AWS::EC2::SecurityGroup
+    Properties:
+      GroupDescription: Enable SSH access via port 22
+      VpcId: !ImportValue
+        Fn::Sub: ${NetworkStack}-VPCID
+      SecurityGroupIngress:
+        - IpProtocol: tcp
+          FromPort: '22'
+          ToPort: 22
+          CidrIp: !Ref SSHLocation
+Outputs:
+  GroupId:
+    Value: !GetAtt InstanceSecurityGroup.GroupId
+    Condition: IsProduction
+  Zone:
+    Value: !Select
+      - '0'
+      - !GetAZs ''
+"#;
+
+        let actual = convert(json, Format::Json, Format::Yaml).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_convert_yaml_to_json() {
+        let yaml = r#"
+Conditions:
+  IsProduction: !And
+    - !Equals [!Ref Environment, production]
+    - !Not [!Condition IsTest]
+Resources:
+  Bucket:
+    Type: AWS::S3::Bucket
+    Properties:
+      BucketName: !Sub
+        - ${Prefix}-logs
+        - Prefix: !FindInMap [Names, !Ref AWS::Region, Prefix]
+"#;
+        let expected = r#"{
+    "Conditions": {
+        "IsProduction": {
+            "Fn::And": [
+                {
+                    "Fn::Equals": [
+                        {
+                            "Ref": "Environment"
+                        },
+                        "production"
+                    ]
+                },
+                {
+                    "Fn::Not": [
+                        {
+                            "Condition": "IsTest"
+                        }
+                    ]
+                }
+            ]
+        }
+    },
+    "Resources": {
+        "Bucket": {
+            "Type": "AWS::S3::Bucket",
+            "Properties": {
+                "BucketName": {
+                    "Fn::Sub": [
+                        "${Prefix}-logs",
+                        {
+                            "Prefix": {
+                                "Fn::FindInMap": [
+                                    "Names",
+                                    {
+                                        "Ref": "AWS::Region"
+                                    },
+                                    "Prefix"
+                                ]
+                            }
+                        }
+                    ]
+                }
+            }
+        }
+    }
+}
+"#;
+
+        let actual = convert(yaml, Format::Yaml, Format::Json).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_convert_round_trip() {
+        let json = include_str!("../template.json");
+
+        let yaml = convert(json, Format::Json, Format::Yaml).unwrap();
+        let actual = convert(&yaml, Format::Yaml, Format::Json).unwrap();
+        assert_eq!(json.trim_end(), actual.trim_end());
+    }
+}
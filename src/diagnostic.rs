@@ -0,0 +1,123 @@
+use std::fmt;
+
+/// A position in the template source. Lines and columns start at 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => f.write_str("error"),
+            Severity::Warning => f.write_str("warning"),
+        }
+    }
+}
+
+/// A problem found in a template. `path` names the offending node with keys
+/// and list indexes separated by `/`, such as `Resources/Bucket/Properties`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub path: String,
+    pub message: String,
+    pub location: Option<Location>,
+}
+
+impl Diagnostic {
+    pub fn error(path: impl Into<String>, message: impl Into<String>) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            path: path.into(),
+            message: message.into(),
+            location: None,
+        }
+    }
+
+    pub fn warning(path: impl Into<String>, message: impl Into<String>) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Warning,
+            ..Diagnostic::error(path, message)
+        }
+    }
+
+    pub fn at(self, location: Location) -> Diagnostic {
+        Diagnostic {
+            location: Some(location),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.severity, self.path, self.message)?;
+        if let Some(location) = self.location {
+            write!(f, " ({})", location)?;
+        }
+        Ok(())
+    }
+}
+
+/// Diagnostics that stop a template from loading.
+#[derive(Debug)]
+pub struct Diagnostics(pub Vec<Diagnostic>);
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, diagnostic) in self.0.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", diagnostic)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostics {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Diagnostic, Diagnostics, Location, Severity};
+
+    #[test]
+    fn test_display_diagnostic() {
+        let diagnostic = Diagnostic::warning("Resources/Bucket/Properties", "versioning is off");
+        assert_eq!(Severity::Warning, diagnostic.severity);
+        assert_eq!(
+            "warning: Resources/Bucket/Properties: versioning is off",
+            diagnostic.to_string()
+        );
+
+        let diagnostic = Diagnostic::error("Resources/Bucket", "duplicate key `Bucket`")
+            .at(Location { line: 4, column: 3 });
+        assert_eq!(
+            "error: Resources/Bucket: duplicate key `Bucket` (line 4, column 3)",
+            diagnostic.to_string()
+        );
+    }
+
+    #[test]
+    fn test_display_diagnostics_one_per_line() {
+        let diagnostics = Diagnostics(vec![
+            Diagnostic::error("A", "first"),
+            Diagnostic::error("B", "second"),
+        ]);
+        assert_eq!("error: A: first\nerror: B: second", diagnostics.to_string());
+    }
+}
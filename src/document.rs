@@ -0,0 +1,114 @@
+use crate::{json, value::Value, yaml};
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::ser::PrettyFormatter;
+use std::path::Path;
+
+/// The syntax a template is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Yaml,
+}
+
+impl Format {
+    /// Picks the format from the file extension, falling back to the content
+    /// for extensions such as `.template` that are used for both.
+    pub fn detect(path: &Path, source: &str) -> Format {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Format::Json,
+            Some("yaml" | "yml") => Format::Yaml,
+            _ if source.trim_start().starts_with('{') => Format::Json,
+            _ => Format::Yaml,
+        }
+    }
+}
+
+/// Parses a template into a lossless document tree that keeps the order of
+/// mapping keys. Duplicate keys are reported with their locations.
+pub fn parse(source: &str, format: Format) -> Result<Value> {
+    match format {
+        Format::Json => json::from_str(source),
+        Format::Yaml => yaml::from_str(source),
+    }
+}
+
+/// Renders a document tree, ending with a newline.
+pub fn to_string(document: &Value, format: Format) -> String {
+    match format {
+        Format::Json => {
+            let mut output = Vec::new();
+            let formatter = PrettyFormatter::with_indent(b"    ");
+            let mut serializer = serde_json::Serializer::with_formatter(&mut output, formatter);
+            document
+                .serialize(&mut serializer)
+                .expect("documents always serialize");
+
+            let mut output = String::from_utf8(output).expect("JSON output is UTF-8");
+            output.push('\n');
+            output
+        }
+        Format::Yaml => yaml::to_string(document),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, to_string, Format};
+    use crate::value::{Map, Value};
+    use std::path::Path;
+
+    #[test]
+    fn test_detect_format() {
+        let test_cases = [
+            ("template.json", "Resources: {}", Format::Json),
+            ("template.yaml", "{}", Format::Yaml),
+            ("template.yml", "{}", Format::Yaml),
+            ("stack.template", "  {\"Resources\": {}}", Format::Json),
+            ("stack.template", "Resources: {}", Format::Yaml),
+        ];
+        for (path, source, expected) in test_cases {
+            assert_eq!(
+                expected,
+                Format::detect(Path::new(path), source),
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_json_and_yaml_alike() {
+        let json = r#"{"Outputs": {"Name": {"Value": {"Ref": "Bucket"}}}}"#;
+        let yaml = "Outputs:\n  Name:\n    Value:\n      Ref: Bucket\n";
+
+        assert_eq!(
+            parse(json, Format::Json).unwrap(),
+            parse(yaml, Format::Yaml).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_to_string_json() {
+        let document = Value::Map(Map::from([(
+            "Resources".to_string(),
+            Value::Map(Map::from([(
+                "Topic".to_string(),
+                Value::Map(Map::from([(
+                    "Type".to_string(),
+                    Value::String("AWS::SNS::Topic".to_string()),
+                )])),
+            )])),
+        )]));
+        let expected = r#"{
+    "Resources": {
+        "Topic": {
+            "Type": "AWS::SNS::Topic"
+        }
+    }
+}
+"#;
+
+        assert_eq!(expected, to_string(&document, Format::Json));
+    }
+}
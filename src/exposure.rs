@@ -0,0 +1,1106 @@
+use crate::{
+    resources::{
+        group_rules, referenced_resource, Ec2, Ingress, LoadBalancer, LoadBalancerV2, Ports,
+        Protocol, Resource, Subnet, Traffic,
+    },
+    value::{Node, Value},
+    Template,
+};
+use std::fmt;
+
+/// Whether a resource can be reached from the internet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reachability {
+    /// Reachable on the listed protocols and ports, such as `tcp/443`.
+    Public(Vec<String>),
+    /// Not reachable. The reason names the first missing link.
+    Private(String),
+    /// Decided by resources outside the template or values only known at
+    /// deploy time.
+    Unknown(String),
+}
+
+/// The reachability of one instance or load balancer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exposure {
+    pub path: String,
+    pub reachability: Reachability,
+}
+
+impl fmt::Display for Exposure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.reachability {
+            Reachability::Public(ports) => {
+                write!(f, "{}: reachable on {}", self.path, ports.join(", "))
+            }
+            Reachability::Private(reason) => write!(f, "{}: not reachable, {}", self.path, reason),
+            Reachability::Unknown(reason) => write!(f, "{}: unknown, {}", self.path, reason),
+        }
+    }
+}
+
+/// Works out which instances and load balancers the internet can reach by
+/// following subnets, route tables, internet gateways, public IP settings and
+/// security group ingress rules. Resources are reported in template order.
+pub fn exposure(template: &Template) -> Vec<Exposure> {
+    let mut report = Vec::new();
+    for (name, resource) in &template.resources {
+        let ports = match resource {
+            Resource::Ec2(instance) => instance_ports(name, &instance.properties, template),
+            Resource::LoadBalancer(load_balancer) => {
+                load_balancer_ports(&load_balancer.properties, template)
+            }
+            // Gateway load balancers only see traffic routed to them.
+            Resource::LoadBalancerV2(load_balancer)
+                if load_balancer.properties.load_balancer_type() != Some("gateway") =>
+            {
+                load_balancer_v2_ports(name, &load_balancer.properties, template)
+            }
+            _ => continue,
+        };
+        let reachability = match ports {
+            Ok(ports) => Reachability::Public(ports),
+            Err(reachability) => reachability,
+        };
+        report.push(Exposure {
+            path: format!("Resources/{}", name),
+            reachability,
+        });
+    }
+    report
+}
+
+fn instance_ports(
+    name: &str,
+    instance: &Ec2,
+    template: &Template,
+) -> Result<Vec<String>, Reachability> {
+    let interfaces = match &instance.network_interfaces {
+        Some(Node::Computed(_)) => {
+            return Err(Reachability::Unknown(
+                "the network interfaces are computed".to_string(),
+            ))
+        }
+        Some(Node::Literal(interfaces)) => interfaces.as_slice(),
+        None => &[],
+    };
+    if interfaces
+        .iter()
+        .any(|interface| interface.literal().is_none())
+    {
+        return Err(Reachability::Unknown(
+            "a network interface is computed".to_string(),
+        ));
+    }
+    let primary = interfaces
+        .iter()
+        .filter_map(Node::literal)
+        .find(|interface| interface.device_index.as_integer() == Some(0));
+    let subnet_id = primary
+        .and_then(|interface| interface.subnet_id.as_ref())
+        .or(instance.subnet_id.as_ref())
+        .ok_or_else(|| {
+            Reachability::Unknown("the instance is launched into a default subnet".to_string())
+        })?;
+    let (subnet_name, subnet) = subnet(subnet_id, template)?;
+
+    let elastic_ip = template.resources.values().any(|resource| match resource {
+        Resource::Eip(eip) => eip
+            .properties
+            .as_ref()
+            .and_then(|eip| eip.instance_id.as_ref())
+            .and_then(referenced_resource)
+            .is_some_and(|instance| instance == name),
+        _ => false,
+    });
+    let public_ip = match primary
+        .and_then(|interface| interface.associate_public_ip_address.as_ref())
+        .or(subnet.map_public_ip_on_launch.as_ref())
+    {
+        Some(value) => value.as_bool().ok_or_else(|| {
+            Reachability::Unknown("whether it gets a public IP address is computed".to_string())
+        })?,
+        None => false,
+    };
+    if !elastic_ip && !public_ip {
+        return Err(Reachability::Private(
+            "the instance has no public IP address".to_string(),
+        ));
+    }
+    routes_to_internet(subnet_name, template)?;
+
+    let lists: Vec<&Node<Vec<Value>>> =
+        match primary.and_then(|interface| interface.group_set.as_ref()) {
+            Some(groups) => vec![groups],
+            None => instance
+                .security_group_ids
+                .iter()
+                .chain(&instance.security_groups)
+                .collect(),
+        };
+    if lists.iter().any(|groups| groups.literal().is_none()) {
+        return Err(Reachability::Unknown(
+            "the security groups are computed".to_string(),
+        ));
+    }
+    let groups: Vec<&Value> = lists.iter().flat_map(|groups| groups.items()).collect();
+    if groups.is_empty() {
+        return Err(Reachability::Private(
+            "the VPC's default security group only allows traffic from its members".to_string(),
+        ));
+    }
+    let (traffic, unresolved) = world_ingress(&groups, template);
+    let mut ports = Vec::new();
+    for traffic in &traffic {
+        let port = describe(traffic);
+        if !ports.contains(&port) {
+            ports.push(port);
+        }
+    }
+    closed_unless_open(ports, unresolved)
+}
+
+fn load_balancer_ports(
+    load_balancer: &LoadBalancer,
+    template: &Template,
+) -> Result<Vec<String>, Reachability> {
+    match load_balancer.is_internet_facing() {
+        Some(true) => {}
+        Some(false) => {
+            return Err(Reachability::Private(
+                "the load balancer is internal".to_string(),
+            ))
+        }
+        None => {
+            return Err(Reachability::Unknown(
+                "the load balancer scheme is computed".to_string(),
+            ))
+        }
+    }
+
+    // Without subnets, the load balancer is placed in the default VPC's
+    // public subnets.
+    if let Some(subnets) = &load_balancer.subnets {
+        let Node::Literal(subnets) = subnets else {
+            return Err(Reachability::Unknown(
+                "the subnets are computed".to_string(),
+            ));
+        };
+        any_routes_to_internet(subnets.iter(), template)?;
+    }
+
+    let Some(listeners) = load_balancer.listeners.all_literals() else {
+        return Err(Reachability::Unknown(
+            "the listeners are computed".to_string(),
+        ));
+    };
+    let listeners = listeners
+        .into_iter()
+        .filter_map(|listener| listener.load_balancer_port.as_integer());
+    let ports: Vec<String> = match &load_balancer.security_groups {
+        // The load balancer gets a security group that opens its listeners.
+        None => listeners.map(|port| format!("tcp/{}", port)).collect(),
+        Some(Node::Computed(_)) => {
+            return Err(Reachability::Unknown(
+                "the security groups are computed".to_string(),
+            ))
+        }
+        Some(Node::Literal(groups)) => {
+            let groups: Vec<&Value> = groups.iter().collect();
+            let (traffic, unresolved) = world_ingress(&groups, template);
+            let ports = listeners
+                .filter(|port| traffic.iter().any(|traffic| traffic.allows_tcp_port(*port)))
+                .map(|port| format!("tcp/{}", port))
+                .collect();
+            return closed_unless_open(ports, unresolved);
+        }
+    };
+    closed_unless_open(ports, None)
+}
+
+fn load_balancer_v2_ports(
+    name: &str,
+    load_balancer: &LoadBalancerV2,
+    template: &Template,
+) -> Result<Vec<String>, Reachability> {
+    let network = match load_balancer.load_balancer_type() {
+        Some(load_balancer_type) => load_balancer_type == "network",
+        None => {
+            return Err(Reachability::Unknown(
+                "the load balancer type is computed".to_string(),
+            ))
+        }
+    };
+    match load_balancer.is_internet_facing() {
+        Some(true) => {}
+        Some(false) => {
+            return Err(Reachability::Private(
+                "the load balancer is internal".to_string(),
+            ))
+        }
+        None => {
+            return Err(Reachability::Unknown(
+                "the load balancer scheme is computed".to_string(),
+            ))
+        }
+    }
+
+    let subnets: Vec<&Value> = match (&load_balancer.subnets, &load_balancer.subnet_mappings) {
+        (Some(Node::Literal(subnets)), _) => subnets.iter().collect(),
+        (None, Some(Node::Literal(mappings))) => mappings
+            .iter()
+            .map(|mapping| match mapping {
+                Value::Map(fields) => fields.get("SubnetId").unwrap_or(mapping),
+                _ => mapping,
+            })
+            .collect(),
+        (None, None) => Vec::new(),
+        _ => {
+            return Err(Reachability::Unknown(
+                "the subnets are computed".to_string(),
+            ))
+        }
+    };
+    if subnets.is_empty() {
+        return Err(Reachability::Unknown(
+            "the load balancer has no subnets".to_string(),
+        ));
+    }
+    any_routes_to_internet(subnets.into_iter(), template)?;
+
+    let mut listeners = Vec::new();
+    for (listener_name, resource) in &template.resources {
+        let Resource::Listener(listener) = resource else {
+            continue;
+        };
+        let listener = &listener.properties;
+        if referenced_resource(&listener.load_balancer_arn) != Some(name) {
+            continue;
+        }
+        let Some(port) = listener.port.as_ref().and_then(Value::as_integer) else {
+            return Err(Reachability::Unknown(format!(
+                "the port of listener `{}` is not known",
+                listener_name
+            )));
+        };
+        let protocols: &[Protocol] = match listener.protocol.as_ref().map(Value::as_str) {
+            Some(Some("UDP")) => &[Protocol::Udp],
+            Some(Some("TCP_UDP")) => &[Protocol::Tcp, Protocol::Udp],
+            Some(None) => {
+                return Err(Reachability::Unknown(format!(
+                    "the protocol of listener `{}` is computed",
+                    listener_name
+                )))
+            }
+            _ => &[Protocol::Tcp],
+        };
+        for protocol in protocols {
+            listeners.push((*protocol, port));
+        }
+    }
+    if listeners.is_empty() {
+        return Err(Reachability::Private(
+            "the load balancer has no listeners".to_string(),
+        ));
+    }
+    let open = |(protocol, port): &(Protocol, i64)| {
+        let protocol = if *protocol == Protocol::Udp {
+            "udp"
+        } else {
+            "tcp"
+        };
+        format!("{}/{}", protocol, port)
+    };
+
+    match &load_balancer.security_groups {
+        // Without security groups, a network load balancer lets all traffic
+        // through to its listeners.
+        None if network => closed_unless_open(listeners.iter().map(open).collect(), None),
+        None => Err(Reachability::Private(
+            "the VPC's default security group only allows traffic from its members".to_string(),
+        )),
+        Some(Node::Computed(_)) => Err(Reachability::Unknown(
+            "the security groups are computed".to_string(),
+        )),
+        Some(Node::Literal(groups)) => {
+            let groups: Vec<&Value> = groups.iter().collect();
+            let (traffic, unresolved) = world_ingress(&groups, template);
+            let ports = listeners
+                .iter()
+                .filter(|(protocol, port)| {
+                    traffic
+                        .iter()
+                        .any(|traffic| traffic.allows_port(*protocol, *port))
+                })
+                .map(open)
+                .collect();
+            closed_unless_open(ports, unresolved)
+        }
+    }
+}
+
+/// Checks that at least one of a load balancer's subnets routes to the
+/// internet, and otherwise gives the reason the first one does not.
+fn any_routes_to_internet<'a>(
+    subnets: impl Iterator<Item = &'a Value>,
+    template: &Template,
+) -> Result<(), Reachability> {
+    let mut reason = None;
+    for subnet_id in subnets {
+        let routed =
+            subnet(subnet_id, template).and_then(|(name, _)| routes_to_internet(name, template));
+        match routed {
+            Ok(()) => return Ok(()),
+            Err(reachability) => {
+                reason.get_or_insert(reachability);
+            }
+        }
+    }
+    match reason {
+        Some(reason) => Err(reason),
+        None => Ok(()),
+    }
+}
+
+fn closed_unless_open(
+    ports: Vec<String>,
+    unresolved: Option<String>,
+) -> Result<Vec<String>, Reachability> {
+    match unresolved {
+        _ if !ports.is_empty() => Ok(ports),
+        Some(unresolved) => Err(Reachability::Unknown(unresolved)),
+        None => Err(Reachability::Private(
+            "no ingress rule allows traffic from the internet".to_string(),
+        )),
+    }
+}
+
+/// Finds a subnet defined in the template.
+fn subnet<'a>(
+    value: &Value,
+    template: &'a Template,
+) -> Result<(&'a str, &'a Subnet), Reachability> {
+    let found = referenced_resource(value).and_then(|name| template.resources.get_key_value(name));
+    match found {
+        Some((name, Resource::Subnet(subnet))) => Ok((name, &subnet.properties)),
+        _ => Err(Reachability::Unknown(format!(
+            "subnet {} is not defined in the template",
+            name(value)
+        ))),
+    }
+}
+
+/// Checks that a subnet's route table has a route to an internet gateway.
+fn routes_to_internet(subnet: &str, template: &Template) -> Result<(), Reachability> {
+    let references = |value: &Value, name: &str| referenced_resource(value) == Some(name);
+    let table = template
+        .resources
+        .values()
+        .find_map(|resource| match resource {
+            Resource::SubnetRouteTableAssociation(association)
+                if references(&association.properties.subnet_id, subnet) =>
+            {
+                Some(&association.properties.route_table_id)
+            }
+            _ => None,
+        });
+    let Some(table) = table.and_then(referenced_resource) else {
+        return Err(Reachability::Private(format!(
+            "subnet `{}` uses the main route table, which has no internet gateway route",
+            subnet
+        )));
+    };
+
+    let routed = template.resources.values().any(|resource| match resource {
+        Resource::Route(route) => {
+            references(&route.properties.route_table_id, table)
+                && route.properties.internet_gateway(template).is_some()
+        }
+        _ => false,
+    });
+    if routed {
+        Ok(())
+    } else {
+        Err(Reachability::Private(format!(
+            "route table `{}` has no route to an internet gateway",
+            table
+        )))
+    }
+}
+
+/// Collects the ingress rules of the given security groups that allow
+/// traffic from anywhere, and the first rule or group that could not be
+/// resolved.
+fn world_ingress<'a>(
+    groups: &[&Value],
+    template: &'a Template,
+) -> (Vec<Traffic<'a>>, Option<String>) {
+    let rules = group_rules(template);
+    let mut traffic = Vec::new();
+    let mut unresolved = None;
+    for group in groups {
+        let Some(ingress) = referenced_resource(group).and_then(|name| rules.get(name)) else {
+            unresolved.get_or_insert(format!(
+                "security group {} is not defined in the template",
+                name(group)
+            ));
+            continue;
+        };
+        for (path, rule) in &ingress.ingress {
+            match rule.and_then(Ingress::traffic) {
+                Some(rule) if rule.peer.is_world() => traffic.push(rule),
+                Some(_) => {}
+                None => {
+                    unresolved.get_or_insert(format!("the ingress rule at `{}` is computed", path));
+                }
+            }
+        }
+    }
+    (traffic, unresolved)
+}
+
+fn describe(traffic: &Traffic) -> String {
+    let protocol = match traffic.protocol {
+        Protocol::All => return "all traffic".to_string(),
+        Protocol::Tcp => "tcp",
+        Protocol::Udp => "udp",
+        Protocol::Icmp => return "icmp".to_string(),
+        Protocol::Icmpv6 => return "icmpv6".to_string(),
+        Protocol::Other(number) => return format!("protocol {}", number),
+    };
+    match traffic.ports {
+        Ports::Range(from, to) if from == to => format!("{}/{}", protocol, from),
+        Ports::Range(from, to) => format!("{}/{}-{}", protocol, from, to),
+        _ => protocol.to_string(),
+    }
+}
+
+/// Names a value in a reason: the resource or parameter it refers to, or the
+/// literal.
+fn name(value: &Value) -> String {
+    match (referenced_resource(value), value.as_str()) {
+        (Some(name), _) | (None, Some(name)) => format!("`{}`", name),
+        (None, None) => "computed by an intrinsic function".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{exposure, Reachability};
+    use crate::{document::Format, Template};
+
+    /// A VPC with a public subnet, routed to an internet gateway, and a
+    /// private one, and a security group open to the world on 443. Tests add
+    /// their resources after it.
+    const NETWORK: &str = r#"
+Parameters:
+  Flag:
+    Type: String
+Resources:
+  Vpc:
+    Type: AWS::EC2::VPC
+    Properties:
+      CidrBlock: 10.0.0.0/16
+  Gateway:
+    Type: AWS::EC2::InternetGateway
+  Attachment:
+    Type: AWS::EC2::VPCGatewayAttachment
+    Properties:
+      VpcId: !Ref Vpc
+      InternetGatewayId: !Ref Gateway
+  PublicSubnet:
+    Type: AWS::EC2::Subnet
+    Properties:
+      VpcId: !Ref Vpc
+      CidrBlock: 10.0.0.0/24
+      MapPublicIpOnLaunch: true
+  PrivateSubnet:
+    Type: AWS::EC2::Subnet
+    Properties:
+      VpcId: !Ref Vpc
+      CidrBlock: 10.0.1.0/24
+      MapPublicIpOnLaunch: true
+  PublicRouteTable:
+    Type: AWS::EC2::RouteTable
+    Properties:
+      VpcId: !Ref Vpc
+  PublicRoute:
+    Type: AWS::EC2::Route
+    DependsOn: Attachment
+    Properties:
+      RouteTableId: !Ref PublicRouteTable
+      DestinationCidrBlock: 0.0.0.0/0
+      GatewayId: !Ref Gateway
+  PublicAssociation:
+    Type: AWS::EC2::SubnetRouteTableAssociation
+    Properties:
+      RouteTableId: !Ref PublicRouteTable
+      SubnetId: !Ref PublicSubnet
+  WebGroup:
+    Type: AWS::EC2::SecurityGroup
+    Properties:
+      GroupDescription: Web
+      VpcId: !Ref Vpc
+      SecurityGroupIngress:
+        - IpProtocol: tcp
+          FromPort: 443
+          ToPort: 443
+          CidrIp: 0.0.0.0/0
+        - IpProtocol: tcp
+          FromPort: 22
+          ToPort: 22
+          CidrIp: 10.0.0.0/8
+"#;
+
+    /// The exposure of `resources` added to [`NETWORK`].
+    fn reachability(resources: &str) -> Vec<(String, Reachability)> {
+        let yaml = format!("{}{}", NETWORK, resources);
+        let template = Template::parse(&yaml, Format::Yaml).unwrap();
+        exposure(&template)
+            .into_iter()
+            .map(|exposure| (exposure.path, exposure.reachability))
+            .collect()
+    }
+
+    fn public(ports: &[&str]) -> Reachability {
+        Reachability::Public(ports.iter().map(|port| port.to_string()).collect())
+    }
+
+    #[test]
+    fn test_exposure_public_instance() {
+        let resources = r#"
+  WebRange:
+    Type: AWS::EC2::SecurityGroupIngress
+    Properties:
+      GroupId: !Ref WebGroup
+      IpProtocol: tcp
+      FromPort: 8000
+      ToPort: 8080
+      CidrIpv6: ::/0
+  Web:
+    Type: AWS::EC2::Instance
+    Properties:
+      ImageId: ami-12345678
+      SubnetId: !Ref PublicSubnet
+      SecurityGroupIds: [!Ref WebGroup]
+"#;
+        let expected = vec![(
+            "Resources/Web".to_string(),
+            public(&["tcp/443", "tcp/8000-8080"]),
+        )];
+
+        assert_eq!(expected, reachability(resources));
+    }
+
+    #[test]
+    fn test_exposure_instance_in_private_subnet() {
+        let resources = r#"
+  Worker:
+    Type: AWS::EC2::Instance
+    Properties:
+      ImageId: ami-12345678
+      SubnetId: !Ref PrivateSubnet
+      SecurityGroupIds: [!Ref WebGroup]
+"#;
+        let expected = vec![(
+            "Resources/Worker".to_string(),
+            Reachability::Private(
+                "subnet `PrivateSubnet` uses the main route table, which has no internet gateway route"
+                    .to_string(),
+            ),
+        )];
+
+        assert_eq!(expected, reachability(resources));
+    }
+
+    #[test]
+    fn test_exposure_instance_public_ip() {
+        let resources = r#"
+  Quiet:
+    Type: AWS::EC2::Instance
+    Properties:
+      ImageId: ami-12345678
+      NetworkInterfaces:
+        - DeviceIndex: 0
+          SubnetId: !Ref PublicSubnet
+          AssociatePublicIpAddress: false
+          GroupSet: [!Ref WebGroup]
+  Elastic:
+    Type: AWS::EC2::Instance
+    Properties:
+      ImageId: ami-12345678
+      NetworkInterfaces:
+        - DeviceIndex: 0
+          SubnetId: !Ref PublicSubnet
+          AssociatePublicIpAddress: false
+          GroupSet: [!Ref WebGroup]
+  Address:
+    Type: AWS::EC2::EIP
+    Properties:
+      InstanceId: !Ref Elastic
+  Conditional:
+    Type: AWS::EC2::Instance
+    Properties:
+      ImageId: ami-12345678
+      NetworkInterfaces:
+        - DeviceIndex: 0
+          SubnetId: !Ref PublicSubnet
+          AssociatePublicIpAddress: !Ref Flag
+          GroupSet: [!Ref WebGroup]
+"#;
+        let expected = vec![
+            (
+                "Resources/Quiet".to_string(),
+                Reachability::Private("the instance has no public IP address".to_string()),
+            ),
+            ("Resources/Elastic".to_string(), public(&["tcp/443"])),
+            (
+                "Resources/Conditional".to_string(),
+                Reachability::Unknown(
+                    "whether it gets a public IP address is computed".to_string(),
+                ),
+            ),
+        ];
+
+        assert_eq!(expected, reachability(resources));
+    }
+
+    #[test]
+    fn test_exposure_instance_subnet_outside_template() {
+        let resources = r#"
+  Elsewhere:
+    Type: AWS::EC2::Instance
+    Properties:
+      ImageId: ami-12345678
+      SubnetId: subnet-12345678
+  Default:
+    Type: AWS::EC2::Instance
+    Properties:
+      ImageId: ami-12345678
+"#;
+        let expected = vec![
+            (
+                "Resources/Elsewhere".to_string(),
+                Reachability::Unknown(
+                    "subnet `subnet-12345678` is not defined in the template".to_string(),
+                ),
+            ),
+            (
+                "Resources/Default".to_string(),
+                Reachability::Unknown("the instance is launched into a default subnet".to_string()),
+            ),
+        ];
+
+        assert_eq!(expected, reachability(resources));
+    }
+
+    #[test]
+    fn test_exposure_instance_security_groups() {
+        let resources = r#"
+  Ungrouped:
+    Type: AWS::EC2::Instance
+    Properties:
+      ImageId: ami-12345678
+      SubnetId: !Ref PublicSubnet
+  Computed:
+    Type: AWS::EC2::Instance
+    Properties:
+      ImageId: ami-12345678
+      SubnetId: !Ref PublicSubnet
+      SecurityGroupIds: !If [HasGroups, [!Ref WebGroup], !Ref AWS::NoValue]
+  Imported:
+    Type: AWS::EC2::Instance
+    Properties:
+      ImageId: ami-12345678
+      SubnetId: !Ref PublicSubnet
+      SecurityGroupIds: [sg-12345678]
+  SshGroup:
+    Type: AWS::EC2::SecurityGroup
+    Properties:
+      GroupDescription: SSH
+      VpcId: !Ref Vpc
+      SecurityGroupIngress:
+        - IpProtocol: tcp
+          FromPort: 22
+          ToPort: 22
+          CidrIp: !Ref Flag
+  Ssh:
+    Type: AWS::EC2::Instance
+    Properties:
+      ImageId: ami-12345678
+      SubnetId: !Ref PublicSubnet
+      SecurityGroupIds: [!Ref SshGroup]
+"#;
+        let expected = vec![
+            (
+                "Resources/Ungrouped".to_string(),
+                Reachability::Private(
+                    "the VPC's default security group only allows traffic from its members"
+                        .to_string(),
+                ),
+            ),
+            (
+                "Resources/Computed".to_string(),
+                Reachability::Unknown("the security groups are computed".to_string()),
+            ),
+            (
+                "Resources/Imported".to_string(),
+                Reachability::Unknown(
+                    "security group `sg-12345678` is not defined in the template".to_string(),
+                ),
+            ),
+            (
+                "Resources/Ssh".to_string(),
+                Reachability::Unknown(
+                    "the ingress rule at `Resources/SshGroup/Properties/SecurityGroupIngress/0` is computed"
+                        .to_string(),
+                ),
+            ),
+        ];
+
+        assert_eq!(expected, reachability(resources));
+    }
+
+    #[test]
+    fn test_exposure_classic_load_balancers() {
+        let resources = r#"
+  Balancer:
+    Type: AWS::ElasticLoadBalancing::LoadBalancer
+    Properties:
+      Subnets: [!Ref PrivateSubnet, !Ref PublicSubnet]
+      SecurityGroups: [!Ref WebGroup]
+      Listeners:
+        - LoadBalancerPort: 443
+          InstancePort: 8080
+          Protocol: HTTPS
+        - LoadBalancerPort: 80
+          InstancePort: 8080
+          Protocol: HTTP
+  Ungrouped:
+    Type: AWS::ElasticLoadBalancing::LoadBalancer
+    Properties:
+      Subnets: [!Ref PublicSubnet]
+      Listeners:
+        - LoadBalancerPort: 80
+          InstancePort: 80
+          Protocol: HTTP
+  Internal:
+    Type: AWS::ElasticLoadBalancing::LoadBalancer
+    Properties:
+      Scheme: internal
+      Listeners:
+        - LoadBalancerPort: 80
+          InstancePort: 80
+          Protocol: HTTP
+  Private:
+    Type: AWS::ElasticLoadBalancing::LoadBalancer
+    Properties:
+      Subnets: [!Ref PrivateSubnet]
+      Listeners:
+        - LoadBalancerPort: 80
+          InstancePort: 80
+          Protocol: HTTP
+"#;
+        let expected = vec![
+            ("Resources/Balancer".to_string(), public(&["tcp/443"])),
+            ("Resources/Ungrouped".to_string(), public(&["tcp/80"])),
+            (
+                "Resources/Internal".to_string(),
+                Reachability::Private("the load balancer is internal".to_string()),
+            ),
+            (
+                "Resources/Private".to_string(),
+                Reachability::Private(
+                    "subnet `PrivateSubnet` uses the main route table, which has no internet gateway route"
+                        .to_string(),
+                ),
+            ),
+        ];
+
+        assert_eq!(expected, reachability(resources));
+    }
+
+    #[test]
+    fn test_exposure_classic_load_balancer_computed() {
+        let resources = r#"
+  Scheme:
+    Type: AWS::ElasticLoadBalancing::LoadBalancer
+    Properties:
+      Scheme: !Ref Flag
+      Listeners:
+        - LoadBalancerPort: 80
+          InstancePort: 80
+          Protocol: HTTP
+  Subnets:
+    Type: AWS::ElasticLoadBalancing::LoadBalancer
+    Properties:
+      Subnets: !Split [",", !Ref Flag]
+      Listeners:
+        - LoadBalancerPort: 80
+          InstancePort: 80
+          Protocol: HTTP
+  Listeners:
+    Type: AWS::ElasticLoadBalancing::LoadBalancer
+    Properties:
+      Subnets: [!Ref PublicSubnet]
+      Listeners:
+        - !If
+          - HasHttp
+          - LoadBalancerPort: 80
+            InstancePort: 80
+            Protocol: HTTP
+          - !Ref AWS::NoValue
+"#;
+        let expected = vec![
+            (
+                "Resources/Scheme".to_string(),
+                Reachability::Unknown("the load balancer scheme is computed".to_string()),
+            ),
+            (
+                "Resources/Subnets".to_string(),
+                Reachability::Unknown("the subnets are computed".to_string()),
+            ),
+            (
+                "Resources/Listeners".to_string(),
+                Reachability::Unknown("the listeners are computed".to_string()),
+            ),
+        ];
+
+        assert_eq!(expected, reachability(resources));
+    }
+
+    #[test]
+    fn test_exposure_application_load_balancers() {
+        let resources = r#"
+  Application:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Subnets: [!Ref PrivateSubnet, !Ref PublicSubnet]
+      SecurityGroups: [!Ref WebGroup]
+  Https:
+    Type: AWS::ElasticLoadBalancingV2::Listener
+    Properties:
+      LoadBalancerArn: !Ref Application
+      Port: 443
+      Protocol: HTTPS
+      DefaultActions:
+        - Type: fixed-response
+          FixedResponseConfig:
+            StatusCode: "200"
+  Http:
+    Type: AWS::ElasticLoadBalancingV2::Listener
+    Properties:
+      LoadBalancerArn: !Ref Application
+      Port: 80
+      Protocol: HTTP
+      DefaultActions:
+        - Type: fixed-response
+          FixedResponseConfig:
+            StatusCode: "200"
+  Ungrouped:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Subnets: [!Ref PublicSubnet]
+  UngroupedHttp:
+    Type: AWS::ElasticLoadBalancingV2::Listener
+    Properties:
+      LoadBalancerArn: !Ref Ungrouped
+      Port: 80
+      Protocol: HTTP
+      DefaultActions:
+        - Type: fixed-response
+          FixedResponseConfig:
+            StatusCode: "200"
+  Private:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Subnets: [!Ref PrivateSubnet]
+      SecurityGroups: [!Ref WebGroup]
+  Idle:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Subnets: [!Ref PublicSubnet]
+      SecurityGroups: [!Ref WebGroup]
+"#;
+        let expected = vec![
+            ("Resources/Application".to_string(), public(&["tcp/443"])),
+            (
+                "Resources/Ungrouped".to_string(),
+                Reachability::Private(
+                    "the VPC's default security group only allows traffic from its members"
+                        .to_string(),
+                ),
+            ),
+            (
+                "Resources/Private".to_string(),
+                Reachability::Private(
+                    "subnet `PrivateSubnet` uses the main route table, which has no internet gateway route"
+                        .to_string(),
+                ),
+            ),
+            (
+                "Resources/Idle".to_string(),
+                Reachability::Private("the load balancer has no listeners".to_string()),
+            ),
+        ];
+
+        assert_eq!(expected, reachability(resources));
+    }
+
+    #[test]
+    fn test_exposure_network_load_balancers() {
+        let resources = r#"
+  Network:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Type: network
+      SubnetMappings:
+        - SubnetId: !Ref PublicSubnet
+  Dns:
+    Type: AWS::ElasticLoadBalancingV2::Listener
+    Properties:
+      LoadBalancerArn: !Ref Network
+      Port: 53
+      Protocol: TCP_UDP
+      DefaultActions:
+        - Type: forward
+          TargetGroupArn: !Ref DnsTargets
+  Grouped:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Type: network
+      Subnets: [!Ref PublicSubnet]
+      SecurityGroups: [!Ref WebGroup]
+  Tls:
+    Type: AWS::ElasticLoadBalancingV2::Listener
+    Properties:
+      LoadBalancerArn: !Ref Grouped
+      Port: 443
+      Protocol: TLS
+      DefaultActions:
+        - Type: forward
+          TargetGroupArn: !Ref WebTargets
+  Ssh:
+    Type: AWS::ElasticLoadBalancingV2::Listener
+    Properties:
+      LoadBalancerArn: !Ref Grouped
+      Port: 22
+      Protocol: TCP
+      DefaultActions:
+        - Type: forward
+          TargetGroupArn: !Ref SshTargets
+"#;
+        let expected = vec![
+            (
+                "Resources/Network".to_string(),
+                public(&["tcp/53", "udp/53"]),
+            ),
+            ("Resources/Grouped".to_string(), public(&["tcp/443"])),
+        ];
+
+        assert_eq!(expected, reachability(resources));
+    }
+
+    #[test]
+    fn test_exposure_load_balancers_v2_internal_and_computed() {
+        let resources = r#"
+  Internal:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Scheme: internal
+      Subnets: [!Ref PublicSubnet]
+  Scheme:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Scheme: !Ref Flag
+      Subnets: [!Ref PublicSubnet]
+  Kind:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Type: !Ref Flag
+      Subnets: [!Ref PublicSubnet]
+  Subnets:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Subnets: !Split [",", !Ref Flag]
+  Port:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Type: network
+      Subnets: [!Ref PublicSubnet]
+  PortListener:
+    Type: AWS::ElasticLoadBalancingV2::Listener
+    Properties:
+      LoadBalancerArn: !Ref Port
+      Port: !Ref Flag
+      Protocol: TCP
+      DefaultActions:
+        - Type: forward
+          TargetGroupArn: !Ref Targets
+  Protocol:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Type: network
+      Subnets: [!Ref PublicSubnet]
+  ProtocolListener:
+    Type: AWS::ElasticLoadBalancingV2::Listener
+    Properties:
+      LoadBalancerArn: !Ref Protocol
+      Port: 53
+      Protocol: !Ref Flag
+      DefaultActions:
+        - Type: forward
+          TargetGroupArn: !Ref Targets
+  Groups:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Subnets: [!Ref PublicSubnet]
+      SecurityGroups: !Split [",", !Ref Flag]
+  GroupsListener:
+    Type: AWS::ElasticLoadBalancingV2::Listener
+    Properties:
+      LoadBalancerArn: !Ref Groups
+      Port: 443
+      Protocol: HTTPS
+      DefaultActions:
+        - Type: forward
+          TargetGroupArn: !Ref Targets
+"#;
+        let expected = vec![
+            (
+                "Resources/Internal".to_string(),
+                Reachability::Private("the load balancer is internal".to_string()),
+            ),
+            (
+                "Resources/Scheme".to_string(),
+                Reachability::Unknown("the load balancer scheme is computed".to_string()),
+            ),
+            (
+                "Resources/Kind".to_string(),
+                Reachability::Unknown("the load balancer type is computed".to_string()),
+            ),
+            (
+                "Resources/Subnets".to_string(),
+                Reachability::Unknown("the subnets are computed".to_string()),
+            ),
+            (
+                "Resources/Port".to_string(),
+                Reachability::Unknown(
+                    "the port of listener `PortListener` is not known".to_string(),
+                ),
+            ),
+            (
+                "Resources/Protocol".to_string(),
+                Reachability::Unknown(
+                    "the protocol of listener `ProtocolListener` is computed".to_string(),
+                ),
+            ),
+            (
+                "Resources/Groups".to_string(),
+                Reachability::Unknown("the security groups are computed".to_string()),
+            ),
+        ];
+
+        assert_eq!(expected, reachability(resources));
+    }
+}
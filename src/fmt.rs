@@ -0,0 +1,248 @@
+use crate::{
+    document::{self, Format},
+    value::{Map, Value},
+    yaml,
+};
+use anyhow::{bail, Result};
+
+const SECTION_ORDER: &[&str] = &[
+    "AWSTemplateFormatVersion",
+    "Description",
+    "Transform",
+    "Metadata",
+    "Parameters",
+    "Rules",
+    "Mappings",
+    "Conditions",
+    "Resources",
+    "Outputs",
+];
+
+const PARAMETER_ORDER: &[&str] = &[
+    "Type",
+    "Description",
+    "Default",
+    "AllowedValues",
+    "AllowedPattern",
+    "MinLength",
+    "MaxLength",
+    "MinValue",
+    "MaxValue",
+    "NoEcho",
+    "ConstraintDescription",
+];
+
+const RESOURCE_ORDER: &[&str] = &[
+    "Type",
+    "Condition",
+    "DependsOn",
+    "Metadata",
+    "Properties",
+    "CreationPolicy",
+    "UpdatePolicy",
+    "UpdateReplacePolicy",
+    "DeletionPolicy",
+];
+
+const OUTPUT_ORDER: &[&str] = &["Description", "Condition", "Value", "Export"];
+
+/// Rewrites a template canonically: sections, parameter, resource and output
+/// attributes in a fixed order, resource properties sorted by name, and the
+/// indentation of the output format.
+///
+/// Formatting works on the document tree rather than `Template`, so
+/// properties the typed model does not know about are kept as they are. The
+/// tree has no place for comments, so a YAML template with comments is
+/// refused rather than rewritten without them.
+pub fn format(source: &str, format: Format) -> Result<String> {
+    let mut document = document::parse(source, format)?;
+    if format == Format::Yaml {
+        if let Some(line) = yaml::find_comment(source)? {
+            bail!(
+                "the template has a comment on line {}, which formatting would remove",
+                line
+            );
+        }
+    }
+    canonicalize(&mut document);
+    Ok(document::to_string(&document, format))
+}
+
+fn canonicalize(document: &mut Value) {
+    let template = match document {
+        Value::Map(template) => template,
+        _ => return,
+    };
+    sort_by_order(template, SECTION_ORDER);
+
+    for_each_entry(template, "Parameters", |parameter| {
+        sort_by_order(parameter, PARAMETER_ORDER)
+    });
+    for_each_entry(template, "Resources", |resource| {
+        sort_by_order(resource, RESOURCE_ORDER);
+        if let Some(Value::Map(properties)) = resource.get_mut("Properties") {
+            properties.sort_keys();
+        }
+    });
+    for_each_entry(template, "Outputs", |output| {
+        sort_by_order(output, OUTPUT_ORDER)
+    });
+}
+
+fn for_each_entry(template: &mut Map, section: &str, mut apply: impl FnMut(&mut Map)) {
+    if let Some(Value::Map(entries)) = template.get_mut(section) {
+        for entry in entries.values_mut() {
+            if let Value::Map(entry) = entry {
+                apply(entry);
+            }
+        }
+    }
+}
+
+/// Sorts known keys into the given order. Unknown keys keep their relative
+/// order after the known ones.
+fn sort_by_order(map: &mut Map, order: &[&str]) {
+    let rank = |key: &str| {
+        order
+            .iter()
+            .position(|known| *known == key)
+            .unwrap_or(order.len())
+    };
+    map.sort_by(|left, _, right, _| rank(left).cmp(&rank(right)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format;
+    use crate::document::Format;
+
+    #[test]
+    fn test_format_json() {
+        let json = r#"{
+  "Resources": {
+    "Ec2Instance": {
+      "Properties": {"SecurityGroups": [{"Ref": "InstanceSecurityGroup"}], "KeyName": "mykey"},
+      "Type": "AWS::EC2::Instance"
+    }
+  },
+  "Description": "Sample",
+  "AWSTemplateFormatVersion": "2010-09-09"
+}"#;
+        let expected = r#"{
+    "AWSTemplateFormatVersion": "2010-09-09",
+    "Description": "Sample",
+    "Resources": {
+        "Ec2Instance": {
+            "Type": "AWS::EC2::Instance",
+            "Properties": {
+                "KeyName": "mykey",
+                "SecurityGroups": [
+                    {
+                        "Ref": "InstanceSecurityGroup"
+                    }
+                ]
+            }
+        }
+    }
+}
+"#;
+
+        let actual = format(json, Format::Json).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_format_yaml() {
+        let yaml = r#"
+Outputs:
+    URL:
+        Value: !Join ['', ['http://', !GetAtt ElasticLoadBalancer.DNSName]]
+        Description: The URL of the website
+Resources:
+    InstanceSecurityGroup:
+        Properties:
+            SecurityGroupIngress:
+                - {IpProtocol: tcp, FromPort: 22, ToPort: 22, CidrIp: !Ref SSHLocation}
+            GroupDescription: "Enable SSH access: port 22"
+        Type: AWS::EC2::SecurityGroup
+        DeletionPolicy: Retain
+Parameters:
+    SSHLocation:
+        Default: 0.0.0.0/0
+        Type: String
+AWSTemplateFormatVersion: "2010-09-09"
+"#;
+        let expected = r#"AWSTemplateFormatVersion: '2010-09-09'
+Parameters:
+  SSHLocation:
+    Type: String
+    Default: 0.0.0.0/0
+Resources:
+  InstanceSecurityGroup:
+    Type: AWS::EC2::SecurityGroup
+    Properties:
+      GroupDescription: 'Enable SSH access: port 22'
+      SecurityGroupIngress:
+        - IpProtocol: tcp
+          FromPort: 22
+          ToPort: 22
+          CidrIp: !Ref SSHLocation
+    DeletionPolicy: Retain
+Outputs:
+  URL:
+    Description: The URL of the website
+    Value: !Join
+      - ''
+      - - http://
+        - !GetAtt ElasticLoadBalancer.DNSName
+"#;
+
+        let actual = format(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_format_is_idempotent() {
+        let yaml = r#"
+Resources:
+  Instance:
+    Type: AWS::EC2::Instance
+    Properties:
+      UserData: !Base64 |
+        #!/bin/bash
+        yum update -y
+      Tags:
+        - Key: Name
+          Value: "yes"
+      ImageId: ami-7a11e213
+"#;
+
+        let formatted = format(yaml, Format::Yaml).unwrap();
+        assert_eq!(formatted, format(&formatted, Format::Yaml).unwrap());
+    }
+
+    #[test]
+    fn test_format_refuses_yaml_with_comments() {
+        let test_cases = [
+            ("# Web tier\nResources: {}\n", 1),
+            (
+                "Resources:\n  # The bucket\n  Bucket:\n    Type: AWS::S3::Bucket\n",
+                2,
+            ),
+            (
+                "Resources:\n  Bucket:\n    Type: AWS::S3::Bucket # logs\n",
+                3,
+            ),
+        ];
+        for (yaml, line) in test_cases {
+            let error = format(yaml, Format::Yaml).unwrap_err();
+            assert_eq!(
+                std::format!(
+                    "the template has a comment on line {}, which formatting would remove",
+                    line
+                ),
+                error.to_string()
+            );
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use crate::{
+    diagnostic::{Diagnostic, Diagnostics, Location},
+    value::{Map, Value},
+};
+use anyhow::Result;
+use serde::de::{DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use std::{cell::Cell, collections::HashMap, fmt, io, rc::Rc};
+
+/// Parses a JSON document with `serde_json`, keeping the order of mapping
+/// keys.
+///
+/// Duplicate mapping keys are reported together as [`Diagnostics`], each
+/// naming the locations of both occurrences.
+pub fn from_str(source: &str) -> Result<Value> {
+    let last_read = Rc::new(Cell::new(Location { line: 1, column: 1 }));
+    let reader = Reader {
+        source: source.as_bytes(),
+        consumed: 0,
+        next: Location { line: 1, column: 1 },
+        last_read: Rc::clone(&last_read),
+    };
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let mut loader = Loader {
+        last_read,
+        path: Vec::new(),
+        duplicates: Vec::new(),
+    };
+
+    let document = Tracked(&mut loader).deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    if !loader.duplicates.is_empty() {
+        return Err(Diagnostics(loader.duplicates).into());
+    }
+    Ok(document)
+}
+
+/// Hands the source to `serde_json` and tracks the location of the last byte
+/// it has read, so keys can be given a location. The location is advanced
+/// with each byte rather than recounted from the start, which would make
+/// loading quadratic in the size of the template.
+struct Reader<'a> {
+    source: &'a [u8],
+    consumed: usize,
+    /// The location of the next byte to read.
+    next: Location,
+    last_read: Rc<Cell<Location>>,
+}
+
+impl io::Read for Reader<'_> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let start = self.consumed;
+        let length = buffer.len().min(self.source.len() - start);
+        buffer[..length].copy_from_slice(&self.source[start..start + length]);
+        for byte in &self.source[start..start + length] {
+            self.last_read.set(self.next);
+            if *byte == b'\n' {
+                self.next = Location {
+                    line: self.next.line + 1,
+                    column: 1,
+                };
+            } else if !is_continuation(*byte) {
+                self.next.column += 1;
+            }
+        }
+        self.consumed = start + length;
+        Ok(length)
+    }
+}
+
+/// Whether a byte continues a multi-byte UTF-8 character, so that columns
+/// count characters rather than bytes.
+fn is_continuation(byte: u8) -> bool {
+    byte & 0xC0 == 0x80
+}
+
+struct Loader {
+    last_read: Rc<Cell<Location>>,
+    path: Vec<String>,
+    duplicates: Vec<Diagnostic>,
+}
+
+impl Loader {
+    /// Returns the location of the last byte `serde_json` has read.
+    fn location(&self) -> Location {
+        self.last_read.get()
+    }
+}
+
+/// Reads any value, tracking the path to it.
+struct Tracked<'l>(&'l mut Loader);
+
+impl<'de> DeserializeSeed<'de> for Tracked<'_> {
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Tracked<'_> {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a template value")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Value, E> {
+        Ok(Value::Bool(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Value, E> {
+        Ok(Value::Number(value))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Value, E> {
+        Ok(i64::try_from(value)
+            .map(Value::Number)
+            .unwrap_or(Value::Float(value as f64)))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Value, E> {
+        Ok(Value::Float(value))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Value, E> {
+        Ok(Value::String(value.to_string()))
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let loader = self.0;
+        let mut items = Vec::new();
+        loop {
+            loader.path.push(items.len().to_string());
+            let item = seq.next_element_seed(Tracked(loader));
+            loader.path.pop();
+            match item? {
+                Some(item) => items.push(item),
+                None => return Ok(Value::List(items)),
+            }
+        }
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Value, A::Error> {
+        let loader = self.0;
+        let mut map = Map::new();
+        let mut locations: HashMap<String, Location> = HashMap::new();
+        while let Some((key, location)) = access.next_key_seed(Key(loader))? {
+            loader.path.push(key.clone());
+            if let Some(first) = locations.get(&key) {
+                let message = format!("duplicate key `{}`, first defined at {}", key, first);
+                loader
+                    .duplicates
+                    .push(Diagnostic::error(loader.path.join("/"), message).at(location));
+            }
+            locations.entry(key.clone()).or_insert(location);
+
+            let value = access.next_value_seed(Tracked(loader));
+            loader.path.pop();
+            map.insert(key, value?);
+        }
+        Ok(Value::from_map(map))
+    }
+}
+
+/// Reads a mapping key with its location. `serde_json` has already read the
+/// opening quote when it asks for the key, so that is where the key starts.
+struct Key<'l>(&'l mut Loader);
+
+impl<'de> DeserializeSeed<'de> for Key<'_> {
+    type Value = (String, Location);
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let location = self.0.location();
+        let key = <String as serde::Deserialize>::deserialize(deserializer)?;
+        Ok((key, location))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::from_str;
+    use crate::{
+        diagnostic::{Diagnostic, Diagnostics, Location},
+        value::{Map, Value},
+    };
+
+    #[test]
+    fn test_from_str_keeps_key_order() {
+        let json = r#"{"Zeta": 1, "Alpha": {"Ref": "Name"}, "Mu": [true, null]}"#;
+        let expected = Value::Map(Map::from([
+            ("Zeta".to_string(), Value::Number(1)),
+            (
+                "Alpha".to_string(),
+                Value::Ref {
+                    r#ref: "Name".to_string(),
+                },
+            ),
+            (
+                "Mu".to_string(),
+                Value::List(vec![Value::Bool(true), Value::Null]),
+            ),
+        ]));
+
+        assert_eq!(expected, from_str(json).unwrap());
+    }
+
+    #[test]
+    fn test_from_str_decodes_surrogate_pairs() {
+        let json = r#"{"Description": "\ud83d\ude00"}"#;
+        let expected = Value::Map(Map::from([(
+            "Description".to_string(),
+            Value::String("😀".to_string()),
+        )]));
+
+        assert_eq!(expected, from_str(json).unwrap());
+    }
+
+    #[test]
+    fn test_from_str_keeps_large_integers_numeric() {
+        let json = "[9223372036854775807, 18446744073709551615]";
+        let expected = Value::List(vec![
+            Value::Number(i64::MAX),
+            Value::Float(18446744073709551615.0),
+        ]);
+
+        assert_eq!(expected, from_str(json).unwrap());
+    }
+
+    #[test]
+    fn test_from_str_reports_duplicate_keys() {
+        let json = "{\n  \"A\": [{\"B\": 1,\n    \"B\": 2}],\n  \"A\": 3\n}";
+        let expected = vec![
+            Diagnostic::error(
+                "A/0/B",
+                "duplicate key `B`, first defined at line 2, column 10",
+            )
+            .at(Location { line: 3, column: 5 }),
+            Diagnostic::error("A", "duplicate key `A`, first defined at line 2, column 3")
+                .at(Location { line: 4, column: 3 }),
+        ];
+
+        let error = from_str(json).unwrap_err();
+        let actual = error.downcast::<Diagnostics>().unwrap();
+        assert_eq!(expected, actual.0);
+    }
+
+    #[test]
+    fn test_from_str_counts_columns_in_characters() {
+        let json = r#"{"é": 1, "A": 1, "A": 2}"#;
+        let expected =
+            vec![
+                Diagnostic::error("A", "duplicate key `A`, first defined at line 1, column 10").at(
+                    Location {
+                        line: 1,
+                        column: 18,
+                    },
+                ),
+            ];
+
+        let error = from_str(json).unwrap_err();
+        let actual = error.downcast::<Diagnostics>().unwrap();
+        assert_eq!(expected, actual.0);
+    }
+
+    #[test]
+    fn test_from_str_large_template() {
+        // About 1.5 MB, past the largest template CloudFormation accepts.
+        let count = 12000;
+        let resource = |index: usize| {
+            format!(
+                "    \"Bucket{}\": {{\n      \"Type\": \"AWS::S3::Bucket\",\n      \"Properties\": {{\"BucketName\": \"bucket-{:0>60}\"}}\n    }}",
+                index % count,
+                index
+            )
+        };
+        let resources: Vec<String> = (0..=count).map(resource).collect();
+        let json = format!(
+            "{{\n  \"Resources\": {{\n{}\n  }}\n}}\n",
+            resources.join(",\n")
+        );
+        assert!(json.len() > 1_000_000);
+        let expected = vec![Diagnostic::error(
+            "Resources/Bucket0",
+            "duplicate key `Bucket0`, first defined at line 3, column 5",
+        )
+        .at(Location {
+            line: 3 + 4 * count,
+            column: 5,
+        })];
+
+        let error = from_str(&json).unwrap_err();
+        let actual = error.downcast::<Diagnostics>().unwrap();
+        assert_eq!(expected, actual.0);
+    }
+
+    #[test]
+    fn test_from_str_rejects_trailing_content() {
+        assert!(from_str(r#"{"A": 1} {"#).is_err());
+    }
+}
@@ -1,27 +1,235 @@
+use anyhow::Result;
+use indexmap::IndexMap;
 use mapping::Mapping;
 use output::Output;
 use parameter::Parameter;
 use resources::Resource;
-use serde::Deserialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use validate::Validate;
+use value::Map;
 
+pub use codegen::generate;
+pub use convert::convert;
+pub use diagnostic::{Diagnostic, Diagnostics, Location, Severity};
+pub use document::Format;
+pub use exposure::{Exposure, Reachability};
+pub use fmt::format;
+pub use lint::{Finding, Policy, Rule};
+pub use spec::Specification;
+pub use workspace::{Stack, Workspace};
+
+mod cidr;
+mod codegen;
+mod convert;
 mod data_type;
+mod diagnostic;
+mod document;
+mod exposure;
+mod fmt;
+mod json;
+mod lint;
 mod mapping;
 mod output;
 mod parameter;
 mod resources;
+mod spec;
 mod tag;
+mod validate;
 mod value;
+mod workspace;
+mod yaml;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "PascalCase")]
 pub struct Template {
-    #[serde(rename = "AWSTemplateFormatVersion")]
+    #[serde(
+        rename = "AWSTemplateFormatVersion",
+        skip_serializing_if = "Option::is_none"
+    )]
     aws_template_format_version: Option<String>,
-    metadata: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<Map>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     mappings: Option<Mapping>,
-    parameters: Option<HashMap<String, Parameter>>,
-    resources: HashMap<String, Resource>,
-    outputs: Option<HashMap<String, Output>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parameters: Option<IndexMap<String, Parameter>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    conditions: Option<Map>,
+    #[serde(deserialize_with = "resources::deserialize_resources")]
+    resources: IndexMap<String, Resource>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    outputs: Option<IndexMap<String, Output>>,
+}
+
+impl Template {
+    /// Parses a JSON or YAML template. Sections and entries keep their
+    /// document order, and duplicate keys anywhere in the source are reported
+    /// as [`Diagnostics`] instead of silently overwriting each other.
+    pub fn parse(source: &str, format: Format) -> Result<Template> {
+        let document = document::parse(source, format)?;
+        Ok(serde_json::from_value(serde_json::to_value(document)?)?)
+    }
+
+    /// Checks the template and returns every problem found, in section order.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for (name, resource) in &self.resources {
+            resource.validate(&format!("Resources/{}", name), self, &mut diagnostics);
+        }
+        resources::validate_security_groups(self, &mut diagnostics);
+        resources::validate_subnets(self, &mut diagnostics);
+        resources::validate_routes(self, &mut diagnostics);
+        resources::validate_listener_rules(self, &mut diagnostics);
+        resources::validate_auto_scaling_groups(self, &mut diagnostics);
+        resources::validate_queue_consumers(self, &mut diagnostics);
+        if let Some(outputs) = &self.outputs {
+            output::validate_outputs(outputs, self, &mut diagnostics);
+        }
+        diagnostics
+    }
+
+    /// Checks the template like [`Template::validate`], then checks the
+    /// properties of every resource against a resource specification.
+    pub fn validate_with(&self, specification: &Specification) -> Vec<Diagnostic> {
+        let mut diagnostics = self.validate();
+        for diagnostic in specification.validate(self) {
+            if !diagnostics.contains(&diagnostic) {
+                diagnostics.push(diagnostic);
+            }
+        }
+        diagnostics
+    }
+
+    /// Reports which instances and load balancers are reachable from the
+    /// internet, and on which ports.
+    pub fn exposure(&self) -> Vec<Exposure> {
+        exposure::exposure(self)
+    }
+
+    /// Runs the built-in security rules and returns what they find.
+    pub fn lint(&self, policy: &Policy) -> Vec<Finding> {
+        lint::lint(self, policy)
+    }
+
+    fn resource(&self, name: &str) -> Option<&Resource> {
+        self.resources.get(name)
+    }
+
+    fn parameter(&self, name: &str) -> Option<&Parameter> {
+        self.parameters.as_ref()?.get(name)
+    }
+
+    fn has_condition(&self, name: &str) -> bool {
+        self.conditions
+            .as_ref()
+            .is_some_and(|conditions| conditions.contains_key(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Template;
+    use crate::{document::Format, Diagnostic, Diagnostics, Location};
+
+    #[test]
+    fn test_serialize_template_round_trip() {
+        let json = include_str!("../template.json");
+        let template: Template = serde_json::from_str(json).unwrap();
+
+        let serialized = serde_json::to_string(&template).unwrap();
+        let actual: Template = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(template, actual);
+    }
+
+    #[test]
+    fn test_parse_keeps_document_order() {
+        let yaml = r#"
+Resources:
+  Zeta:
+    Type: AWS::SNS::Topic
+  Alpha:
+    Type: AWS::SNS::Topic
+  Mu:
+    Type: AWS::SNS::Topic
+"#;
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        let actual: Vec<&String> = template.resources.keys().collect();
+        assert_eq!(vec!["Zeta", "Alpha", "Mu"], actual);
+    }
+
+    #[test]
+    fn test_parse_reports_duplicate_keys() {
+        let yaml = r#"
+Resources:
+  Topic:
+    Type: AWS::SNS::Topic
+  Queue:
+    Type: AWS::SQS::Queue
+  Topic:
+    Type: AWS::SNS::Topic
+Outputs:
+  Arn:
+    Value: !Ref Topic
+    Value: !Ref Queue
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Topic",
+                "duplicate key `Topic`, first defined at line 3, column 3",
+            )
+            .at(Location { line: 7, column: 3 }),
+            Diagnostic::error(
+                "Outputs/Arn/Value",
+                "duplicate key `Value`, first defined at line 11, column 5",
+            )
+            .at(Location {
+                line: 12,
+                column: 5,
+            }),
+        ];
+
+        let error = Template::parse(yaml, Format::Yaml).unwrap_err();
+        let actual = error.downcast::<Diagnostics>().unwrap();
+        assert_eq!(expected, actual.0);
+    }
+
+    #[test]
+    fn test_parse_reports_duplicate_keys_in_json() {
+        let json = r#"{
+    "Resources": {
+        "Topic": {"Type": "AWS::SNS::Topic"},
+        "Topic": {"Type": "AWS::SNS::Topic"}
+    }
+}"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Topic",
+            "duplicate key `Topic`, first defined at line 3, column 9",
+        )
+        .at(Location { line: 4, column: 9 })];
+
+        let error = Template::parse(json, Format::Json).unwrap_err();
+        let actual = error.downcast::<Diagnostics>().unwrap();
+        assert_eq!(expected, actual.0);
+    }
+
+    #[test]
+    fn test_parse_names_the_resource_that_fails() {
+        let yaml = r#"
+Resources:
+  Topic:
+    Type: AWS::SNS::Topic
+  SubnetGroup:
+    Type: AWS::RDS::DBSubnetGroup
+    Properties:
+      DBSubnetGroupDescription: Database subnets
+"#;
+        let error = Template::parse(yaml, Format::Yaml).unwrap_err();
+        assert_eq!(
+            "Resources/SubnetGroup: missing field `SubnetIds`",
+            error.to_string()
+        );
+    }
 }
@@ -0,0 +1,924 @@
+use crate::{
+    resources::{
+        group_rules, policy_documents, strings, Egress, Ingress, PolicyKind, Protocol, Resource,
+    },
+    value::{Node, Value},
+    Template,
+};
+use std::fmt;
+
+/// A security check with a stable ID that findings refer to.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Rule {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Why a finding matters.
+    pub risk: &'static str,
+    /// How to resolve a finding.
+    pub fix: &'static str,
+}
+
+const SSH_OPEN_TO_WORLD: Rule = Rule {
+    id: "SEC001",
+    name: "ssh-open-to-world",
+    risk: "anyone on the internet can try to log in over SSH, which invites brute-force attacks and exploits of the SSH server",
+    fix: "restrict the source to a known address range or a bastion security group, or connect with Session Manager instead",
+};
+
+const RDP_OPEN_TO_WORLD: Rule = Rule {
+    id: "SEC002",
+    name: "rdp-open-to-world",
+    risk: "anyone on the internet can reach Remote Desktop, a frequent target of credential stuffing and remote code execution exploits",
+    fix: "restrict the source to a known address range or a bastion security group, or connect with Fleet Manager instead",
+};
+
+const DATABASE_OPEN_TO_WORLD: Rule = Rule {
+    id: "SEC003",
+    name: "database-open-to-world",
+    risk: "databases exposed to the internet are scanned constantly, and a weak password or unpatched server leaks the data",
+    fix: "allow the port only from the security groups of the applications that use the database",
+};
+
+const ALL_TRAFFIC_INGRESS: Rule = Rule {
+    id: "SEC004",
+    name: "all-traffic-ingress",
+    risk: "the rule exposes every service on the instances in the group, including ones never meant to be reachable",
+    fix: "allow only the protocols and ports the workload needs",
+};
+
+const OPEN_EGRESS: Rule = Rule {
+    id: "SEC005",
+    name: "open-egress",
+    risk: "unrestricted outbound traffic lets a compromised instance exfiltrate data or reach command-and-control servers",
+    fix: "allow only the destinations and ports the workload needs, such as HTTPS to a prefix list or VPC endpoints",
+};
+
+const INLINE_KEY_NAME: Rule = Rule {
+    id: "SEC006",
+    name: "inline-key-name",
+    risk: "a key pair fixed in the template ties every stack to one shared SSH key that cannot differ per environment",
+    fix: "take the key pair from a parameter of type `AWS::EC2::KeyPair::KeyName`, or drop it and connect with Session Manager",
+};
+
+const WILDCARD_PERMISSIONS: Rule = Rule {
+    id: "SEC007",
+    name: "wildcard-permissions",
+    risk: "the statement grants every action of a service, or of all services, on every resource, far beyond what any workload needs",
+    fix: "list the actions the workload calls in `Action` and the ARNs of the resources it uses in `Resource`",
+};
+
+const PASS_ROLE_ON_ANY_RESOURCE: Rule = Rule {
+    id: "SEC008",
+    name: "pass-role-on-any-resource",
+    risk: "a principal that can pass any role can hand a more privileged role to a service it controls and escalate its own access",
+    fix: "limit `iam:PassRole` to the ARNs of the roles the workload passes, and add an `iam:PassedToService` condition",
+};
+
+const TRUST_ANY_PRINCIPAL: Rule = Rule {
+    id: "SEC009",
+    name: "trust-any-principal",
+    risk: "anyone with an AWS account can assume the role and use its permissions",
+    fix: "name the accounts, roles or services that assume the role in `Principal`, or restrict it with a condition such as `aws:PrincipalOrgID`",
+};
+
+const NOT_ACTION_WITH_ALLOW: Rule = Rule {
+    id: "SEC010",
+    name: "not-action-with-allow",
+    risk: "`NotAction` with `Allow` grants every action except the listed ones, including actions of services added later",
+    fix: "list the granted actions in `Action` instead",
+};
+
+const INLINE_USER_POLICY: Rule = Rule {
+    id: "SEC011",
+    name: "inline-user-policy",
+    risk: "permissions attached to individual users are hard to audit and tend to linger after people change jobs",
+    fix: "attach the policy to a group or role and add the user to it",
+};
+
+const BUCKET_WITHOUT_ENCRYPTION: Rule = Rule {
+    id: "SEC012",
+    name: "bucket-without-encryption",
+    risk: "the template neither documents nor enforces how the bucket's data is encrypted at rest, and cannot use a key you control",
+    fix: "add `BucketEncryption` with `aws:kms` and a customer managed key, or `AES256`",
+};
+
+const BUCKET_WITHOUT_PUBLIC_ACCESS_BLOCK: Rule = Rule {
+    id: "SEC013",
+    name: "bucket-without-public-access-block",
+    risk: "a single ACL or bucket policy change can make the bucket's objects readable by anyone on the internet",
+    fix: "add `PublicAccessBlockConfiguration` with all four settings set to `true`",
+};
+
+const PUBLIC_RESOURCE_POLICY: Rule = Rule {
+    id: "SEC014",
+    name: "public-resource-policy",
+    risk: "anyone, including anonymous users, can perform the granted actions on the resource",
+    fix: "name the accounts, roles or services that need access in `Principal`, or restrict it with a condition such as `aws:SourceVpce`",
+};
+
+const MISSING_DELETION_POLICY: Rule = Rule {
+    id: "SEC015",
+    name: "missing-deletion-policy",
+    risk: "deleting the stack, or a change that replaces the resource, destroys its data with no backup",
+    fix: "set `DeletionPolicy` and `UpdateReplacePolicy` to `Snapshot` or `Retain`",
+};
+
+const UNENCRYPTED_DATABASE: Rule = Rule {
+    id: "SEC016",
+    name: "unencrypted-database",
+    risk: "the database storage, its snapshots and its replicas are not encrypted at rest, and encryption cannot be turned on later without a restore",
+    fix: "set `StorageEncrypted: true`, with `KmsKeyId` for a customer managed key",
+};
+
+const LITERAL_MASTER_PASSWORD: Rule = Rule {
+    id: "SEC017",
+    name: "literal-master-password",
+    risk: "a password written in the template, or taken from a parameter without `NoEcho`, is visible to anyone who can read the template or the stack",
+    fix: "set `ManageMasterUserPassword: true`, or use a `NoEcho` parameter or a `{{resolve:secretsmanager:...}}` dynamic reference",
+};
+
+/// Ports that must not be reachable from the whole internet.
+const SENSITIVE_PORTS: &[(i64, &str, &Rule)] = &[
+    (22, "SSH", &SSH_OPEN_TO_WORLD),
+    (3389, "RDP", &RDP_OPEN_TO_WORLD),
+    (1433, "SQL Server", &DATABASE_OPEN_TO_WORLD),
+    (1521, "Oracle", &DATABASE_OPEN_TO_WORLD),
+    (3306, "MySQL", &DATABASE_OPEN_TO_WORLD),
+    (5432, "PostgreSQL", &DATABASE_OPEN_TO_WORLD),
+    (6379, "Redis", &DATABASE_OPEN_TO_WORLD),
+    (27017, "MongoDB", &DATABASE_OPEN_TO_WORLD),
+];
+
+/// Organisation-specific choices that turn optional rules on.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Policy {
+    /// Report egress that allows all traffic to the internet, including the
+    /// implicit rule of groups without egress rules.
+    pub forbid_open_egress: bool,
+}
+
+/// A security problem found by a [`Rule`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub rule: &'static Rule,
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}: {}", self.rule.id, self.path, self.message)
+    }
+}
+
+/// Runs the security rules over a template, in template order.
+pub fn lint(template: &Template, policy: &Policy) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut finding = |rule, path: &str, message| {
+        findings.push(Finding {
+            rule,
+            path: path.to_string(),
+            message,
+        })
+    };
+
+    for (name, rules) in group_rules(template) {
+        for (path, rule) in &rules.ingress {
+            let Some(traffic) = rule.and_then(Ingress::traffic) else {
+                continue;
+            };
+            if traffic.protocol == Protocol::All {
+                finding(
+                    &ALL_TRAFFIC_INGRESS,
+                    path,
+                    "ingress rule allows all protocols and ports".to_string(),
+                );
+                continue;
+            }
+            if !traffic.peer.is_world() {
+                continue;
+            }
+            for (port, service, lint_rule) in SENSITIVE_PORTS {
+                if traffic.allows_tcp_port(*port) {
+                    finding(
+                        lint_rule,
+                        path,
+                        format!("{} port {} is open to the internet", service, port),
+                    );
+                }
+            }
+        }
+
+        if !policy.forbid_open_egress {
+            continue;
+        }
+        if rules.egress.is_empty() {
+            finding(
+                &OPEN_EGRESS,
+                &format!("Resources/{}", name),
+                "security group has no egress rules, so it allows all outbound traffic".to_string(),
+            );
+        }
+        for (path, rule) in &rules.egress {
+            let Some(traffic) = rule.and_then(Egress::traffic) else {
+                continue;
+            };
+            if traffic.peer.is_world() && traffic.allows_all_tcp_ports() {
+                finding(
+                    &OPEN_EGRESS,
+                    path,
+                    "egress rule allows all traffic to the internet".to_string(),
+                );
+            }
+        }
+    }
+
+    for (name, resource) in &template.resources {
+        let path = format!("Resources/{}/Properties", name);
+        match resource {
+            Resource::Ec2(instance) => {
+                if let Some(Value::String(key_name)) = &instance.properties.key_name {
+                    finding(
+                        &INLINE_KEY_NAME,
+                        &format!("{}/KeyName", path),
+                        format!("key pair `{}` is written into the template", key_name),
+                    );
+                }
+            }
+            Resource::User(user) => {
+                let policies = user
+                    .properties
+                    .as_ref()
+                    .and_then(|user| user.policies.as_ref());
+                if policies.is_some_and(|policies| !policies.items().is_empty()) {
+                    finding(
+                        &INLINE_USER_POLICY,
+                        &format!("{}/Policies", path),
+                        "user has inline policies".to_string(),
+                    );
+                }
+            }
+            Resource::Policy(policy) if policy.properties.users.is_some() => finding(
+                &INLINE_USER_POLICY,
+                &format!("{}/Users", path),
+                "inline policy is attached to users".to_string(),
+            ),
+            Resource::DbInstance(instance) => {
+                let properties = &instance.properties;
+                // Cluster members are backed up and encrypted by their cluster.
+                if properties.db_cluster_identifier.is_none() {
+                    check_deletion_policy(&mut finding, name, &instance.deletion_policy, true);
+                    check_encryption(&mut finding, &path, &properties.storage_encrypted);
+                }
+                check_password(
+                    &mut finding,
+                    template,
+                    &path,
+                    &properties.master_user_password,
+                );
+            }
+            Resource::DbCluster(cluster) => {
+                let properties = &cluster.properties;
+                check_deletion_policy(&mut finding, name, &cluster.deletion_policy, true);
+                check_encryption(&mut finding, &path, &properties.storage_encrypted);
+                check_password(
+                    &mut finding,
+                    template,
+                    &path,
+                    &properties.master_user_password,
+                );
+            }
+            Resource::Table(table) => {
+                check_deletion_policy(&mut finding, name, &table.deletion_policy, false)
+            }
+            Resource::Bucket(bucket) => {
+                let bucket = bucket.properties.as_ref();
+                if bucket.is_none_or(|bucket| bucket.bucket_encryption.is_none()) {
+                    finding(
+                        &BUCKET_WITHOUT_ENCRYPTION,
+                        &format!("Resources/{}", name),
+                        "bucket does not configure server-side encryption".to_string(),
+                    );
+                }
+                let block = bucket
+                    .and_then(|bucket| bucket.public_access_block_configuration.as_ref())
+                    .filter(|block| !matches!(block, Node::Computed(value) if value.is_no_value()));
+                match block {
+                    None => finding(
+                        &BUCKET_WITHOUT_PUBLIC_ACCESS_BLOCK,
+                        &format!("Resources/{}", name),
+                        "bucket has no public access block".to_string(),
+                    ),
+                    Some(Node::Computed(_)) => {}
+                    Some(Node::Literal(block)) => {
+                        let missing: Vec<_> = block
+                            .missing()
+                            .iter()
+                            .map(|name| format!("`{}`", name))
+                            .collect();
+                        if !missing.is_empty() {
+                            finding(
+                                &BUCKET_WITHOUT_PUBLIC_ACCESS_BLOCK,
+                                &format!("{}/PublicAccessBlockConfiguration", path),
+                                format!(
+                                    "public access block does not turn on {}",
+                                    missing.join(", ")
+                                ),
+                            );
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for (path, kind, document) in policy_documents(template) {
+        for (path, statement) in document.statements(&path) {
+            if !statement.is_allow() {
+                continue;
+            }
+            if statement.not_action.is_some() {
+                finding(
+                    &NOT_ACTION_WITH_ALLOW,
+                    &format!("{}/NotAction", path),
+                    "statement allows every action except the listed ones".to_string(),
+                );
+            }
+
+            // A condition such as `aws:PrincipalOrgID` narrows `*` down.
+            let public = statement.condition.is_none() && is_any_principal(&statement.principal);
+            match kind {
+                PolicyKind::Trust if public => finding(
+                    &TRUST_ANY_PRINCIPAL,
+                    &format!("{}/Principal", path),
+                    "trust policy lets any principal assume the role".to_string(),
+                ),
+                PolicyKind::Resource if public => finding(
+                    &PUBLIC_RESOURCE_POLICY,
+                    &format!("{}/Principal", path),
+                    "resource policy grants access to any principal".to_string(),
+                ),
+                _ => {}
+            }
+            if kind == PolicyKind::Trust {
+                continue;
+            }
+
+            if !strings(&statement.resource).contains(&"*") {
+                continue;
+            }
+            let actions = strings(&statement.action);
+            if let Some(action) = actions.iter().find(|action| is_wildcard_action(action)) {
+                finding(
+                    &WILDCARD_PERMISSIONS,
+                    &path,
+                    format!("statement allows `{}` on every resource", action),
+                );
+            } else if let Some(action) = actions
+                .iter()
+                .find(|action| action_matches(action, "iam:PassRole"))
+            {
+                finding(
+                    &PASS_ROLE_ON_ANY_RESOURCE,
+                    &path,
+                    format!("statement allows `{}` on every role", action),
+                );
+            }
+        }
+    }
+    findings
+}
+
+type Report<'a> = dyn FnMut(&'static Rule, &str, String) + 'a;
+
+/// Reports a stateful resource whose data is deleted with it. Only some
+/// resource types, like databases, can take a final snapshot.
+fn check_deletion_policy(
+    finding: &mut Report,
+    name: &str,
+    deletion_policy: &Option<Value>,
+    snapshots: bool,
+) {
+    let kept = match deletion_policy {
+        Some(policy) => match policy.as_str() {
+            Some("Retain" | "RetainExceptOnCreate") => true,
+            Some("Snapshot") => snapshots,
+            Some(_) => false,
+            None => true,
+        },
+        None => false,
+    };
+    if !kept {
+        let expected = if snapshots {
+            "`Snapshot` or `Retain`"
+        } else {
+            "`Retain`"
+        };
+        finding(
+            &MISSING_DELETION_POLICY,
+            &format!("Resources/{}", name),
+            format!("stateful resource has no `DeletionPolicy` of {}", expected),
+        );
+    }
+}
+
+fn check_encryption(finding: &mut Report, path: &str, storage_encrypted: &Option<Value>) {
+    let encrypted = match storage_encrypted {
+        Some(value) => value.as_intrinsic().is_some() || value.as_bool() == Some(true),
+        None => false,
+    };
+    if !encrypted {
+        finding(
+            &UNENCRYPTED_DATABASE,
+            path,
+            "database storage is not encrypted".to_string(),
+        );
+    }
+}
+
+/// Reports a master password that is a literal, or a parameter without
+/// `NoEcho`. Dynamic references are resolved at deploy time and are fine.
+fn check_password(finding: &mut Report, template: &Template, path: &str, password: &Option<Value>) {
+    let path = format!("{}/MasterUserPassword", path);
+    match password {
+        Some(Value::String(password)) if !password.starts_with("{{resolve:") => finding(
+            &LITERAL_MASTER_PASSWORD,
+            &path,
+            "master password is written into the template".to_string(),
+        ),
+        Some(Value::Ref { r#ref }) => {
+            if let Some(parameter) = template.parameter(r#ref) {
+                if !parameter.is_no_echo() {
+                    finding(
+                        &LITERAL_MASTER_PASSWORD,
+                        &path,
+                        format!(
+                            "master password comes from parameter `{}` without `NoEcho`",
+                            r#ref
+                        ),
+                    );
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Whether an action is `*` or covers a whole service, like `s3:*`.
+fn is_wildcard_action(action: &str) -> bool {
+    action == "*" || action.ends_with(":*")
+}
+
+/// Matches an action against a pattern with `*` and `?` wildcards, ignoring
+/// case as IAM does.
+fn action_matches(pattern: &str, action: &str) -> bool {
+    fn matches(pattern: &[u8], text: &[u8]) -> bool {
+        match (pattern.split_first(), text.split_first()) {
+            (None, _) => text.is_empty(),
+            (Some((b'*', rest)), _) => {
+                matches(rest, text) || (!text.is_empty() && matches(pattern, &text[1..]))
+            }
+            (Some((b'?', rest)), Some((_, text))) => matches(rest, text),
+            (Some((p, rest)), Some((t, text))) => p.eq_ignore_ascii_case(t) && matches(rest, text),
+            (Some(_), None) => false,
+        }
+    }
+    matches(pattern.as_bytes(), action.as_bytes())
+}
+
+/// Whether a principal is `*`, on its own or as the `AWS` principal.
+fn is_any_principal(principal: &Option<Value>) -> bool {
+    match principal {
+        Some(Value::Map(types)) => strings(&types.get("AWS").cloned()).contains(&"*"),
+        principal => strings(principal).contains(&"*"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lint, Policy};
+    use crate::{document::Format, Template};
+
+    fn ids(yaml: &str, policy: &Policy) -> Vec<(&'static str, String)> {
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        lint(&template, policy)
+            .into_iter()
+            .map(|finding| (finding.rule.id, finding.path))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_sample_template() {
+        let template = Template::parse(include_str!("../template.json"), Format::Json).unwrap();
+        let findings: Vec<String> = lint(&template, &Policy::default())
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            vec![
+                "SEC001 Resources/InstanceSecurityGroup/Properties/SecurityGroupIngress/0: SSH port 22 is open to the internet",
+                "SEC006 Resources/Ec2Instance/Properties/KeyName: key pair `mykey` is written into the template",
+            ],
+            findings
+        );
+    }
+
+    #[test]
+    fn test_lint_security_groups() {
+        let yaml = r#"
+Resources:
+  Group:
+    Type: AWS::EC2::SecurityGroup
+    Properties:
+      GroupDescription: Rules
+      SecurityGroupIngress:
+        - IpProtocol: tcp
+          FromPort: 3000
+          ToPort: 3400
+          CidrIpv6: ::/0
+        - IpProtocol: tcp
+          FromPort: 22
+          ToPort: 22
+          CidrIp: 10.0.0.0/8
+        - IpProtocol: "-1"
+          SourceSecurityGroupId: sg-12345678
+      SecurityGroupEgress:
+        - IpProtocol: "-1"
+          CidrIp: 0.0.0.0/0
+        - IpProtocol: tcp
+          FromPort: 443
+          ToPort: 443
+          CidrIp: 0.0.0.0/0
+  Quiet:
+    Type: AWS::EC2::SecurityGroup
+    Properties:
+      GroupDescription: No egress rules
+  Instance:
+    Type: AWS::EC2::Instance
+    Properties:
+      ImageId: ami-12345678
+      KeyName: !Ref KeyName
+"#;
+        let ingress = "Resources/Group/Properties/SecurityGroupIngress";
+        let egress = "Resources/Group/Properties/SecurityGroupEgress";
+        assert_eq!(
+            vec![
+                ("SEC002", format!("{}/0", ingress)),
+                ("SEC003", format!("{}/0", ingress)),
+                ("SEC004", format!("{}/2", ingress)),
+            ],
+            ids(yaml, &Policy::default())
+        );
+        assert_eq!(
+            vec![
+                ("SEC002", format!("{}/0", ingress)),
+                ("SEC003", format!("{}/0", ingress)),
+                ("SEC004", format!("{}/2", ingress)),
+                ("SEC005", format!("{}/0", egress)),
+                ("SEC005", "Resources/Quiet".to_string()),
+            ],
+            ids(
+                yaml,
+                &Policy {
+                    forbid_open_egress: true
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_lint_iam() {
+        let yaml = r#"
+Resources:
+  Role:
+    Type: AWS::IAM::Role
+    Properties:
+      AssumeRolePolicyDocument:
+        Version: "2012-10-17"
+        Statement:
+          - Effect: Allow
+            Principal: {Service: ec2.amazonaws.com}
+            Action: sts:AssumeRole
+          - Effect: Allow
+            Principal: {AWS: "*"}
+            Action: sts:AssumeRole
+          - Effect: Allow
+            Principal: "*"
+            Action: sts:AssumeRole
+            Condition:
+              StringEquals: {aws:PrincipalOrgID: o-123}
+      Policies:
+        - PolicyName: admin
+          PolicyDocument:
+            Version: "2012-10-17"
+            Statement:
+              - Effect: Allow
+                Action: s3:GetObject
+                Resource: "*"
+              - Effect: Allow
+                Action: [logs:PutLogEvents, ec2:*]
+                Resource: "*"
+              - Effect: Deny
+                Action: "*"
+                Resource: "*"
+              - Effect: Allow
+                NotAction: iam:*
+                Resource: "*"
+  Deployer:
+    Type: AWS::IAM::ManagedPolicy
+    Properties:
+      PolicyDocument:
+        Version: "2012-10-17"
+        Statement:
+          - Effect: Allow
+            Action: iam:passrole
+            Resource: !Sub arn:${AWS::Partition}:iam::${AWS::AccountId}:role/app
+          - Effect: Allow
+            Action: [iam:Pass*]
+            Resource: ["*"]
+  Person:
+    Type: AWS::IAM::User
+    Properties:
+      Policies:
+        - PolicyName: own
+          PolicyDocument:
+            Version: "2012-10-17"
+            Statement: []
+  UserPolicy:
+    Type: AWS::IAM::Policy
+    Properties:
+      PolicyName: attached
+      Users: [!Ref Person]
+      PolicyDocument:
+        Version: "2012-10-17"
+        Statement: []
+"#;
+        let role = "Resources/Role/Properties";
+        assert_eq!(
+            vec![
+                ("SEC011", "Resources/Person/Properties/Policies".to_string()),
+                (
+                    "SEC011",
+                    "Resources/UserPolicy/Properties/Users".to_string()
+                ),
+                (
+                    "SEC009",
+                    format!("{}/AssumeRolePolicyDocument/Statement/1/Principal", role)
+                ),
+                (
+                    "SEC007",
+                    format!("{}/Policies/0/PolicyDocument/Statement/1", role)
+                ),
+                (
+                    "SEC010",
+                    format!("{}/Policies/0/PolicyDocument/Statement/3/NotAction", role)
+                ),
+                (
+                    "SEC008",
+                    "Resources/Deployer/Properties/PolicyDocument/Statement/1".to_string()
+                ),
+            ],
+            ids(yaml, &Policy::default())
+        );
+    }
+
+    #[test]
+    fn test_lint_buckets() {
+        let yaml = r#"
+Resources:
+  Bucket:
+    Type: AWS::S3::Bucket
+    Properties:
+      BucketEncryption:
+        ServerSideEncryptionConfiguration:
+          - ServerSideEncryptionByDefault:
+              SSEAlgorithm: aws:kms
+      PublicAccessBlockConfiguration:
+        BlockPublicAcls: true
+        BlockPublicPolicy: true
+        IgnorePublicAcls: true
+        RestrictPublicBuckets: false
+  Plain:
+    Type: AWS::S3::Bucket
+  Policy:
+    Type: AWS::S3::BucketPolicy
+    Properties:
+      Bucket: !Ref Bucket
+      PolicyDocument:
+        Version: "2012-10-17"
+        Statement:
+          - Effect: Allow
+            Principal: "*"
+            Action: s3:GetObject
+            Resource: !Sub ${Bucket.Arn}/*
+          - Effect: Allow
+            Principal: "*"
+            Action: s3:GetObject
+            Resource: !Sub ${Bucket.Arn}/*
+            Condition:
+              StringEquals: {aws:SourceVpce: vpce-123}
+"#;
+        assert_eq!(
+            vec![
+                (
+                    "SEC013",
+                    "Resources/Bucket/Properties/PublicAccessBlockConfiguration".to_string()
+                ),
+                ("SEC012", "Resources/Plain".to_string()),
+                ("SEC013", "Resources/Plain".to_string()),
+                (
+                    "SEC014",
+                    "Resources/Policy/Properties/PolicyDocument/Statement/0/Principal".to_string()
+                ),
+            ],
+            ids(yaml, &Policy::default())
+        );
+    }
+
+    #[test]
+    fn test_lint_topic_and_queue_policies() {
+        let yaml = r#"
+Resources:
+  Topic:
+    Type: AWS::SNS::Topic
+  Queue:
+    Type: AWS::SQS::Queue
+  TopicPolicy:
+    Type: AWS::SNS::TopicPolicy
+    Properties:
+      Topics: [!Ref Topic]
+      PolicyDocument:
+        Version: "2012-10-17"
+        Statement:
+          - Effect: Allow
+            Principal: {AWS: "*"}
+            Action: sns:Publish
+            Resource: !Ref Topic
+  QueuePolicy:
+    Type: AWS::SQS::QueuePolicy
+    Properties:
+      Queues: [!Ref Queue]
+      PolicyDocument:
+        Version: "2012-10-17"
+        Statement:
+          - Effect: Allow
+            Principal: "*"
+            Action: sqs:SendMessage
+            Resource: !GetAtt Queue.Arn
+            Condition:
+              ArnEquals: {aws:SourceArn: !Ref Topic}
+          - Effect: Allow
+            Principal: "*"
+            Action: sqs:ReceiveMessage
+            Resource: !GetAtt Queue.Arn
+"#;
+        assert_eq!(
+            vec![
+                (
+                    "SEC014",
+                    "Resources/TopicPolicy/Properties/PolicyDocument/Statement/0/Principal"
+                        .to_string()
+                ),
+                (
+                    "SEC014",
+                    "Resources/QueuePolicy/Properties/PolicyDocument/Statement/1/Principal"
+                        .to_string()
+                ),
+            ],
+            ids(yaml, &Policy::default())
+        );
+    }
+
+    #[test]
+    fn test_lint_databases() {
+        let yaml = r#"
+Parameters:
+  Password:
+    Type: String
+  Secret:
+    Type: String
+    NoEcho: true
+Resources:
+  Database:
+    Type: AWS::RDS::DBInstance
+    DeletionPolicy: Snapshot
+    Properties:
+      Engine: postgres
+      AllocatedStorage: 20
+      StorageEncrypted: true
+      MasterUserPassword: "{{resolve:secretsmanager:db:SecretString:password}}"
+  Plain:
+    Type: AWS::RDS::DBInstance
+    Properties:
+      Engine: mysql
+      AllocatedStorage: 20
+      MasterUserPassword: hunter22
+  Cluster:
+    Type: AWS::RDS::DBCluster
+    DeletionPolicy: Delete
+    Properties:
+      Engine: aurora-mysql
+      StorageEncrypted: true
+      MasterUserPassword: !Ref Password
+  Member:
+    Type: AWS::RDS::DBInstance
+    Properties:
+      Engine: aurora-mysql
+      DBClusterIdentifier: !Ref Cluster
+      MasterUserPassword: !Ref Secret
+  Table:
+    Type: AWS::DynamoDB::Table
+    DeletionPolicy: Snapshot
+    Properties:
+      BillingMode: PAY_PER_REQUEST
+      AttributeDefinitions:
+        - {AttributeName: id, AttributeType: S}
+      KeySchema:
+        - {AttributeName: id, KeyType: HASH}
+"#;
+        assert_eq!(
+            vec![
+                ("SEC015", "Resources/Plain".to_string()),
+                ("SEC016", "Resources/Plain/Properties".to_string()),
+                (
+                    "SEC017",
+                    "Resources/Plain/Properties/MasterUserPassword".to_string()
+                ),
+                ("SEC015", "Resources/Cluster".to_string()),
+                (
+                    "SEC017",
+                    "Resources/Cluster/Properties/MasterUserPassword".to_string()
+                ),
+                ("SEC015", "Resources/Table".to_string()),
+            ],
+            ids(yaml, &Policy::default())
+        );
+    }
+
+    #[test]
+    fn test_lint_public_access_block_without_value() {
+        let yaml = r#"
+Resources:
+  Removed:
+    Type: AWS::S3::Bucket
+    Properties:
+      BucketEncryption:
+        ServerSideEncryptionConfiguration:
+          - ServerSideEncryptionByDefault:
+              SSEAlgorithm: AES256
+      PublicAccessBlockConfiguration: !Ref AWS::NoValue
+  Setting:
+    Type: AWS::S3::Bucket
+    Properties:
+      BucketEncryption:
+        ServerSideEncryptionConfiguration:
+          - ServerSideEncryptionByDefault:
+              SSEAlgorithm: AES256
+      PublicAccessBlockConfiguration:
+        BlockPublicAcls: true
+        BlockPublicPolicy: !Ref AWS::NoValue
+        IgnorePublicAcls: true
+        RestrictPublicBuckets: !Ref Restrict
+  Conditional:
+    Type: AWS::S3::Bucket
+    Properties:
+      BucketEncryption:
+        ServerSideEncryptionConfiguration:
+          - ServerSideEncryptionByDefault:
+              SSEAlgorithm: AES256
+      PublicAccessBlockConfiguration: !If
+        - IsPublic
+        - !Ref AWS::NoValue
+        - BlockPublicAcls: true
+          BlockPublicPolicy: true
+          IgnorePublicAcls: true
+          RestrictPublicBuckets: true
+Parameters:
+  Restrict:
+    Type: String
+Conditions:
+  IsPublic: !Equals [!Ref Restrict, "false"]
+"#;
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        let findings: Vec<_> = lint(&template, &Policy::default())
+            .into_iter()
+            .map(|finding| (finding.rule.id, finding.path, finding.message))
+            .collect();
+        assert_eq!(
+            vec![
+                (
+                    "SEC013",
+                    "Resources/Removed".to_string(),
+                    "bucket has no public access block".to_string()
+                ),
+                (
+                    "SEC013",
+                    "Resources/Setting/Properties/PublicAccessBlockConfiguration".to_string(),
+                    "public access block does not turn on `BlockPublicPolicy`".to_string()
+                ),
+            ],
+            findings
+        );
+    }
+}
@@ -1,11 +1,218 @@
-use anyhow::Result;
-use cfn_validator::{self, Template};
-use std::fs;
+use anyhow::{bail, Result};
+use cfn_validator::{
+    self, Diagnostic, Format, Policy, Severity, Specification, Template, Workspace,
+};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+const USAGE: &str = "usage: cfn-validator [validate [--spec <path>]... [<file>] | \
+lint [--forbid-open-egress] [<file>] | exposure [<file>] | fmt [--check] <file>... | \
+convert <input> [<output>] | workspace <path>... [--baseline <path>...]]";
 
 fn main() -> Result<()> {
-    let code = fs::read_to_string("./template.json")?;
-    let result: Template = serde_json::from_str(&code)?;
-    println!("{:?}", result);
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("fmt") => fmt(&args[1..]),
+        Some("lint") => lint(&args[1..]),
+        Some("exposure") => exposure(&args[1..]),
+        Some("convert") => convert(&args[1..]),
+        Some("validate") => validate(&args[1..]),
+        Some("workspace") => workspace(&args[1..]),
+        Some(_) => bail!(USAGE),
+        None => validate(&[]),
+    }
+}
+
+/// Prints the problems found in a template and exits with a failure status
+/// if any of them is an error. Each `--spec` adds a resource specification
+/// or provider schema, as a file or a directory, to check resources against.
+fn validate(mut args: &[String]) -> Result<()> {
+    let mut specification: Option<Specification> = None;
+    while let [flag, path, rest @ ..] = args {
+        if flag != "--spec" {
+            break;
+        }
+        specification
+            .get_or_insert_with(Specification::default)
+            .add_path(Path::new(path))?;
+        args = rest;
+    }
+    let path = match args {
+        [] => "./template.json",
+        [path] => path,
+        _ => bail!(USAGE),
+    };
+
+    let source = fs::read_to_string(path)?;
+    let template = Template::parse(&source, Format::detect(Path::new(path), &source))?;
+    let diagnostics = match &specification {
+        Some(specification) => template.validate_with(specification),
+        None => template.validate(),
+    };
+    for diagnostic in &diagnostics {
+        println!("{}: {}", path, diagnostic);
+    }
+
+    if diagnostics.iter().any(is_error) {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+/// Prints the security findings for a template with their risk and fix, and
+/// exits with a failure status if there are any.
+fn lint(args: &[String]) -> Result<()> {
+    let policy = Policy {
+        forbid_open_egress: args.iter().any(|arg| arg == "--forbid-open-egress"),
+    };
+    let paths: Vec<&String> = args
+        .iter()
+        .filter(|arg| *arg != "--forbid-open-egress")
+        .collect();
+    let path = match paths[..] {
+        [] => "./template.json",
+        [path] => path,
+        _ => bail!(USAGE),
+    };
+
+    let source = fs::read_to_string(path)?;
+    let template = Template::parse(&source, Format::detect(Path::new(path), &source))?;
+    let findings = template.lint(&policy);
+    for finding in &findings {
+        println!("{}: {}", path, finding);
+        println!("  risk: {}", finding.rule.risk);
+        println!("  fix: {}", finding.rule.fix);
+    }
+
+    if !findings.is_empty() {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+/// Prints which instances and load balancers are reachable from the
+/// internet, and on which ports.
+fn exposure(args: &[String]) -> Result<()> {
+    let path = match args {
+        [] => "./template.json",
+        [path] => path,
+        _ => bail!(USAGE),
+    };
+
+    let source = fs::read_to_string(path)?;
+    let template = Template::parse(&source, Format::detect(Path::new(path), &source))?;
+    for exposure in template.exposure() {
+        println!("{}", exposure);
+    }
+    Ok(())
+}
 
+/// Formats templates in place, or with `--check` lists the ones that are not
+/// formatted and exits with a failure status.
+fn fmt(args: &[String]) -> Result<()> {
+    let check = args.iter().any(|arg| arg == "--check");
+    let paths: Vec<&String> = args.iter().filter(|arg| *arg != "--check").collect();
+    if paths.is_empty() {
+        bail!(USAGE);
+    }
+
+    let mut unformatted = false;
+    for path in paths {
+        let source = fs::read_to_string(path)?;
+        let format = Format::detect(Path::new(path), &source);
+        let formatted = cfn_validator::format(&source, format)
+            .map_err(|error| error.context(format!("failed to format {}", path)))?;
+
+        if formatted == source {
+            continue;
+        }
+        if check {
+            println!("{} is not formatted", path);
+            unformatted = true;
+        } else {
+            fs::write(path, formatted)?;
+        }
+    }
+
+    if unformatted {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+/// Converts a template to the other format. The output format follows the
+/// extension of `<output>`; without one the result is printed.
+fn convert(args: &[String]) -> Result<()> {
+    let (input, output) = match args {
+        [input] => (input, None),
+        [input, output] => (input, Some(output)),
+        _ => bail!(USAGE),
+    };
+
+    let source = fs::read_to_string(input)?;
+    let from = Format::detect(Path::new(input), &source);
+    let to = match output {
+        Some(output) => Format::detect(Path::new(output), ""),
+        None if from == Format::Json => Format::Yaml,
+        None => Format::Json,
+    };
+    let converted = cfn_validator::convert(&source, from, to)
+        .map_err(|error| error.context(format!("failed to convert {}", input)))?;
+
+    match output {
+        Some(output) => fs::write(output, converted)?,
+        None => print!("{}", converted),
+    }
     Ok(())
 }
+
+/// Checks the exports and imports between stacks and prints the deployment
+/// order. With `--baseline`, also reports exports that the change deletes or
+/// renames while they are still imported.
+fn workspace(args: &[String]) -> Result<()> {
+    let (paths, baseline) = match args.iter().position(|arg| arg == "--baseline") {
+        Some(index) => (&args[..index], Some(&args[index + 1..])),
+        None => (args, None),
+    };
+    if paths.is_empty() || baseline.is_some_and(|baseline| baseline.is_empty()) {
+        bail!(USAGE);
+    }
+
+    let to_paths = |args: &[String]| args.iter().map(PathBuf::from).collect::<Vec<_>>();
+    let workspace = Workspace::load(&to_paths(paths))?;
+    let mut diagnostics = workspace.validate();
+    if let Some(baseline) = baseline {
+        let baseline = Workspace::load(&to_paths(baseline))?;
+        diagnostics.extend(workspace.breaking_changes(&baseline));
+    }
+
+    let mut failed = false;
+    match workspace.deployment_order() {
+        Ok(order) => println!("deployment order: {}", order.join(", ")),
+        Err(cycles) => {
+            for cycle in cycles {
+                println!("error: imports form a cycle between {}", cycle.join(", "));
+            }
+            failed = true;
+        }
+    }
+    for (stack, diagnostic) in &diagnostics {
+        println!("{}: {}", stack, diagnostic);
+    }
+
+    if failed
+        || diagnostics
+            .iter()
+            .any(|(_, diagnostic)| is_error(diagnostic))
+    {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+fn is_error(diagnostic: &Diagnostic) -> bool {
+    diagnostic.severity == Severity::Error
+}
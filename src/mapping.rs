@@ -1,13 +1,13 @@
-use serde::Deserialize;
-use std::collections::HashMap;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
 pub struct Mapping {
     #[serde(flatten)]
-    entries: HashMap<String, MappingEntry>,
+    entries: IndexMap<String, MappingEntry>,
 }
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum MappingEntry {
     String(String),
@@ -19,7 +19,7 @@ pub enum MappingEntry {
 mod test {
     use super::Mapping;
     use crate::mapping::MappingEntry;
-    use std::collections::HashMap;
+    use indexmap::IndexMap;
 
     #[test]
     fn test_deserialize_mappings() {
@@ -41,7 +41,7 @@ NameMap:
         "#;
 
         let expected = Mapping {
-            entries: HashMap::from([
+            entries: IndexMap::from([
                 ("Name".to_string(), MappingEntry::String("Test".to_string())),
                 (
                     "NameList".to_string(),
@@ -50,7 +50,7 @@ NameMap:
                 (
                     "NameMap".to_string(),
                     MappingEntry::Mapping(Mapping {
-                        entries: HashMap::from([
+                        entries: IndexMap::from([
                             (
                                 "first".to_string(),
                                 MappingEntry::String("First".to_string()),
@@ -66,7 +66,7 @@ NameMap:
                             (
                                 "third".to_string(),
                                 MappingEntry::Mapping(Mapping {
-                                    entries: HashMap::from([
+                                    entries: IndexMap::from([
                                         ("A".to_string(), MappingEntry::String("B".to_string())),
                                         ("B".to_string(), MappingEntry::String("C".to_string())),
                                         ("C".to_string(), MappingEntry::String("A".to_string())),
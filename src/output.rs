@@ -1,23 +1,113 @@
-use crate::value::Value;
-use serde::Deserialize;
+use crate::{
+    diagnostic::Diagnostic,
+    validate::{check_references, is_string, Validate},
+    value::Value,
+    Template,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
+/// The number of outputs CloudFormation accepts in one template.
+const MAX_OUTPUTS: usize = 200;
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "PascalCase")]
 pub struct Output {
+    #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    condition: Option<String>,
     value: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
     export: Option<Export>,
 }
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "PascalCase")]
 struct Export {
     name: Value,
 }
 
+impl Output {
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    pub fn export_name(&self) -> Option<&Value> {
+        self.export.as_ref().map(|export| &export.name)
+    }
+}
+
+impl Validate for Output {
+    fn validate(&self, path: &str, template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        if let Some(condition) = &self.condition {
+            if !template.has_condition(condition) {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/Condition", path),
+                    format!("condition `{}` is not defined in Conditions", condition),
+                ));
+            }
+        }
+
+        check_references(
+            &self.value,
+            &format!("{}/Value", path),
+            template,
+            diagnostics,
+        );
+
+        if let Some(export) = &self.export {
+            let path = format!("{}/Export/Name", path);
+            if !is_string(&export.name) {
+                diagnostics.push(Diagnostic::error(
+                    &path,
+                    "export name must resolve to a string",
+                ));
+            }
+            check_references(&export.name, &path, template, diagnostics);
+        }
+    }
+}
+
+/// Checks every output, then the rules that span the whole section: the
+/// output limit and unique export names.
+pub fn validate_outputs(
+    outputs: &IndexMap<String, Output>,
+    template: &Template,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if outputs.len() > MAX_OUTPUTS {
+        diagnostics.push(Diagnostic::error(
+            "Outputs",
+            format!(
+                "template has {} outputs, more than the limit of {}",
+                outputs.len(),
+                MAX_OUTPUTS
+            ),
+        ));
+    }
+
+    // Names built by the same expression resolve to the same string.
+    let mut exports: Vec<(&Value, &str)> = Vec::new();
+    for (name, output) in outputs {
+        let path = format!("Outputs/{}", name);
+        output.validate(&path, template, diagnostics);
+
+        if let Some(export) = &output.export {
+            match exports.iter().find(|(other, _)| **other == export.name) {
+                Some((_, first)) => diagnostics.push(Diagnostic::error(
+                    format!("{}/Export/Name", path),
+                    format!("export name is already used by output `{}`", first),
+                )),
+                None => exports.push((&export.name, name)),
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::value::Value;
+    use crate::{diagnostic::Diagnostic, document::Format, value::Value, Template};
 
     use super::{Export, Output};
 
@@ -32,6 +122,7 @@ Value:
             "#,
                 Output {
                     description: Some("The URL of the website".to_string()),
+                    condition: None,
                     value: Value::Join {
                         join: (
                             "".to_string(),
@@ -60,12 +151,14 @@ Export:
             "#,
                 Output {
                     description: Some("The ID of the VPC".to_string()),
+                    condition: None,
                     value: Value::Ref {
                         r#ref: "MyVPC".to_string(),
                     },
                     export: Some(Export {
                         name: Value::Sub {
                             sub: "${AWS::StackName}-VPCID".to_string(),
+                            variables: None,
                         },
                     }),
                 },
@@ -78,4 +171,73 @@ Export:
             assert_eq!(expected, actual);
         }
     }
+
+    #[test]
+    fn test_validate_outputs() {
+        let yaml = r#"
+Parameters:
+  Environment:
+    Type: String
+Conditions:
+  IsProduction: !Equals [!Ref Environment, production]
+Resources:
+  Topic:
+    Type: AWS::SNS::Topic
+Outputs:
+  TopicArn:
+    Condition: IsProduction
+    Value: !Ref Topic
+    Export:
+      Name: !Sub ${AWS::StackName}-TopicArn
+  QueueUrl:
+    Condition: IsStaging
+    Value: !GetAtt Queue.QueueUrl
+    Export:
+      Name: !Sub ${AWS::StackName}-TopicArn
+  Region:
+    Value: !Sub ${AWS::Region}-${Missing}
+    Export:
+      Name: [not, a, string]
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Outputs/QueueUrl/Condition",
+                "condition `IsStaging` is not defined in Conditions",
+            ),
+            Diagnostic::error(
+                "Outputs/QueueUrl/Value",
+                "references resource `Queue` that does not exist",
+            ),
+            Diagnostic::error(
+                "Outputs/QueueUrl/Export/Name",
+                "export name is already used by output `TopicArn`",
+            ),
+            Diagnostic::error(
+                "Outputs/Region/Value",
+                "references resource or parameter `Missing` that does not exist",
+            ),
+            Diagnostic::error(
+                "Outputs/Region/Export/Name",
+                "export name must resolve to a string",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_output_limit() {
+        let outputs: String = (0..201)
+            .map(|index| format!("  Output{}:\n    Value: value\n", index))
+            .collect();
+        let yaml = format!("Resources: {{}}\nOutputs:\n{}", outputs);
+        let expected = vec![Diagnostic::error(
+            "Outputs",
+            "template has 201 outputs, more than the limit of 200",
+        )];
+
+        let template = Template::parse(&yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
 }
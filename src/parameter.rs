@@ -1,23 +1,44 @@
 use crate::{data_type::DataType, value::Value};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "PascalCase")]
 pub struct Parameter {
     #[serde(rename = "Type")]
     data_type: DataType,
+    #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     max_length: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     min_length: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     max_value: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     min_value: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     default: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     allowed_pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     allowed_values: Option<Vec<Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     constraint_description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     no_echo: Option<bool>,
 }
 
+impl Parameter {
+    pub fn default(&self) -> Option<&Value> {
+        self.default.as_ref()
+    }
+
+    /// Whether the value is masked in the console and API output.
+    pub fn is_no_echo(&self) -> bool {
+        self.no_echo == Some(true)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Parameter;
@@ -0,0 +1,841 @@
+use super::{
+    ec2::{BlockDeviceMapping, LaunchTemplateSpecification},
+    referenced_resource, Resource, ResourceContainer,
+};
+use crate::{
+    diagnostic::Diagnostic,
+    validate::{check_boolean, check_one_of, check_range, Validate},
+    value::{Node, Value},
+    Template,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+const HEALTH_CHECK_TYPES: &[&str] = &["EBS", "ELB", "EC2", "VPC_LATTICE"];
+
+const PLACEMENT_TENANCIES: &[&str] = &["dedicated", "default"];
+
+const PERCENTAGES: RangeInclusive<i64> = 0..=100;
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct AutoScalingGroup {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_scaling_group_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub availability_zones: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capacity_rebalance: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cooldown: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desired_capacity: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health_check_grace_period: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health_check_type: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub launch_configuration_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub launch_template: Option<Node<LaunchTemplateSpecification>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub load_balancer_names: Option<Node<Vec<Value>>>,
+    pub max_size: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metrics_collection: Option<Node<Vec<Value>>>,
+    pub min_size: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mixed_instances_policy: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notification_configurations: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Value>>>,
+    #[serde(rename = "TargetGroupARNs", skip_serializing_if = "Option::is_none")]
+    pub target_group_arns: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub termination_policies: Option<Node<Vec<Value>>>,
+    #[serde(rename = "VPCZoneIdentifier", skip_serializing_if = "Option::is_none")]
+    pub vpc_zone_identifier: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct LaunchConfiguration {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub associate_public_ip_address: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_device_mappings: Option<Node<Vec<Node<BlockDeviceMapping>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ebs_optimized: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iam_instance_profile: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_monitoring: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_type: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub launch_configuration_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata_options: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placement_tenancy: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security_groups: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spot_price: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_data: Option<Value>,
+}
+
+/// The `CreationPolicy` attribute, which makes CloudFormation wait for
+/// success signals before a resource is complete.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct CreationPolicy {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_scaling_creation_policy: Option<AutoScalingCreationPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_signal: Option<ResourceSignal>,
+    #[serde(flatten)]
+    pub other: IndexMap<String, Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct AutoScalingCreationPolicy {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_successful_instances_percent: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResourceSignal {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<Value>,
+}
+
+/// The `UpdatePolicy` attribute. Only the Auto Scaling rolling update is
+/// modelled; policies of other resource types are kept in `other`.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct UpdatePolicy {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_scaling_rolling_update: Option<AutoScalingRollingUpdate>,
+    #[serde(flatten)]
+    pub other: IndexMap<String, Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct AutoScalingRollingUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_batch_size: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_active_instances_percent: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_instances_in_service: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_successful_instances_percent: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pause_time: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suspend_processes: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wait_on_resource_signals: Option<Value>,
+}
+
+impl AutoScalingGroup {
+    /// The number of instances the group launches when it is created, when
+    /// it is a literal.
+    fn initial_size(&self) -> Option<i64> {
+        self.desired_capacity
+            .as_ref()
+            .unwrap_or(&self.min_size)
+            .as_integer()
+    }
+
+    /// Checks that the subnets in `VPCZoneIdentifier` that this template
+    /// defines lie in the listed `AvailabilityZones`.
+    fn check_zones(&self, path: &str, template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        let (Some(Value::List(zones)), Some(Value::List(subnets))) =
+            (&self.availability_zones, &self.vpc_zone_identifier)
+        else {
+            return;
+        };
+        let Some(zones) = zones.iter().map(Value::as_str).collect::<Option<Vec<_>>>() else {
+            return;
+        };
+        for (index, subnet) in subnets.iter().enumerate() {
+            let Some(name) = referenced_resource(subnet) else {
+                continue;
+            };
+            let Some(Resource::Subnet(resource)) = template.resource(name) else {
+                continue;
+            };
+            let zone = resource
+                .properties
+                .availability_zone
+                .as_ref()
+                .and_then(Value::as_str);
+            if let Some(zone) = zone.filter(|zone| !zones.contains(zone)) {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/VPCZoneIdentifier/{}", path, index),
+                    format!(
+                        "subnet `{}` is in `{}`, which is not one of the group's `AvailabilityZones`",
+                        name, zone
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+impl Validate for AutoScalingGroup {
+    fn validate(&self, path: &str, template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        let min = self.min_size.as_integer();
+        let max = self.max_size.as_integer();
+        let desired = self.desired_capacity.as_ref().and_then(Value::as_integer);
+        match (min, desired, max) {
+            (Some(min), _, Some(max)) if min > max => diagnostics.push(Diagnostic::error(
+                format!("{}/MinSize", path),
+                format!("`MinSize` of {} is greater than `MaxSize` of {}", min, max),
+            )),
+            (Some(min), Some(desired), _) if desired < min => diagnostics.push(Diagnostic::error(
+                format!("{}/DesiredCapacity", path),
+                format!(
+                    "`DesiredCapacity` of {} is less than `MinSize` of {}",
+                    desired, min
+                ),
+            )),
+            (_, Some(desired), Some(max)) if desired > max => diagnostics.push(Diagnostic::error(
+                format!("{}/DesiredCapacity", path),
+                format!(
+                    "`DesiredCapacity` of {} is greater than `MaxSize` of {}",
+                    desired, max
+                ),
+            )),
+            _ => {}
+        }
+
+        let sources = [
+            ("InstanceId", self.instance_id.is_some()),
+            (
+                "LaunchConfigurationName",
+                self.launch_configuration_name.is_some(),
+            ),
+            ("LaunchTemplate", self.launch_template.is_some()),
+            (
+                "MixedInstancesPolicy",
+                self.mixed_instances_policy.is_some(),
+            ),
+        ];
+        if sources.iter().filter(|(_, set)| *set).count() != 1 {
+            diagnostics.push(Diagnostic::error(
+                path,
+                "exactly one of `InstanceId`, `LaunchConfigurationName`, `LaunchTemplate` and `MixedInstancesPolicy` is required",
+            ));
+        }
+        if let Some(launch_template) = self.launch_template.as_ref().and_then(Node::literal) {
+            launch_template.validate(&format!("{}/LaunchTemplate", path), diagnostics);
+        }
+        if let Some(Value::Ref { r#ref }) = &self.launch_configuration_name {
+            let resource = template.resource(r#ref);
+            if resource.is_some() && !matches!(resource, Some(Resource::LaunchConfiguration(_))) {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/LaunchConfigurationName", path),
+                    format!("`{}` is not a launch configuration", r#ref),
+                ));
+            }
+        }
+
+        if self.availability_zones.is_none() && self.vpc_zone_identifier.is_none() {
+            diagnostics.push(Diagnostic::error(
+                path,
+                "one of `AvailabilityZones` or `VPCZoneIdentifier` is required",
+            ));
+        }
+        self.check_zones(path, template, diagnostics);
+
+        if let Some(health_check_type) = &self.health_check_type {
+            check_one_of(
+                health_check_type,
+                HEALTH_CHECK_TYPES,
+                "health check type",
+                &format!("{}/HealthCheckType", path),
+                diagnostics,
+            );
+        }
+        if let Some(capacity_rebalance) = &self.capacity_rebalance {
+            let path = format!("{}/CapacityRebalance", path);
+            check_boolean(capacity_rebalance, &path, diagnostics);
+        }
+    }
+}
+
+impl Validate for LaunchConfiguration {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        // An existing instance supplies whatever is not set explicitly.
+        if self.instance_id.is_none() {
+            for (name, value) in [
+                ("ImageId", &self.image_id),
+                ("InstanceType", &self.instance_type),
+            ] {
+                if value.is_none() {
+                    diagnostics.push(Diagnostic::error(
+                        path,
+                        format!("`{}` is required unless `InstanceId` is set", name),
+                    ));
+                }
+            }
+        }
+        if let Some(tenancy) = &self.placement_tenancy {
+            check_one_of(
+                tenancy,
+                PLACEMENT_TENANCIES,
+                "placement tenancy",
+                &format!("{}/PlacementTenancy", path),
+                diagnostics,
+            );
+        }
+        for (name, value) in [
+            (
+                "AssociatePublicIpAddress",
+                &self.associate_public_ip_address,
+            ),
+            ("EbsOptimized", &self.ebs_optimized),
+            ("InstanceMonitoring", &self.instance_monitoring),
+        ] {
+            if let Some(value) = value {
+                check_boolean(value, &format!("{}/{}", path, name), diagnostics);
+            }
+        }
+        for (index, mapping) in self.block_device_mappings.iter().flat_map(Node::literals) {
+            mapping.validate(
+                &format!("{}/BlockDeviceMappings/{}", path, index),
+                diagnostics,
+            );
+        }
+    }
+}
+
+/// Checks the `CreationPolicy` and `UpdatePolicy` of each Auto Scaling group
+/// against the size of the group.
+pub fn validate_auto_scaling_groups(template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+    for (name, resource) in &template.resources {
+        let Resource::AutoScalingGroup(ResourceContainer {
+            creation_policy,
+            update_policy,
+            properties: group,
+            ..
+        }) = resource
+        else {
+            continue;
+        };
+        let path = format!("Resources/{}", name);
+
+        if let Some(creation_policy) = creation_policy {
+            let path = format!("{}/CreationPolicy", path);
+            let count = creation_policy
+                .resource_signal
+                .as_ref()
+                .and_then(|signal| signal.count.as_ref())
+                .and_then(Value::as_integer);
+            if let (Some(count), Some(size)) = (count, group.initial_size()) {
+                if count > size {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/ResourceSignal/Count", path),
+                        format!(
+                            "waits for {} signals, but the group launches {} instances",
+                            count, size
+                        ),
+                    ));
+                }
+            }
+            if let Some(policy) = &creation_policy.auto_scaling_creation_policy {
+                check_range(
+                    &policy.min_successful_instances_percent,
+                    PERCENTAGES,
+                    "minimum successful instances percent",
+                    &format!(
+                        "{}/AutoScalingCreationPolicy/MinSuccessfulInstancesPercent",
+                        path
+                    ),
+                    diagnostics,
+                );
+            }
+        }
+
+        let rolling_update = update_policy
+            .as_ref()
+            .and_then(|policy| policy.auto_scaling_rolling_update.as_ref());
+        if let Some(rolling_update) = rolling_update {
+            let path = format!("{}/UpdatePolicy/AutoScalingRollingUpdate", path);
+            let in_service = rolling_update
+                .min_instances_in_service
+                .as_ref()
+                .and_then(Value::as_integer);
+            if let (Some(in_service), Some(max)) = (in_service, group.max_size.as_integer()) {
+                if in_service >= max {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/MinInstancesInService", path),
+                        format!(
+                            "`MinInstancesInService` of {} must be less than `MaxSize` of {}",
+                            in_service, max
+                        ),
+                    ));
+                }
+            }
+            for (property, value, what) in [
+                (
+                    "MinActiveInstancesPercent",
+                    &rolling_update.min_active_instances_percent,
+                    "minimum active instances percent",
+                ),
+                (
+                    "MinSuccessfulInstancesPercent",
+                    &rolling_update.min_successful_instances_percent,
+                    "minimum successful instances percent",
+                ),
+            ] {
+                check_range(
+                    value,
+                    PERCENTAGES,
+                    what,
+                    &format!("{}/{}", path, property),
+                    diagnostics,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{diagnostic::Diagnostic, document::Format, Template};
+
+    #[test]
+    fn test_validate_group_sizes() {
+        let yaml = r#"
+Parameters:
+  MinSize:
+    Type: Number
+Resources:
+  Inverted:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    Properties:
+      MinSize: 4
+      MaxSize: 2
+      LaunchTemplate: {LaunchTemplateName: web, Version: "1"}
+      AvailabilityZones: [eu-west-1a]
+  Small:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    Properties:
+      MinSize: 2
+      DesiredCapacity: 1
+      MaxSize: 4
+      LaunchTemplate: {LaunchTemplateName: web, Version: "1"}
+      AvailabilityZones: [eu-west-1a]
+  Large:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    Properties:
+      MinSize: "1"
+      DesiredCapacity: "4"
+      MaxSize: "3"
+      LaunchTemplate: {LaunchTemplateName: web, Version: "1"}
+      AvailabilityZones: [eu-west-1a]
+  FromParameter:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    Properties:
+      MinSize: !Ref MinSize
+      DesiredCapacity: 1
+      MaxSize: 4
+      LaunchTemplate: {LaunchTemplateName: web, Version: "1"}
+      AvailabilityZones: [eu-west-1a]
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Inverted/Properties/MinSize",
+                "`MinSize` of 4 is greater than `MaxSize` of 2",
+            ),
+            Diagnostic::error(
+                "Resources/Small/Properties/DesiredCapacity",
+                "`DesiredCapacity` of 1 is less than `MinSize` of 2",
+            ),
+            Diagnostic::error(
+                "Resources/Large/Properties/DesiredCapacity",
+                "`DesiredCapacity` of 4 is greater than `MaxSize` of 3",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_group_launch_source() {
+        let yaml = r#"
+Parameters:
+  LaunchConfiguration:
+    Type: String
+  InstanceId:
+    Type: String
+Resources:
+  Subnet:
+    Type: AWS::EC2::Subnet
+    Properties:
+      VpcId: vpc-123
+      CidrBlock: 10.0.0.0/24
+  Both:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    Properties:
+      MinSize: 1
+      MaxSize: 1
+      LaunchConfigurationName: !Ref LaunchConfiguration
+      LaunchTemplate: {LaunchTemplateName: web, Version: "1"}
+      AvailabilityZones: [eu-west-1a]
+  Neither:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    Properties:
+      MinSize: 1
+      MaxSize: 1
+      AvailabilityZones: [eu-west-1a]
+  Unnamed:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    Properties:
+      MinSize: 1
+      MaxSize: 1
+      LaunchTemplate: {Version: "1"}
+      AvailabilityZones: [eu-west-1a]
+  WrongResource:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    Properties:
+      MinSize: 1
+      MaxSize: 1
+      LaunchConfigurationName: !Ref Subnet
+      AvailabilityZones: [eu-west-1a]
+  Mixed:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    Properties:
+      MinSize: 1
+      MaxSize: 1
+      MixedInstancesPolicy:
+        LaunchTemplate:
+          LaunchTemplateSpecification: {LaunchTemplateName: web, Version: "1"}
+      AvailabilityZones: [eu-west-1a]
+  Computed:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    Properties:
+      MinSize: 1
+      MaxSize: 1
+      LaunchTemplate: !If [HasTemplate, {LaunchTemplateName: web, Version: "1"}, !Ref AWS::NoValue]
+      AvailabilityZones: [eu-west-1a]
+  FromInstance:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    Properties:
+      MinSize: 1
+      MaxSize: 1
+      InstanceId: i-0123456789abcdef0
+      AvailabilityZones: [eu-west-1a]
+  InstanceAndTemplate:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    Properties:
+      MinSize: 1
+      MaxSize: 1
+      InstanceId: !Ref InstanceId
+      LaunchTemplate: {LaunchTemplateName: web, Version: "1"}
+      AvailabilityZones: [eu-west-1a]
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Both/Properties",
+                "exactly one of `InstanceId`, `LaunchConfigurationName`, `LaunchTemplate` and `MixedInstancesPolicy` is required",
+            ),
+            Diagnostic::error(
+                "Resources/Neither/Properties",
+                "exactly one of `InstanceId`, `LaunchConfigurationName`, `LaunchTemplate` and `MixedInstancesPolicy` is required",
+            ),
+            Diagnostic::error(
+                "Resources/Unnamed/Properties/LaunchTemplate",
+                "exactly one of `LaunchTemplateId` and `LaunchTemplateName` is required",
+            ),
+            Diagnostic::error(
+                "Resources/WrongResource/Properties/LaunchConfigurationName",
+                "`Subnet` is not a launch configuration",
+            ),
+            Diagnostic::error(
+                "Resources/InstanceAndTemplate/Properties",
+                "exactly one of `InstanceId`, `LaunchConfigurationName`, `LaunchTemplate` and `MixedInstancesPolicy` is required",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_group_zones() {
+        let yaml = r#"
+Parameters:
+  Subnets:
+    Type: CommaDelimitedList
+Resources:
+  SubnetA:
+    Type: AWS::EC2::Subnet
+    Properties:
+      VpcId: vpc-123
+      CidrBlock: 10.0.0.0/24
+      AvailabilityZone: eu-west-1a
+  SubnetC:
+    Type: AWS::EC2::Subnet
+    Properties:
+      VpcId: vpc-123
+      CidrBlock: 10.0.1.0/24
+      AvailabilityZone: eu-west-1c
+  Nowhere:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    Properties:
+      MinSize: 1
+      MaxSize: 1
+      LaunchTemplate: {LaunchTemplateName: web, Version: "1"}
+  Mismatched:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    Properties:
+      MinSize: 1
+      MaxSize: 1
+      LaunchTemplate: {LaunchTemplateName: web, Version: "1"}
+      AvailabilityZones: [eu-west-1a, eu-west-1b]
+      VPCZoneIdentifier: [!Ref SubnetA, !Ref SubnetC, subnet-123]
+  AllZones:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    Properties:
+      MinSize: 1
+      MaxSize: 1
+      LaunchTemplate: {LaunchTemplateName: web, Version: "1"}
+      AvailabilityZones: !GetAZs ""
+      VPCZoneIdentifier: [!Ref SubnetA, !Ref SubnetC]
+  FromParameter:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    Properties:
+      MinSize: 1
+      MaxSize: 1
+      LaunchTemplate: {LaunchTemplateName: web, Version: "1"}
+      AvailabilityZones: [eu-west-1a]
+      VPCZoneIdentifier: !Ref Subnets
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Nowhere/Properties",
+                "one of `AvailabilityZones` or `VPCZoneIdentifier` is required",
+            ),
+            Diagnostic::error(
+                "Resources/Mismatched/Properties/VPCZoneIdentifier/1",
+                "subnet `SubnetC` is in `eu-west-1c`, which is not one of the group's `AvailabilityZones`",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_group_health_check_and_rebalance() {
+        let yaml = r#"
+Parameters:
+  HealthCheckType:
+    Type: String
+Resources:
+  Group:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    Properties:
+      MinSize: 1
+      MaxSize: 1
+      LaunchTemplate: {LaunchTemplateName: web, Version: "1"}
+      AvailabilityZones: [eu-west-1a]
+      HealthCheckType: ALB
+      CapacityRebalance: "yes"
+  FromParameter:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    Properties:
+      MinSize: 1
+      MaxSize: 1
+      LaunchTemplate: {LaunchTemplateName: web, Version: "1"}
+      AvailabilityZones: [eu-west-1a]
+      HealthCheckType: !Ref HealthCheckType
+      CapacityRebalance: !If [IsProduction, true, false]
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Group/Properties/HealthCheckType",
+                "`ALB` is not a valid health check type, expected one of EBS, ELB, EC2, VPC_LATTICE",
+            ),
+            Diagnostic::error(
+                "Resources/Group/Properties/CapacityRebalance",
+                "expected a boolean, `true` or `false`",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_launch_configuration() {
+        let yaml = r#"
+Resources:
+  Launch:
+    Type: AWS::AutoScaling::LaunchConfiguration
+    Properties:
+      ImageId: ami-123
+      PlacementTenancy: host
+      EbsOptimized: "yes"
+      BlockDeviceMappings:
+        - DeviceName: /dev/sdb
+          NoDevice: {}
+          Ebs: {VolumeSize: 20}
+        - !If [HasData, {DeviceName: /dev/sdc, Ebs: {VolumeSize: 20}}, !Ref AWS::NoValue]
+  FromInstance:
+    Type: AWS::AutoScaling::LaunchConfiguration
+    Properties:
+      InstanceId: i-1234567890abcdef0
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Launch/Properties",
+                "`InstanceType` is required unless `InstanceId` is set",
+            ),
+            Diagnostic::error(
+                "Resources/Launch/Properties/PlacementTenancy",
+                "`host` is not a valid placement tenancy, expected one of dedicated, default",
+            ),
+            Diagnostic::error(
+                "Resources/Launch/Properties/EbsOptimized",
+                "expected a boolean, `true` or `false`",
+            ),
+            Diagnostic::error(
+                "Resources/Launch/Properties/BlockDeviceMappings/0",
+                "`Ebs` cannot be used with `NoDevice` or `VirtualName`",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_group_creation_policy() {
+        let yaml = r#"
+Parameters:
+  Desired:
+    Type: Number
+Resources:
+  Group:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    CreationPolicy:
+      ResourceSignal: {Count: 4, Timeout: PT15M}
+      AutoScalingCreationPolicy:
+        MinSuccessfulInstancesPercent: 150
+    Properties:
+      MinSize: 2
+      DesiredCapacity: 3
+      MaxSize: 6
+      LaunchTemplate: {LaunchTemplateName: web, Version: "1"}
+      AvailabilityZones: [eu-west-1a]
+  Minimum:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    CreationPolicy:
+      ResourceSignal: {Count: 3, Timeout: PT15M}
+    Properties:
+      MinSize: 2
+      MaxSize: 6
+      LaunchTemplate: {LaunchTemplateName: web, Version: "1"}
+      AvailabilityZones: [eu-west-1a]
+  FromParameter:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    CreationPolicy:
+      ResourceSignal: {Count: 4, Timeout: PT15M}
+    Properties:
+      MinSize: 2
+      DesiredCapacity: !Ref Desired
+      MaxSize: 6
+      LaunchTemplate: {LaunchTemplateName: web, Version: "1"}
+      AvailabilityZones: [eu-west-1a]
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Group/CreationPolicy/ResourceSignal/Count",
+                "waits for 4 signals, but the group launches 3 instances",
+            ),
+            Diagnostic::error(
+                "Resources/Group/CreationPolicy/AutoScalingCreationPolicy/MinSuccessfulInstancesPercent",
+                "minimum successful instances percent must be between 0 and 100, not 150",
+            ),
+            Diagnostic::error(
+                "Resources/Minimum/CreationPolicy/ResourceSignal/Count",
+                "waits for 3 signals, but the group launches 2 instances",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_group_rolling_update() {
+        let yaml = r#"
+Parameters:
+  MaxSize:
+    Type: Number
+Resources:
+  Group:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    UpdatePolicy:
+      AutoScalingRollingUpdate:
+        MinInstancesInService: 6
+        MinActiveInstancesPercent: -1
+        MinSuccessfulInstancesPercent: 150
+    Properties:
+      MinSize: 2
+      MaxSize: 6
+      LaunchTemplate: {LaunchTemplateName: web, Version: "1"}
+      AvailabilityZones: [eu-west-1a]
+  FromParameter:
+    Type: AWS::AutoScaling::AutoScalingGroup
+    UpdatePolicy:
+      AutoScalingRollingUpdate:
+        MinInstancesInService: 6
+    Properties:
+      MinSize: 2
+      MaxSize: !Ref MaxSize
+      LaunchTemplate: {LaunchTemplateName: web, Version: "1"}
+      AvailabilityZones: [eu-west-1a]
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Group/UpdatePolicy/AutoScalingRollingUpdate/MinInstancesInService",
+                "`MinInstancesInService` of 6 must be less than `MaxSize` of 6",
+            ),
+            Diagnostic::error(
+                "Resources/Group/UpdatePolicy/AutoScalingRollingUpdate/MinActiveInstancesPercent",
+                "minimum active instances percent must be between 0 and 100, not -1",
+            ),
+            Diagnostic::error(
+                "Resources/Group/UpdatePolicy/AutoScalingRollingUpdate/MinSuccessfulInstancesPercent",
+                "minimum successful instances percent must be between 0 and 100, not 150",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+}
@@ -0,0 +1,1003 @@
+use super::{policy_document::check_arn, Resource};
+use crate::{
+    diagnostic::Diagnostic,
+    validate::{check_boolean, check_one_of, check_range, Validate},
+    value::{Node, Value},
+    Template,
+};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+const STATISTICS: &[&str] = &["Average", "Maximum", "Minimum", "SampleCount", "Sum"];
+
+const COMPARISON_OPERATORS: &[&str] = &[
+    "GreaterThanOrEqualToThreshold",
+    "GreaterThanThreshold",
+    "GreaterThanUpperThreshold",
+    "LessThanLowerOrGreaterThanUpperThreshold",
+    "LessThanLowerThreshold",
+    "LessThanOrEqualToThreshold",
+    "LessThanThreshold",
+];
+
+/// Operators that compare against an anomaly detection band, given by
+/// `ThresholdMetricId`, instead of a static `Threshold`.
+const BAND_OPERATORS: &[&str] = &[
+    "GreaterThanUpperThreshold",
+    "LessThanLowerOrGreaterThanUpperThreshold",
+    "LessThanLowerThreshold",
+];
+
+const MISSING_DATA_TREATMENTS: &[&str] = &["breaching", "ignore", "missing", "notBreaching"];
+
+/// Services whose ARNs an alarm can notify or act on.
+const ACTION_SERVICES: &[&str] = &[
+    "autoscaling",
+    "automate",
+    "lambda",
+    "sns",
+    "ssm",
+    "ssm-incidents",
+    "swf",
+];
+
+/// Resource types whose `Ref` (`None`) or attribute returns an ARN an alarm
+/// can notify or act on.
+const ACTION_RESOURCES: &[(&str, Option<&str>)] = &[
+    ("AWS::ApplicationAutoScaling::ScalingPolicy", None),
+    ("AWS::ApplicationAutoScaling::ScalingPolicy", Some("Arn")),
+    ("AWS::AutoScaling::ScalingPolicy", None),
+    ("AWS::AutoScaling::ScalingPolicy", Some("Arn")),
+    ("AWS::Lambda::Alias", None),
+    ("AWS::Lambda::Function", Some("Arn")),
+    ("AWS::Lambda::Version", None),
+    ("AWS::SNS::Topic", None),
+    ("AWS::SNS::Topic", Some("TopicArn")),
+    ("AWS::SSMIncidents::ResponsePlan", None),
+    ("AWS::SSMIncidents::ResponsePlan", Some("Arn")),
+];
+
+const DIMENSIONS: RangeInclusive<i64> = 0..=30;
+
+const QUERIES: RangeInclusive<i64> = 1..=10;
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Alarm {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actions_enabled: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alarm_actions: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alarm_description: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alarm_name: Option<Value>,
+    pub comparison_operator: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datapoints_to_alarm: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<Node<Vec<Node<Dimension>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub evaluate_low_sample_count_percentile: Option<Value>,
+    pub evaluation_periods: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extended_statistic: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub insufficient_data_actions: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<Node<Vec<Node<MetricDataQuery>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<Value>,
+    #[serde(rename = "OKActions", skip_serializing_if = "Option::is_none")]
+    pub ok_actions: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub statistic: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold_metric_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub treat_missing_data: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Dimension {
+    pub name: Value,
+    pub value: Value,
+}
+
+/// One entry of `Metrics`: either a metric or a math expression over the
+/// other entries.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct MetricDataQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expression: Option<Value>,
+    pub id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric_stat: Option<Node<MetricStat>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_data: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct MetricStat {
+    pub metric: Node<Metric>,
+    pub period: Value,
+    pub stat: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Metric {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<Node<Vec<Node<Dimension>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<Value>,
+}
+
+/// Reports a period that is not 10, 30 or a multiple of 60 seconds.
+fn check_period(period: &Value, path: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(seconds) = period.as_integer() else {
+        return;
+    };
+    if !(seconds == 10 || seconds == 30 || seconds > 0 && seconds % 60 == 0) {
+        diagnostics.push(Diagnostic::error(
+            path,
+            format!(
+                "period must be 10, 30 or a multiple of 60 seconds, not {}",
+                seconds
+            ),
+        ));
+    }
+}
+
+fn check_dimensions(
+    dimensions: &Node<Vec<Node<Dimension>>>,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Node::Literal(list) = dimensions else {
+        return;
+    };
+    check_range(
+        &Some(Value::Number(list.len() as i64)),
+        DIMENSIONS,
+        "the number of dimensions",
+        path,
+        diagnostics,
+    );
+    let mut names = Vec::new();
+    for (index, dimension) in dimensions.literals() {
+        let Some(name) = dimension.name.as_str() else {
+            continue;
+        };
+        if names.contains(&name) {
+            diagnostics.push(Diagnostic::error(
+                format!("{}/{}/Name", path, index),
+                format!("dimension `{}` is listed more than once", name),
+            ));
+        }
+        names.push(name);
+    }
+}
+
+/// Reports an alarm action that is not an SNS topic or scaling policy of the
+/// template, a parameter or the literal ARN of something an alarm can act on.
+fn check_action(
+    action: &Value,
+    path: &str,
+    template: &Template,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let (name, attribute) = match action {
+        Value::Ref { r#ref } if template.parameter(r#ref).is_some() => return,
+        Value::Ref { r#ref } => (r#ref, None),
+        Value::GetAtt { get_att } => (&get_att[0], Some(get_att[1].as_str())),
+        _ => {
+            check_arn(action, path, diagnostics);
+            let service = action
+                .as_str()
+                .and_then(|arn| arn.split(':').nth(2))
+                .filter(|service| !service.is_empty());
+            if let Some(service) = service {
+                if !ACTION_SERVICES.contains(&service) {
+                    diagnostics.push(Diagnostic::error(
+                        path,
+                        format!("an alarm cannot act on a `{}` ARN", service),
+                    ));
+                }
+            }
+            return;
+        }
+    };
+    match template.resource(name).map(Resource::resource_type) {
+        Some(resource_type) if ACTION_RESOURCES.contains(&(&resource_type, attribute)) => {}
+        Some(resource_type) => diagnostics.push(Diagnostic::error(
+            path,
+            match attribute {
+                None => format!(
+                    "`Ref` of `{}` ({}) is not an ARN an alarm can act on",
+                    name, resource_type
+                ),
+                Some(attribute) => format!(
+                    "`{}.{}` ({}) is not an ARN an alarm can act on",
+                    name, attribute, resource_type
+                ),
+            },
+        )),
+        None => diagnostics.push(Diagnostic::error(
+            path,
+            format!("alarm action `{}` does not exist", name),
+        )),
+    }
+}
+
+impl Validate for Alarm {
+    fn validate(&self, path: &str, template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        check_one_of(
+            &self.comparison_operator,
+            COMPARISON_OPERATORS,
+            "comparison operator",
+            &format!("{}/ComparisonOperator", path),
+            diagnostics,
+        );
+        if let Some(treatment) = &self.treat_missing_data {
+            check_one_of(
+                treatment,
+                MISSING_DATA_TREATMENTS,
+                "missing data treatment",
+                &format!("{}/TreatMissingData", path),
+                diagnostics,
+            );
+        }
+        if let Some(enabled) = &self.actions_enabled {
+            check_boolean(enabled, &format!("{}/ActionsEnabled", path), diagnostics);
+        }
+
+        // Band operators compare against an anomaly detection model instead of
+        // a static threshold.
+        let operator = self.comparison_operator.as_str();
+        match operator {
+            Some(operator) if BAND_OPERATORS.contains(&operator) => {
+                if self.threshold_metric_id.is_none() {
+                    diagnostics.push(Diagnostic::error(
+                        path,
+                        format!("`{}` requires `ThresholdMetricId`", operator),
+                    ));
+                }
+                if self.threshold.is_some() {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/Threshold", path),
+                        format!("`Threshold` cannot be used with `{}`", operator),
+                    ));
+                }
+            }
+            Some(_) if self.threshold.is_none() => diagnostics.push(Diagnostic::error(
+                path,
+                "`Threshold` is required unless the alarm uses an anomaly detection band",
+            )),
+            _ => {}
+        }
+
+        check_range(
+            &Some(self.evaluation_periods.clone()),
+            1..=i64::MAX,
+            "evaluation periods",
+            &format!("{}/EvaluationPeriods", path),
+            diagnostics,
+        );
+        let evaluation_periods = self.evaluation_periods.as_integer();
+        let datapoints = self
+            .datapoints_to_alarm
+            .as_ref()
+            .and_then(Value::as_integer);
+        if let (Some(evaluation_periods), Some(datapoints)) = (evaluation_periods, datapoints) {
+            if !(1..=evaluation_periods).contains(&datapoints) {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/DatapointsToAlarm", path),
+                    format!(
+                        "datapoints to alarm must be between 1 and the {} evaluation periods, not {}",
+                        evaluation_periods, datapoints
+                    ),
+                ));
+            }
+        }
+
+        match &self.metrics {
+            Some(Node::Literal(metrics)) => self.check_metrics(metrics, path, diagnostics),
+            Some(Node::Computed(_)) => {}
+            None => self.check_single_metric(path, diagnostics),
+        }
+
+        for (name, actions) in [
+            ("AlarmActions", &self.alarm_actions),
+            ("InsufficientDataActions", &self.insufficient_data_actions),
+            ("OKActions", &self.ok_actions),
+        ] {
+            for (index, action) in actions.iter().flat_map(Node::items).enumerate() {
+                let path = format!("{}/{}/{}", path, name, index);
+                check_action(action, &path, template, diagnostics);
+            }
+        }
+    }
+}
+
+impl Alarm {
+    /// Checks an alarm on one metric, described by the top-level properties.
+    fn check_single_metric(&self, path: &str, diagnostics: &mut Vec<Diagnostic>) {
+        for (name, value) in [
+            ("MetricName", &self.metric_name),
+            ("Namespace", &self.namespace),
+            ("Period", &self.period),
+        ] {
+            if value.is_none() {
+                diagnostics.push(Diagnostic::error(
+                    path,
+                    format!("`{}` is required unless the alarm uses `Metrics`", name),
+                ));
+            }
+        }
+        match (&self.statistic, &self.extended_statistic) {
+            (Some(_), Some(_)) => diagnostics.push(Diagnostic::error(
+                path,
+                "`Statistic` cannot be used with `ExtendedStatistic`",
+            )),
+            (None, None) => diagnostics.push(Diagnostic::error(
+                path,
+                "one of `Statistic` or `ExtendedStatistic` is required",
+            )),
+            (Some(statistic), None) => check_one_of(
+                statistic,
+                STATISTICS,
+                "statistic",
+                &format!("{}/Statistic", path),
+                diagnostics,
+            ),
+            (None, Some(_)) => {}
+        }
+        if let Some(period) = &self.period {
+            check_period(period, &format!("{}/Period", path), diagnostics);
+        }
+        if let Some(dimensions) = &self.dimensions {
+            check_dimensions(dimensions, &format!("{}/Dimensions", path), diagnostics);
+        }
+    }
+
+    /// Checks an alarm on a metric math expression, described by `Metrics`.
+    fn check_metrics(
+        &self,
+        metrics: &[Node<MetricDataQuery>],
+        path: &str,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        for (name, set) in [
+            ("MetricName", self.metric_name.is_some()),
+            ("Namespace", self.namespace.is_some()),
+            ("Statistic", self.statistic.is_some()),
+            ("ExtendedStatistic", self.extended_statistic.is_some()),
+            ("Dimensions", self.dimensions.is_some()),
+            ("Period", self.period.is_some()),
+            ("Unit", self.unit.is_some()),
+        ] {
+            if set {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/{}", path, name),
+                    format!("`{}` cannot be used with `Metrics`", name),
+                ));
+            }
+        }
+
+        let path = format!("{}/Metrics", path);
+        check_range(
+            &Some(Value::Number(metrics.len() as i64)),
+            QUERIES,
+            "the number of metrics",
+            &path,
+            diagnostics,
+        );
+        let mut ids = Vec::new();
+        let queries = metrics.iter().enumerate();
+        for (index, query) in queries.filter_map(|(index, query)| Some((index, query.literal()?))) {
+            let path = format!("{}/{}", path, index);
+            if let Some(id) = query.id.as_str() {
+                if !id.starts_with(|c: char| c.is_ascii_lowercase()) {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/Id", path),
+                        format!("metric id `{}` must start with a lowercase letter", id),
+                    ));
+                }
+                if ids.contains(&id) {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/Id", path),
+                        format!("metric id `{}` is used more than once", id),
+                    ));
+                }
+                ids.push(id);
+            }
+            match (&query.expression, &query.metric_stat) {
+                (Some(_), Some(_)) => diagnostics.push(Diagnostic::error(
+                    &path,
+                    "`Expression` cannot be used with `MetricStat`",
+                )),
+                (None, None) => diagnostics.push(Diagnostic::error(
+                    &path,
+                    "one of `Expression` or `MetricStat` is required",
+                )),
+                (None, Some(Node::Computed(_))) => {}
+                (None, Some(Node::Literal(stat))) => {
+                    check_period(
+                        &stat.period,
+                        &format!("{}/MetricStat/Period", path),
+                        diagnostics,
+                    );
+                    let metric = stat.metric.literal();
+                    if let Some(dimensions) = metric.and_then(|metric| metric.dimensions.as_ref()) {
+                        let path = format!("{}/MetricStat/Metric/Dimensions", path);
+                        check_dimensions(dimensions, &path, diagnostics);
+                    }
+                }
+                (Some(_), None) => {}
+            }
+            if let Some(return_data) = &query.return_data {
+                check_boolean(return_data, &format!("{}/ReturnData", path), diagnostics);
+            }
+        }
+
+        // `ReturnData` defaults to true, and an alarm needs exactly one series.
+        // Which series a computed query returns is not known.
+        if metrics.iter().any(|query| query.literal().is_none()) {
+            return;
+        }
+        let returned = metrics
+            .iter()
+            .filter_map(Node::literal)
+            .filter(|query| query.return_data.as_ref().and_then(Value::as_bool) != Some(false))
+            .count();
+        if returned != 1 {
+            diagnostics.push(Diagnostic::error(
+                path,
+                format!(
+                    "exactly one metric must have `ReturnData` set to true, not {}",
+                    returned
+                ),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{diagnostic::Diagnostic, document::Format, Template};
+
+    #[test]
+    fn test_validate_alarm_enumerations() {
+        let yaml = r#"
+Parameters:
+  Operator:
+    Type: String
+Resources:
+  Alarm:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      Namespace: AWS/EC2
+      MetricName: CPUUtilization
+      Statistic: Average
+      Period: 60
+      EvaluationPeriods: 1
+      Threshold: 80
+      ComparisonOperator: GreaterThan
+      TreatMissingData: zero
+      ActionsEnabled: "on"
+  FromParameter:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      Namespace: AWS/EC2
+      MetricName: CPUUtilization
+      Statistic: Average
+      Period: 60
+      EvaluationPeriods: 1
+      ComparisonOperator: !Ref Operator
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Alarm/Properties/ComparisonOperator",
+                "`GreaterThan` is not a valid comparison operator, expected one of GreaterThanOrEqualToThreshold, GreaterThanThreshold, GreaterThanUpperThreshold, LessThanLowerOrGreaterThanUpperThreshold, LessThanLowerThreshold, LessThanOrEqualToThreshold, LessThanThreshold",
+            ),
+            Diagnostic::error(
+                "Resources/Alarm/Properties/TreatMissingData",
+                "`zero` is not a valid missing data treatment, expected one of breaching, ignore, missing, notBreaching",
+            ),
+            Diagnostic::error(
+                "Resources/Alarm/Properties/ActionsEnabled",
+                "expected a boolean, `true` or `false`",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_alarm_threshold() {
+        let yaml = r#"
+Resources:
+  Static:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      Namespace: AWS/EC2
+      MetricName: CPUUtilization
+      Statistic: Average
+      Period: 60
+      EvaluationPeriods: 1
+      ComparisonOperator: GreaterThanThreshold
+  Band:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      Namespace: AWS/EC2
+      MetricName: CPUUtilization
+      Statistic: Average
+      Period: 60
+      EvaluationPeriods: 1
+      Threshold: 80
+      ComparisonOperator: LessThanLowerThreshold
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Static/Properties",
+                "`Threshold` is required unless the alarm uses an anomaly detection band",
+            ),
+            Diagnostic::error(
+                "Resources/Band/Properties",
+                "`LessThanLowerThreshold` requires `ThresholdMetricId`",
+            ),
+            Diagnostic::error(
+                "Resources/Band/Properties/Threshold",
+                "`Threshold` cannot be used with `LessThanLowerThreshold`",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_alarm_evaluation_periods() {
+        let yaml = r#"
+Parameters:
+  Datapoints:
+    Type: Number
+Resources:
+  Never:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      Namespace: AWS/EC2
+      MetricName: CPUUtilization
+      Statistic: Average
+      Period: 60
+      EvaluationPeriods: 0
+      Threshold: 80
+      ComparisonOperator: GreaterThanThreshold
+  TooMany:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      Namespace: AWS/EC2
+      MetricName: CPUUtilization
+      Statistic: Average
+      Period: 60
+      EvaluationPeriods: 3
+      DatapointsToAlarm: 5
+      Threshold: 80
+      ComparisonOperator: GreaterThanThreshold
+  FromParameter:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      Namespace: AWS/EC2
+      MetricName: CPUUtilization
+      Statistic: Average
+      Period: 60
+      EvaluationPeriods: 3
+      DatapointsToAlarm: !Ref Datapoints
+      Threshold: 80
+      ComparisonOperator: GreaterThanThreshold
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Never/Properties/EvaluationPeriods",
+                "evaluation periods must be between 1 and 9223372036854775807, not 0",
+            ),
+            Diagnostic::error(
+                "Resources/TooMany/Properties/DatapointsToAlarm",
+                "datapoints to alarm must be between 1 and the 3 evaluation periods, not 5",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_alarm_single_metric() {
+        let yaml = r#"
+Resources:
+  Unnamed:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      Statistic: Average
+      EvaluationPeriods: 1
+      Threshold: 80
+      ComparisonOperator: GreaterThanThreshold
+  Both:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      Namespace: AWS/EC2
+      MetricName: CPUUtilization
+      Statistic: Average
+      ExtendedStatistic: p99
+      Period: 60
+      EvaluationPeriods: 1
+      Threshold: 80
+      ComparisonOperator: GreaterThanThreshold
+  Neither:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      Namespace: AWS/EC2
+      MetricName: CPUUtilization
+      Period: 60
+      EvaluationPeriods: 1
+      Threshold: 80
+      ComparisonOperator: GreaterThanThreshold
+  Median:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      Namespace: AWS/EC2
+      MetricName: CPUUtilization
+      Statistic: Median
+      Period: 60
+      EvaluationPeriods: 1
+      Threshold: 80
+      ComparisonOperator: GreaterThanThreshold
+  Percentile:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      Namespace: AWS/EC2
+      MetricName: CPUUtilization
+      ExtendedStatistic: p99
+      Period: 60
+      EvaluationPeriods: 1
+      Threshold: 80
+      ComparisonOperator: GreaterThanThreshold
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Unnamed/Properties",
+                "`MetricName` is required unless the alarm uses `Metrics`",
+            ),
+            Diagnostic::error(
+                "Resources/Unnamed/Properties",
+                "`Namespace` is required unless the alarm uses `Metrics`",
+            ),
+            Diagnostic::error(
+                "Resources/Unnamed/Properties",
+                "`Period` is required unless the alarm uses `Metrics`",
+            ),
+            Diagnostic::error(
+                "Resources/Both/Properties",
+                "`Statistic` cannot be used with `ExtendedStatistic`",
+            ),
+            Diagnostic::error(
+                "Resources/Neither/Properties",
+                "one of `Statistic` or `ExtendedStatistic` is required",
+            ),
+            Diagnostic::error(
+                "Resources/Median/Properties/Statistic",
+                "`Median` is not a valid statistic, expected one of Average, Maximum, Minimum, SampleCount, Sum",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_alarm_period_and_dimensions() {
+        let yaml = r#"
+Parameters:
+  Period:
+    Type: Number
+Resources:
+  Alarm:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      Namespace: AWS/EC2
+      MetricName: CPUUtilization
+      Statistic: Average
+      Dimensions:
+        - {Name: InstanceId, Value: i-1234}
+        - {Name: InstanceId, Value: i-5678}
+      Period: 45
+      EvaluationPeriods: 1
+      Threshold: 80
+      ComparisonOperator: GreaterThanThreshold
+  HighResolution:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      Namespace: AWS/EC2
+      MetricName: CPUUtilization
+      Statistic: Average
+      Dimensions: !If [HasInstance, [{Name: InstanceId, Value: i-1234}], []]
+      Period: 10
+      EvaluationPeriods: 1
+      Threshold: 80
+      ComparisonOperator: GreaterThanThreshold
+  FromParameter:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      Namespace: AWS/EC2
+      MetricName: CPUUtilization
+      Statistic: Average
+      Period: !Ref Period
+      EvaluationPeriods: 1
+      Threshold: 80
+      ComparisonOperator: GreaterThanThreshold
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Alarm/Properties/Period",
+                "period must be 10, 30 or a multiple of 60 seconds, not 45",
+            ),
+            Diagnostic::error(
+                "Resources/Alarm/Properties/Dimensions/1/Name",
+                "dimension `InstanceId` is listed more than once",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_alarm_metrics() {
+        let yaml = r#"
+Resources:
+  ErrorRate:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      Namespace: AWS/Lambda
+      EvaluationPeriods: 1
+      Threshold: 1
+      ComparisonOperator: GreaterThanThreshold
+      Metrics:
+        - Id: errors
+          MetricStat:
+            Metric: {Namespace: AWS/Lambda, MetricName: Errors}
+            Period: 45
+            Stat: Sum
+        - Id: errors
+          Expression: FILL(errors, 0)
+          MetricStat:
+            Metric: {Namespace: AWS/Lambda, MetricName: Errors}
+            Period: 60
+            Stat: Sum
+          ReturnData: false
+        - Id: Rate
+          ReturnData: "no"
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/ErrorRate/Properties/Namespace",
+                "`Namespace` cannot be used with `Metrics`",
+            ),
+            Diagnostic::error(
+                "Resources/ErrorRate/Properties/Metrics/0/MetricStat/Period",
+                "period must be 10, 30 or a multiple of 60 seconds, not 45",
+            ),
+            Diagnostic::error(
+                "Resources/ErrorRate/Properties/Metrics/1/Id",
+                "metric id `errors` is used more than once",
+            ),
+            Diagnostic::error(
+                "Resources/ErrorRate/Properties/Metrics/1",
+                "`Expression` cannot be used with `MetricStat`",
+            ),
+            Diagnostic::error(
+                "Resources/ErrorRate/Properties/Metrics/2/Id",
+                "metric id `Rate` must start with a lowercase letter",
+            ),
+            Diagnostic::error(
+                "Resources/ErrorRate/Properties/Metrics/2",
+                "one of `Expression` or `MetricStat` is required",
+            ),
+            Diagnostic::error(
+                "Resources/ErrorRate/Properties/Metrics/2/ReturnData",
+                "expected a boolean, `true` or `false`",
+            ),
+            Diagnostic::error(
+                "Resources/ErrorRate/Properties/Metrics",
+                "exactly one metric must have `ReturnData` set to true, not 2",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_alarm_metrics_computed() {
+        let yaml = r#"
+Resources:
+  ErrorRate:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      EvaluationPeriods: 1
+      Threshold: 1
+      ComparisonOperator: GreaterThanThreshold
+      Metrics:
+        - Id: errors
+          MetricStat:
+            Metric: {Namespace: AWS/Lambda, MetricName: Errors}
+            Period: 60
+            Stat: Sum
+        - !If
+          - HasThrottles
+          - Id: throttles
+            MetricStat:
+              Metric: {Namespace: AWS/Lambda, MetricName: Throttles}
+              Period: 60
+              Stat: Sum
+          - !Ref AWS::NoValue
+  Everything:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      EvaluationPeriods: 1
+      Threshold: 1
+      ComparisonOperator: GreaterThanThreshold
+      Metrics: !If [HasErrors, [{Id: errors, Expression: "SUM(METRICS())"}], []]
+"#;
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(Vec::<Diagnostic>::new(), template.validate());
+    }
+
+    #[test]
+    fn test_validate_alarm_actions() {
+        let yaml = r#"
+Parameters:
+  AlertTopic:
+    Type: String
+Resources:
+  Topic:
+    Type: AWS::SNS::Topic
+  Bucket:
+    Type: AWS::S3::Bucket
+  Alarm:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      Namespace: AWS/EC2
+      MetricName: CPUUtilization
+      Statistic: Average
+      Period: 60
+      EvaluationPeriods: 1
+      Threshold: 80
+      ComparisonOperator: GreaterThanThreshold
+      AlarmActions:
+        - !Ref Topic
+        - !Ref Bucket
+        - !Ref Missing
+        - arn:aws:s3:::bucket
+      OKActions:
+        - !GetAtt Topic.TopicArn
+        - !Ref AlertTopic
+        - arn:aws:sns:us-east-1:123456789012:alerts
+      InsufficientDataActions:
+        - !GetAtt Bucket.Arn
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Alarm/Properties/AlarmActions/1",
+                "`Ref` of `Bucket` (AWS::S3::Bucket) is not an ARN an alarm can act on",
+            ),
+            Diagnostic::error(
+                "Resources/Alarm/Properties/AlarmActions/2",
+                "alarm action `Missing` does not exist",
+            ),
+            Diagnostic::error(
+                "Resources/Alarm/Properties/AlarmActions/3",
+                "an alarm cannot act on a `s3` ARN",
+            ),
+            Diagnostic::error(
+                "Resources/Alarm/Properties/InsufficientDataActions/0",
+                "`Bucket.Arn` (AWS::S3::Bucket) is not an ARN an alarm can act on",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_alarm_actions_by_resource_type() {
+        let yaml = r#"
+Resources:
+  ScaleOut:
+    Type: AWS::ApplicationAutoScaling::ScalingPolicy
+    Properties:
+      PolicyName: scale-out
+      PolicyType: StepScaling
+  Handler:
+    Type: AWS::Lambda::Function
+    Properties:
+      Role: arn:aws:iam::123456789012:role/handler
+      Code: {ZipFile: "exports.handler = async () => {}"}
+      Runtime: nodejs20.x
+      Handler: index.handler
+  Alarm:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      Namespace: AWS/ECS
+      MetricName: CPUUtilization
+      Statistic: Average
+      Period: 60
+      EvaluationPeriods: 1
+      Threshold: 80
+      ComparisonOperator: GreaterThanThreshold
+      AlarmActions:
+        - !Ref ScaleOut
+        - !GetAtt Handler.Arn
+        - !Ref Handler
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Alarm/Properties/AlarmActions/2",
+            "`Ref` of `Handler` (AWS::Lambda::Function) is not an ARN an alarm can act on",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_alarm_ec2_actions() {
+        let yaml = r#"
+Resources:
+  Alarm:
+    Type: AWS::CloudWatch::Alarm
+    Properties:
+      Namespace: AWS/EC2
+      MetricName: StatusCheckFailed_System
+      Dimensions:
+        - Name: InstanceId
+          Value: i-0123456789abcdef0
+      Statistic: Maximum
+      Period: 60
+      EvaluationPeriods: 2
+      Threshold: 0
+      ComparisonOperator: GreaterThanThreshold
+      AlarmActions:
+        - arn:aws:swf:us-east-1:123456789012:action/actions/AWS_EC2.InstanceId.Reboot/1.0
+        - arn:aws:swf:us-east-1:123456789012:action/actions/AWS_EC2.InstanceId.Stop/1.0
+        - arn:aws:swf:us-east-1:123456789012:action/actions/AWS_EC2.InstanceId.Terminate/1.0
+        - arn:aws:automate:us-east-1:ec2:recover
+      OKActions:
+        - !Sub arn:aws:swf:${AWS::Region}:${AWS::AccountId}:action/actions/AWS_EC2.InstanceId.Reboot/1.0
+"#;
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(Vec::<Diagnostic>::new(), template.validate());
+    }
+}
@@ -0,0 +1,430 @@
+use crate::{
+    diagnostic::Diagnostic,
+    tag::Tag,
+    validate::{check_one_of, Validate},
+    value::{Node, Value},
+    Template,
+};
+use serde::{Deserialize, Serialize};
+
+const ATTRIBUTE_TYPES: &[&str] = &["B", "N", "S"];
+
+const BILLING_MODES: &[&str] = &["PAY_PER_REQUEST", "PROVISIONED"];
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Table {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attribute_definitions: Option<Node<Vec<Node<AttributeDefinition>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub billing_mode: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deletion_protection_enabled: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub global_secondary_indexes: Option<Node<Vec<Node<SecondaryIndex>>>>,
+    pub key_schema: Node<Vec<Node<KeySchema>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_secondary_indexes: Option<Node<Vec<Node<SecondaryIndex>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub point_in_time_recovery_specification: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provisioned_throughput: Option<Value>,
+    #[serde(rename = "SSESpecification", skip_serializing_if = "Option::is_none")]
+    pub sse_specification: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_specification: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_to_live_specification: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct AttributeDefinition {
+    pub attribute_name: Value,
+    pub attribute_type: Value,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct KeySchema {
+    pub attribute_name: Value,
+    pub key_type: Value,
+}
+
+/// A global or local secondary index.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct SecondaryIndex {
+    pub index_name: Value,
+    pub key_schema: Node<Vec<Node<KeySchema>>>,
+    pub projection: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provisioned_throughput: Option<Value>,
+}
+
+/// Reports a key schema that is not a `HASH` key optionally followed by a
+/// `RANGE` key.
+fn check_key_schema(key_schema: &[&KeySchema], path: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let key_types: Option<Vec<&str>> = key_schema.iter().map(|key| key.key_type.as_str()).collect();
+    match key_types.as_deref() {
+        Some(["HASH"] | ["HASH", "RANGE"]) | None => {}
+        Some(_) => diagnostics.push(Diagnostic::error(
+            path,
+            "a key schema must be a `HASH` key, optionally followed by a `RANGE` key",
+        )),
+    }
+}
+
+impl Validate for Table {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        let definitions = self.attribute_definitions.iter().flat_map(Node::literals);
+        for (index, definition) in definitions {
+            check_one_of(
+                &definition.attribute_type,
+                ATTRIBUTE_TYPES,
+                "attribute type",
+                &format!("{}/AttributeDefinitions/{}/AttributeType", path, index),
+                diagnostics,
+            );
+        }
+
+        // A computed index or key schema may use any attribute.
+        let mut all_schemas = true;
+        let mut written = vec![(format!("{}/KeySchema", path), &self.key_schema)];
+        for (name, indexes) in [
+            ("GlobalSecondaryIndexes", &self.global_secondary_indexes),
+            ("LocalSecondaryIndexes", &self.local_secondary_indexes),
+        ] {
+            let Some(indexes) = indexes else {
+                continue;
+            };
+            all_schemas &= indexes.all_literals().is_some();
+            for (index, secondary) in indexes.literals() {
+                let path = format!("{}/{}/{}/KeySchema", path, name, index);
+                written.push((path, &secondary.key_schema));
+            }
+        }
+        let mut schemas = Vec::new();
+        for (path, key_schema) in written {
+            match key_schema.all_literals() {
+                Some(key_schema) => schemas.push((path, key_schema)),
+                None => all_schemas = false,
+            }
+        }
+        for (path, key_schema) in &schemas {
+            check_key_schema(key_schema, path, diagnostics);
+        }
+
+        // Attributes must be defined exactly when a key schema uses them.
+        let defined: Option<Vec<&str>> = match &self.attribute_definitions {
+            Some(definitions) => definitions.all_literals().map(|definitions| {
+                definitions
+                    .iter()
+                    .filter_map(|definition| definition.attribute_name.as_str())
+                    .collect()
+            }),
+            None => Some(Vec::new()),
+        };
+        let mut used = Vec::new();
+        for (path, key_schema) in &schemas {
+            for (index, key) in key_schema.iter().enumerate() {
+                let Some(name) = key.attribute_name.as_str() else {
+                    continue;
+                };
+                used.push(name);
+                if defined
+                    .as_ref()
+                    .is_some_and(|defined| !defined.contains(&name))
+                {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/{}/AttributeName", path, index),
+                        format!("key attribute `{}` is not in `AttributeDefinitions`", name),
+                    ));
+                }
+            }
+        }
+        if all_schemas {
+            let definitions = self.attribute_definitions.iter().flat_map(Node::literals);
+            for (index, definition) in definitions {
+                let Some(name) = definition.attribute_name.as_str() else {
+                    continue;
+                };
+                if !used.contains(&name) {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/AttributeDefinitions/{}", path, index),
+                        format!(
+                            "attribute `{}` is defined but is not a key of the table or an index",
+                            name
+                        ),
+                    ));
+                }
+            }
+        }
+
+        fn hash_key(key_schema: &Node<Vec<Node<KeySchema>>>) -> Option<&str> {
+            key_schema
+                .items()
+                .first()?
+                .literal()?
+                .attribute_name
+                .as_str()
+        }
+        let table_hash_key = hash_key(&self.key_schema);
+        let locals = self.local_secondary_indexes.iter().flat_map(Node::literals);
+        for (index, local) in locals {
+            let local_hash_key = hash_key(&local.key_schema);
+            if table_hash_key.is_some()
+                && local_hash_key.is_some()
+                && local_hash_key != table_hash_key
+            {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/LocalSecondaryIndexes/{}/KeySchema", path, index),
+                    "a local secondary index must use the table's `HASH` key",
+                ));
+            }
+        }
+
+        if let Some(billing_mode) = &self.billing_mode {
+            check_one_of(
+                billing_mode,
+                BILLING_MODES,
+                "billing mode",
+                &format!("{}/BillingMode", path),
+                diagnostics,
+            );
+        }
+        let billing_mode = match &self.billing_mode {
+            Some(billing_mode) => billing_mode.as_str(),
+            None => Some("PROVISIONED"),
+        };
+        match billing_mode {
+            Some("PROVISIONED") if self.provisioned_throughput.is_none() => {
+                diagnostics.push(Diagnostic::error(
+                    path,
+                    "`ProvisionedThroughput` is required in `PROVISIONED` mode",
+                ))
+            }
+            Some("PAY_PER_REQUEST") if self.provisioned_throughput.is_some() => {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/ProvisionedThroughput", path),
+                    "`ProvisionedThroughput` cannot be used in `PAY_PER_REQUEST` mode",
+                ))
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{diagnostic::Diagnostic, document::Format, Template};
+
+    #[test]
+    fn test_validate_table_attribute_types() {
+        let yaml = r#"
+Resources:
+  Table:
+    Type: AWS::DynamoDB::Table
+    Properties:
+      BillingMode: PAY_PER_REQUEST
+      AttributeDefinitions:
+        - {AttributeName: pk, AttributeType: String}
+      KeySchema:
+        - {AttributeName: pk, KeyType: HASH}
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Table/Properties/AttributeDefinitions/0/AttributeType",
+            "`String` is not a valid attribute type, expected one of B, N, S",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_table_key_schema_order() {
+        let yaml = r#"
+Resources:
+  Table:
+    Type: AWS::DynamoDB::Table
+    Properties:
+      BillingMode: PAY_PER_REQUEST
+      AttributeDefinitions:
+        - {AttributeName: pk, AttributeType: S}
+        - {AttributeName: sk, AttributeType: S}
+      KeySchema:
+        - {AttributeName: sk, KeyType: RANGE}
+        - {AttributeName: pk, KeyType: HASH}
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Table/Properties/KeySchema",
+            "a key schema must be a `HASH` key, optionally followed by a `RANGE` key",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_table_key_attributes_are_defined() {
+        let yaml = r#"
+Resources:
+  Table:
+    Type: AWS::DynamoDB::Table
+    Properties:
+      BillingMode: PAY_PER_REQUEST
+      AttributeDefinitions:
+        - {AttributeName: pk, AttributeType: S}
+        - {AttributeName: status, AttributeType: S}
+      KeySchema:
+        - {AttributeName: pk, KeyType: HASH}
+      GlobalSecondaryIndexes:
+        - IndexName: byStatus
+          KeySchema:
+            - {AttributeName: status, KeyType: HASH}
+            - {AttributeName: created, KeyType: RANGE}
+          Projection: {ProjectionType: ALL}
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Table/Properties/GlobalSecondaryIndexes/0/KeySchema/1/AttributeName",
+            "key attribute `created` is not in `AttributeDefinitions`",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_table_unused_attribute_definitions() {
+        let yaml = r#"
+Resources:
+  Table:
+    Type: AWS::DynamoDB::Table
+    Properties:
+      BillingMode: PAY_PER_REQUEST
+      AttributeDefinitions:
+        - {AttributeName: pk, AttributeType: S}
+        - {AttributeName: unused, AttributeType: N}
+      KeySchema:
+        - {AttributeName: pk, KeyType: HASH}
+  Conditional:
+    Type: AWS::DynamoDB::Table
+    Properties:
+      BillingMode: PAY_PER_REQUEST
+      AttributeDefinitions:
+        - {AttributeName: pk, AttributeType: S}
+        - {AttributeName: status, AttributeType: S}
+      KeySchema:
+        - {AttributeName: pk, KeyType: HASH}
+      GlobalSecondaryIndexes:
+        - !If
+          - HasStatusIndex
+          - IndexName: byStatus
+            KeySchema:
+              - {AttributeName: status, KeyType: HASH}
+            Projection: {ProjectionType: ALL}
+          - !Ref AWS::NoValue
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Table/Properties/AttributeDefinitions/1",
+            "attribute `unused` is defined but is not a key of the table or an index",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_local_index_hash_key() {
+        let yaml = r#"
+Resources:
+  Table:
+    Type: AWS::DynamoDB::Table
+    Properties:
+      BillingMode: PAY_PER_REQUEST
+      AttributeDefinitions:
+        - {AttributeName: pk, AttributeType: S}
+        - {AttributeName: sk, AttributeType: S}
+        - {AttributeName: other, AttributeType: S}
+      KeySchema:
+        - {AttributeName: pk, KeyType: HASH}
+        - {AttributeName: sk, KeyType: RANGE}
+      LocalSecondaryIndexes:
+        - IndexName: byOther
+          KeySchema:
+            - {AttributeName: other, KeyType: HASH}
+            - {AttributeName: sk, KeyType: RANGE}
+          Projection: {ProjectionType: KEYS_ONLY}
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Table/Properties/LocalSecondaryIndexes/0/KeySchema",
+            "a local secondary index must use the table's `HASH` key",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_table_billing_mode() {
+        let yaml = r#"
+Parameters:
+  BillingMode:
+    Type: String
+Resources:
+  Provisioned:
+    Type: AWS::DynamoDB::Table
+    Properties:
+      AttributeDefinitions:
+        - {AttributeName: id, AttributeType: S}
+      KeySchema:
+        - {AttributeName: id, KeyType: HASH}
+  OnDemand:
+    Type: AWS::DynamoDB::Table
+    Properties:
+      BillingMode: PAY_PER_REQUEST
+      ProvisionedThroughput: {ReadCapacityUnits: 5, WriteCapacityUnits: 5}
+      AttributeDefinitions:
+        - {AttributeName: id, AttributeType: S}
+      KeySchema:
+        - {AttributeName: id, KeyType: HASH}
+  Misspelled:
+    Type: AWS::DynamoDB::Table
+    Properties:
+      BillingMode: ON_DEMAND
+      AttributeDefinitions:
+        - {AttributeName: id, AttributeType: S}
+      KeySchema:
+        - {AttributeName: id, KeyType: HASH}
+  FromParameter:
+    Type: AWS::DynamoDB::Table
+    Properties:
+      BillingMode: !Ref BillingMode
+      AttributeDefinitions:
+        - {AttributeName: id, AttributeType: S}
+      KeySchema:
+        - {AttributeName: id, KeyType: HASH}
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Provisioned/Properties",
+                "`ProvisionedThroughput` is required in `PROVISIONED` mode",
+            ),
+            Diagnostic::error(
+                "Resources/OnDemand/Properties/ProvisionedThroughput",
+                "`ProvisionedThroughput` cannot be used in `PAY_PER_REQUEST` mode",
+            ),
+            Diagnostic::error(
+                "Resources/Misspelled/Properties/BillingMode",
+                "`ON_DEMAND` is not a valid billing mode, expected one of PAY_PER_REQUEST, PROVISIONED",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+}
@@ -1,20 +1,280 @@
-use serde::Deserialize;
+use crate::{
+    diagnostic::Diagnostic,
+    tag::Tag,
+    validate::{check_one_of, Validate},
+    value::{Node, Value},
+    Template,
+};
+use serde::{Deserialize, Serialize};
 
-use crate::value::Value;
+const TENANCIES: &[&str] = &["default", "dedicated", "host"];
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
+const VOLUME_TYPES: &[&str] = &["gp2", "gp3", "io1", "io2", "sc1", "st1", "standard"];
+
+/// Volume types that only accept `Iops`, and require it.
+const PROVISIONED_IOPS_VOLUME_TYPES: &[&str] = &["io1", "io2"];
+
+#[derive(Deserialize, Serialize, Debug, Default, PartialEq, Eq)]
 #[serde(rename_all = "PascalCase")]
 pub struct Ec2 {
-    pub security_groups: Option<Vec<Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security_groups: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub key_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub image_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_type: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subnet_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security_group_ids: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_data: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iam_instance_profile: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub availability_zone: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_device_mappings: Option<Node<Vec<Node<BlockDeviceMapping>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_interfaces: Option<Node<Vec<Node<NetworkInterface>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monitoring: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ebs_optimized: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenancy: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub launch_template: Option<Node<LaunchTemplateSpecification>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_ip_address: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv6_address_count: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv6_addresses: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_dest_check: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disable_api_termination: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_initiated_shutdown_behavior: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placement_group_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kernel_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ramdisk_id: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct BlockDeviceMapping {
+    pub device_name: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ebs: Option<Node<Ebs>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_device: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtual_name: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Ebs {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete_on_termination: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encrypted: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iops: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kms_key_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume_size: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume_type: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct NetworkInterface {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub associate_public_ip_address: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete_on_termination: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Value>,
+    pub device_index: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_set: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_interface_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_ip_address: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secondary_private_ip_address_count: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subnet_id: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct LaunchTemplateSpecification {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub launch_template_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub launch_template_name: Option<Value>,
+    pub version: Value,
+}
+
+impl Validate for Ec2 {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        if self.image_id.is_none() && self.launch_template.is_none() {
+            diagnostics.push(Diagnostic::error(
+                path,
+                "`ImageId` is required unless `LaunchTemplate` provides it",
+            ));
+        }
+
+        // With network interfaces, the subnet, addresses and security groups
+        // belong to the interfaces.
+        if self.network_interfaces.is_some() {
+            let conflicts = [
+                ("SecurityGroups", self.security_groups.is_some()),
+                ("SecurityGroupIds", self.security_group_ids.is_some()),
+                ("SubnetId", self.subnet_id.is_some()),
+                ("PrivateIpAddress", self.private_ip_address.is_some()),
+                ("Ipv6AddressCount", self.ipv6_address_count.is_some()),
+                ("Ipv6Addresses", self.ipv6_addresses.is_some()),
+            ];
+            for (property, _) in conflicts.iter().filter(|(_, set)| *set) {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/{}", path, property),
+                    format!(
+                        "`{}` cannot be used with `NetworkInterfaces`; set it on the network interface instead",
+                        property
+                    ),
+                ));
+            }
+        }
+        if self.ipv6_address_count.is_some() && self.ipv6_addresses.is_some() {
+            diagnostics.push(Diagnostic::error(
+                format!("{}/Ipv6Addresses", path),
+                "`Ipv6Addresses` cannot be used with `Ipv6AddressCount`",
+            ));
+        }
+
+        if let Some(tenancy) = &self.tenancy {
+            check_one_of(
+                tenancy,
+                TENANCIES,
+                "tenancy",
+                &format!("{}/Tenancy", path),
+                diagnostics,
+            );
+        }
+
+        if let Some(launch_template) = self.launch_template.as_ref().and_then(Node::literal) {
+            launch_template.validate(&format!("{}/LaunchTemplate", path), diagnostics);
+        }
+
+        for (index, mapping) in self.block_device_mappings.iter().flat_map(Node::literals) {
+            mapping.validate(
+                &format!("{}/BlockDeviceMappings/{}", path, index),
+                diagnostics,
+            );
+        }
+
+        let interfaces = self.network_interfaces.iter().flat_map(Node::literals);
+        for (index, interface) in interfaces {
+            if interface.network_interface_id.is_some()
+                && (interface.subnet_id.is_some() || interface.group_set.is_some())
+            {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/NetworkInterfaces/{}", path, index),
+                    "an existing `NetworkInterfaceId` cannot be combined with `SubnetId` or `GroupSet`",
+                ));
+            }
+        }
+    }
+}
+
+impl LaunchTemplateSpecification {
+    pub fn validate(&self, path: &str, diagnostics: &mut Vec<Diagnostic>) {
+        if self.launch_template_id.is_some() == self.launch_template_name.is_some() {
+            diagnostics.push(Diagnostic::error(
+                path,
+                "exactly one of `LaunchTemplateId` and `LaunchTemplateName` is required",
+            ));
+        }
+    }
+}
+
+impl BlockDeviceMapping {
+    pub fn validate(&self, path: &str, diagnostics: &mut Vec<Diagnostic>) {
+        if self.ebs.is_some() && (self.no_device.is_some() || self.virtual_name.is_some()) {
+            diagnostics.push(Diagnostic::error(
+                path,
+                "`Ebs` cannot be used with `NoDevice` or `VirtualName`",
+            ));
+        }
+
+        let Some(ebs) = self.ebs.as_ref().and_then(Node::literal) else {
+            return;
+        };
+        let path = format!("{}/Ebs", path);
+        let volume_type = match &ebs.volume_type {
+            Some(volume_type) => volume_type.as_str(),
+            None => Some("gp2"),
+        };
+        match volume_type {
+            Some(volume_type) if !VOLUME_TYPES.contains(&volume_type) => {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/VolumeType", path),
+                    format!(
+                        "`{}` is not a valid volume type, expected one of {}",
+                        volume_type,
+                        VOLUME_TYPES.join(", ")
+                    ),
+                ));
+            }
+            Some(volume_type)
+                if PROVISIONED_IOPS_VOLUME_TYPES.contains(&volume_type) && ebs.iops.is_none() =>
+            {
+                diagnostics.push(Diagnostic::error(
+                    path,
+                    format!("`Iops` is required for `{}` volumes", volume_type),
+                ));
+            }
+            Some(volume_type @ ("gp2" | "sc1" | "st1" | "standard")) if ebs.iops.is_some() => {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/Iops", path),
+                    format!("`Iops` is not supported for `{}` volumes", volume_type),
+                ));
+            }
+            _ => {}
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::value::Value;
+    use crate::{
+        diagnostic::Diagnostic,
+        document::Format,
+        tag::Tag,
+        value::{Node, Value},
+        Template,
+    };
 
-    use super::Ec2;
+    use super::{BlockDeviceMapping, Ebs, Ec2, NetworkInterface};
 
     #[test]
     fn test_deserialize_ec2() {
@@ -33,16 +293,197 @@ mod tests {
         let expected = Ec2 {
             image_id: Some(Value::String("ami-7a11e213".to_string())),
             key_name: Some(Value::String("mykey".to_string())),
-            security_groups: Some(vec![
+            security_groups: Some(Node::Literal(vec![
                 Value::Ref {
                     r#ref: "InstanceSecurityGroup".to_string(),
                 },
                 Value::String("MyExistingSecurityGroup".to_string()),
-            ]),
+            ])),
+            ..Ec2::default()
         };
 
         let actual = serde_json::from_str(json).unwrap();
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_deserialize_ec2_full() {
+        let yaml = r#"
+ImageId: ami-7a11e213
+InstanceType: t3.micro
+IamInstanceProfile: !Ref InstanceProfile
+Monitoring: true
+UserData: !Base64 echo hello
+BlockDeviceMappings:
+  - DeviceName: /dev/xvda
+    Ebs:
+      VolumeSize: 20
+      VolumeType: gp3
+      Encrypted: true
+NetworkInterfaces:
+  - DeviceIndex: "0"
+    AssociatePublicIpAddress: true
+    SubnetId: !Ref Subnet
+    GroupSet: [!Ref InstanceSecurityGroup]
+Tags:
+  - Key: Name
+    Value: !Ref AWS::StackName
+"#;
+        let expected = Ec2 {
+            image_id: Some(Value::String("ami-7a11e213".to_string())),
+            instance_type: Some(Value::String("t3.micro".to_string())),
+            iam_instance_profile: Some(Value::Ref {
+                r#ref: "InstanceProfile".to_string(),
+            }),
+            monitoring: Some(Value::Bool(true)),
+            user_data: Some(Value::Base64 {
+                base64: Box::new(Value::String("echo hello".to_string())),
+            }),
+            block_device_mappings: Some(Node::Literal(vec![Node::Literal(BlockDeviceMapping {
+                device_name: Value::String("/dev/xvda".to_string()),
+                ebs: Some(Node::Literal(Ebs {
+                    delete_on_termination: None,
+                    encrypted: Some(Value::Bool(true)),
+                    iops: None,
+                    kms_key_id: None,
+                    snapshot_id: None,
+                    volume_size: Some(Value::Number(20)),
+                    volume_type: Some(Value::String("gp3".to_string())),
+                })),
+                no_device: None,
+                virtual_name: None,
+            })])),
+            network_interfaces: Some(Node::Literal(vec![Node::Literal(NetworkInterface {
+                associate_public_ip_address: Some(Value::Bool(true)),
+                delete_on_termination: None,
+                description: None,
+                device_index: Value::String("0".to_string()),
+                group_set: Some(Node::Literal(vec![Value::Ref {
+                    r#ref: "InstanceSecurityGroup".to_string(),
+                }])),
+                network_interface_id: None,
+                private_ip_address: None,
+                secondary_private_ip_address_count: None,
+                subnet_id: Some(Value::Ref {
+                    r#ref: "Subnet".to_string(),
+                }),
+            })])),
+            tags: Some(Node::Literal(vec![Node::Literal(Tag {
+                key: "Name".to_string(),
+                value: Value::Ref {
+                    r#ref: "AWS::StackName".to_string(),
+                },
+            })])),
+            ..Ec2::default()
+        };
+
+        let document: Value = crate::yaml::from_str(yaml).unwrap();
+        let actual = serde_json::from_value(serde_json::to_value(document).unwrap()).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_validate_ec2() {
+        let yaml = r#"
+Resources:
+  Instance:
+    Type: AWS::EC2::Instance
+    Properties:
+      SecurityGroups: [default]
+      SubnetId: subnet-123
+      Tenancy: shared
+      NetworkInterfaces:
+        - DeviceIndex: 0
+          NetworkInterfaceId: eni-123
+          SubnetId: subnet-456
+      BlockDeviceMappings:
+        - DeviceName: /dev/xvda
+          Ebs:
+            VolumeType: io2
+        - DeviceName: /dev/xvdb
+          Ebs:
+            Iops: 3000
+"#;
+        let path = "Resources/Instance/Properties";
+        let expected = vec![
+            Diagnostic::error(
+                path,
+                "`ImageId` is required unless `LaunchTemplate` provides it",
+            ),
+            Diagnostic::error(
+                format!("{}/SecurityGroups", path),
+                "`SecurityGroups` cannot be used with `NetworkInterfaces`; set it on the network interface instead",
+            ),
+            Diagnostic::error(
+                format!("{}/SubnetId", path),
+                "`SubnetId` cannot be used with `NetworkInterfaces`; set it on the network interface instead",
+            ),
+            Diagnostic::error(
+                format!("{}/Tenancy", path),
+                "`shared` is not a valid tenancy, expected one of default, dedicated, host",
+            ),
+            Diagnostic::error(
+                format!("{}/BlockDeviceMappings/0/Ebs", path),
+                "`Iops` is required for `io2` volumes",
+            ),
+            Diagnostic::error(
+                format!("{}/BlockDeviceMappings/1/Ebs/Iops", path),
+                "`Iops` is not supported for `gp2` volumes",
+            ),
+            Diagnostic::error(
+                format!("{}/NetworkInterfaces/0", path),
+                "an existing `NetworkInterfaceId` cannot be combined with `SubnetId` or `GroupSet`",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_ec2_conditional_devices() {
+        let yaml = r#"
+Resources:
+  Instance:
+    Type: AWS::EC2::Instance
+    Properties:
+      ImageId: ami-7a11e213
+      LaunchTemplate: !If [UseTemplate, {LaunchTemplateId: lt-123, Version: "1"}, !Ref AWS::NoValue]
+      BlockDeviceMappings:
+        - !If
+          - HasDataVolume
+          - DeviceName: /dev/xvdb
+            Ebs:
+              Iops: 3000
+          - !Ref AWS::NoValue
+        - DeviceName: /dev/xvdc
+          Ebs: !If [Encrypt, {Encrypted: true}, !Ref AWS::NoValue]
+      NetworkInterfaces: !If
+        - Public
+        - - DeviceIndex: 0
+            AssociatePublicIpAddress: true
+        - !Ref AWS::NoValue
+"#;
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(Vec::<Diagnostic>::new(), template.validate());
+    }
+
+    #[test]
+    fn test_validate_ec2_tenancy_from_parameter() {
+        let yaml = r#"
+Parameters:
+  Tenancy:
+    Type: String
+    AllowedValues: [default, dedicated]
+Resources:
+  Instance:
+    Type: AWS::EC2::Instance
+    Properties:
+      ImageId: ami-7a11e213
+      Tenancy: !Ref Tenancy
+"#;
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(Vec::<Diagnostic>::new(), template.validate());
+    }
 }
@@ -0,0 +1,864 @@
+use super::Resource;
+use crate::{
+    diagnostic::Diagnostic,
+    tag::Tag,
+    validate::{check_one_of, Validate},
+    value::{Node, Value},
+    Template,
+};
+use serde::{Deserialize, Serialize};
+
+/// The memory sizes in MiB Fargate offers for each CPU size in CPU units, as
+/// `(cpu, smallest, largest, step)`.
+const FARGATE_SIZES: &[(i64, FargateMemory)] = &[
+    (256, FargateMemory::Listed(&[512, 1024, 2048])),
+    (512, FargateMemory::Range(1024, 4096, 1024)),
+    (1024, FargateMemory::Range(2048, 8192, 1024)),
+    (2048, FargateMemory::Range(4096, 16384, 1024)),
+    (4096, FargateMemory::Range(8192, 30720, 1024)),
+    (8192, FargateMemory::Range(16384, 61440, 4096)),
+    (16384, FargateMemory::Range(32768, 122880, 8192)),
+];
+
+/// The memory, in MiB, that Fargate offers with a CPU size.
+enum FargateMemory {
+    Listed(&'static [i64]),
+    /// The smallest and largest size, and the step between sizes.
+    Range(i64, i64, i64),
+}
+
+impl FargateMemory {
+    fn offers(&self, memory: i64) -> bool {
+        match self {
+            FargateMemory::Listed(sizes) => sizes.contains(&memory),
+            FargateMemory::Range(smallest, largest, step) => {
+                (*smallest..=*largest).contains(&memory) && (memory - smallest) % step == 0
+            }
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            FargateMemory::Listed(sizes) => {
+                let sizes: Vec<String> = sizes.iter().map(i64::to_string).collect();
+                format!("one of {} MiB", sizes.join(", "))
+            }
+            FargateMemory::Range(smallest, largest, step) => {
+                format!("{} to {} MiB in steps of {}", smallest, largest, step)
+            }
+        }
+    }
+}
+
+const LOG_DRIVERS: &[&str] = &[
+    "awsfirelens",
+    "awslogs",
+    "fluentd",
+    "gelf",
+    "journald",
+    "json-file",
+    "splunk",
+    "syslog",
+];
+
+const FARGATE_LOG_DRIVERS: &[&str] = &["awsfirelens", "awslogs", "splunk"];
+
+const NETWORK_MODES: &[&str] = &["awsvpc", "bridge", "host", "none"];
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Cluster {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capacity_providers: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cluster_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cluster_settings: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub configuration: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_capacity_provider_strategy: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct TaskDefinition {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_definitions: Option<Node<Vec<Node<ContainerDefinition>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ephemeral_storage: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_role_arn: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub family: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_mode: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placement_constraints: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requires_compatibilities: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runtime_platform: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_role_arn: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volumes: Option<Node<Vec<Value>>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContainerDefinition {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depends_on: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry_point: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub environment: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub essential: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health_check: Option<Value>,
+    pub image: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_configuration: Option<Node<LogConfiguration>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_reservation: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mount_points: Option<Node<Vec<Value>>>,
+    pub name: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port_mappings: Option<Node<Vec<Node<PortMapping>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub readonly_root_filesystem: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secrets: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub working_directory: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct PortMapping {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_protocol: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_port: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_port_range: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_port: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct LogConfiguration {
+    pub log_driver: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret_options: Option<Node<Vec<Value>>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Service {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capacity_provider_strategy: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cluster: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deployment_configuration: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desired_count: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_execute_command: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health_check_grace_period_seconds: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub launch_type: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub load_balancers: Option<Node<Vec<Node<ServiceLoadBalancer>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_configuration: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform_version: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub propagate_tags: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheduling_strategy: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_definition: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ServiceLoadBalancer {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_port: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub load_balancer_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_group_arn: Option<Value>,
+}
+
+impl TaskDefinition {
+    /// Whether the task can run on Fargate.
+    pub fn is_fargate(&self) -> bool {
+        self.requires_compatibilities
+            .iter()
+            .flat_map(Node::items)
+            .any(|compatibility| compatibility.as_str() == Some("FARGATE"))
+    }
+
+    fn container(&self, name: &str) -> Option<&ContainerDefinition> {
+        self.container_definitions
+            .iter()
+            .flat_map(Node::literals)
+            .map(|(_, container)| container)
+            .find(|container| container.name.as_str() == Some(name))
+    }
+}
+
+/// Reads a task size in CPU units or MiB, written as a number or with a
+/// `vCPU` or `GB` unit.
+fn task_size(value: &Value) -> Option<i64> {
+    if let Some(size) = value.as_integer() {
+        return Some(size);
+    }
+    let text = value.as_str()?.to_ascii_lowercase();
+    let amount = text
+        .strip_suffix("vcpu")
+        .or_else(|| text.strip_suffix("gb"))?;
+    let amount: f64 = amount.trim().parse().ok()?;
+    Some((amount * 1024.0) as i64)
+}
+
+impl Validate for TaskDefinition {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        if let Some(network_mode) = &self.network_mode {
+            check_one_of(
+                network_mode,
+                NETWORK_MODES,
+                "network mode",
+                &format!("{}/NetworkMode", path),
+                diagnostics,
+            );
+        }
+        let network_mode = self.network_mode.as_ref().map(Value::as_str);
+        let fargate = self.is_fargate();
+
+        if fargate {
+            match network_mode {
+                Some(Some("awsvpc")) | Some(None) => {}
+                _ => diagnostics.push(Diagnostic::error(
+                    path,
+                    "Fargate tasks require `NetworkMode: awsvpc`",
+                )),
+            }
+            match (&self.cpu, &self.memory) {
+                (Some(cpu), Some(memory)) => {
+                    if let (Some(cpu), Some(memory)) = (task_size(cpu), task_size(memory)) {
+                        let size = FARGATE_SIZES.iter().find(|(size, ..)| *size == cpu);
+                        match size {
+                            None => diagnostics.push(Diagnostic::error(
+                                format!("{}/Cpu", path),
+                                format!(
+                                    "Fargate does not offer {} CPU units, expected one of {}",
+                                    cpu,
+                                    FARGATE_SIZES
+                                        .iter()
+                                        .map(|(cpu, ..)| cpu.to_string())
+                                        .collect::<Vec<_>>()
+                                        .join(", ")
+                                ),
+                            )),
+                            Some((_, sizes)) if !sizes.offers(memory) => {
+                                diagnostics.push(Diagnostic::error(
+                                    format!("{}/Memory", path),
+                                    format!(
+                                    "Fargate does not offer {} MiB with {} CPU units, expected {}",
+                                    memory,
+                                    cpu,
+                                    sizes.describe()
+                                ),
+                                ))
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                _ => diagnostics.push(Diagnostic::error(
+                    path,
+                    "Fargate tasks require task-level `Cpu` and `Memory`",
+                )),
+            }
+        }
+
+        let mut names = Vec::new();
+        let containers = self.container_definitions.iter().flat_map(Node::literals);
+        for (index, container) in containers {
+            let path = format!("{}/ContainerDefinitions/{}", path, index);
+            if let Some(name) = container.name.as_str() {
+                if names.contains(&name) {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/Name", path),
+                        format!("container name `{}` is used more than once", name),
+                    ));
+                }
+                names.push(name);
+            }
+
+            if let Some(log) = container.log_configuration.as_ref().and_then(Node::literal) {
+                let drivers = if fargate {
+                    FARGATE_LOG_DRIVERS
+                } else {
+                    LOG_DRIVERS
+                };
+                let kind = if fargate {
+                    "Fargate log driver"
+                } else {
+                    "log driver"
+                };
+                check_one_of(
+                    &log.log_driver,
+                    drivers,
+                    kind,
+                    &format!("{}/LogConfiguration/LogDriver", path),
+                    diagnostics,
+                );
+            }
+
+            if network_mode != Some(Some("awsvpc")) && !fargate {
+                continue;
+            }
+            let mappings = container.port_mappings.iter().flat_map(Node::literals);
+            for (index, mapping) in mappings {
+                let container_port = mapping.container_port.as_ref().and_then(Value::as_integer);
+                let host_port = mapping.host_port.as_ref().and_then(Value::as_integer);
+                if let (Some(container_port), Some(host_port)) = (container_port, host_port) {
+                    if container_port != host_port {
+                        diagnostics.push(Diagnostic::error(
+                            format!("{}/PortMappings/{}/HostPort", path, index),
+                            format!(
+                                "with `awsvpc`, `HostPort` must equal `ContainerPort` {}, not {}",
+                                container_port, host_port
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Validate for Service {
+    fn validate(&self, path: &str, template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        let task_definition = match &self.task_definition {
+            Some(Value::Ref { r#ref }) => match template.resource(r#ref) {
+                Some(Resource::TaskDefinition(task)) => task.properties.as_ref(),
+                _ => None,
+            },
+            _ => None,
+        };
+        let Some(task_definition) = task_definition else {
+            return;
+        };
+
+        let awsvpc = task_definition
+            .network_mode
+            .as_ref()
+            .and_then(Value::as_str)
+            == Some("awsvpc");
+        if awsvpc && self.network_configuration.is_none() {
+            diagnostics.push(Diagnostic::error(
+                path,
+                "`NetworkConfiguration` is required for a task definition with `awsvpc` networking",
+            ));
+        }
+        if self.launch_type.as_ref().and_then(Value::as_str) == Some("FARGATE")
+            && !task_definition.is_fargate()
+        {
+            diagnostics.push(Diagnostic::error(
+                format!("{}/LaunchType", path),
+                "the task definition does not list `FARGATE` in `RequiresCompatibilities`",
+            ));
+        }
+
+        let load_balancers = self.load_balancers.iter().flat_map(Node::literals);
+        for (index, load_balancer) in load_balancers {
+            let path = format!("{}/LoadBalancers/{}", path, index);
+            let Some(name) = load_balancer
+                .container_name
+                .as_ref()
+                .and_then(Value::as_str)
+            else {
+                continue;
+            };
+            let Some(container) = task_definition.container(name) else {
+                // A computed container may be the one named.
+                let containers = task_definition.container_definitions.as_ref();
+                if containers.is_some_and(|containers| containers.all_literals().is_none()) {
+                    continue;
+                }
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/ContainerName", path),
+                    format!("the task definition has no container `{}`", name),
+                ));
+                continue;
+            };
+            let Some(port) = load_balancer
+                .container_port
+                .as_ref()
+                .and_then(Value::as_integer)
+            else {
+                continue;
+            };
+            // A computed mapping may map the port.
+            let mapped = match &container.port_mappings {
+                Some(mappings) => mappings.all_literals().is_none_or(|mappings| {
+                    mappings.iter().any(|mapping| {
+                        mapping.container_port.as_ref().and_then(Value::as_integer) == Some(port)
+                    })
+                }),
+                None => false,
+            };
+            if !mapped {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/ContainerPort", path),
+                    format!("container `{}` has no port mapping for port {}", name, port),
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::task_size;
+    use crate::{diagnostic::Diagnostic, document::Format, value::Value, Template};
+
+    #[test]
+    fn test_task_size() {
+        let size = |text: &str| task_size(&Value::String(text.to_string()));
+        assert_eq!(Some(256), size("256"));
+        assert_eq!(Some(512), size(".5 vCPU"));
+        assert_eq!(Some(2048), size("2 vCPU"));
+        assert_eq!(Some(3072), size("3GB"));
+        assert_eq!(None, size("lots"));
+    }
+
+    #[test]
+    fn test_validate_task_network_mode() {
+        let yaml = r#"
+Parameters:
+  NetworkMode:
+    Type: String
+Resources:
+  Misspelled:
+    Type: AWS::ECS::TaskDefinition
+    Properties:
+      NetworkMode: nat
+  Bridge:
+    Type: AWS::ECS::TaskDefinition
+    Properties:
+      RequiresCompatibilities: [FARGATE]
+      NetworkMode: bridge
+      Cpu: 256
+      Memory: 512
+  FromParameter:
+    Type: AWS::ECS::TaskDefinition
+    Properties:
+      RequiresCompatibilities: [FARGATE]
+      NetworkMode: !Ref NetworkMode
+      Cpu: 256
+      Memory: 512
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Misspelled/Properties/NetworkMode",
+                "`nat` is not a valid network mode, expected one of awsvpc, bridge, host, none",
+            ),
+            Diagnostic::error(
+                "Resources/Bridge/Properties",
+                "Fargate tasks require `NetworkMode: awsvpc`",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_task_fargate_size() {
+        let yaml = r#"
+Parameters:
+  Cpu:
+    Type: String
+Resources:
+  Unsized:
+    Type: AWS::ECS::TaskDefinition
+    Properties:
+      RequiresCompatibilities: [FARGATE]
+      NetworkMode: awsvpc
+      Memory: 512
+  OddCpu:
+    Type: AWS::ECS::TaskDefinition
+    Properties:
+      RequiresCompatibilities: [FARGATE]
+      NetworkMode: awsvpc
+      Cpu: 3000
+      Memory: 8192
+  SmallMemory:
+    Type: AWS::ECS::TaskDefinition
+    Properties:
+      RequiresCompatibilities: [FARGATE]
+      NetworkMode: awsvpc
+      Cpu: 512
+      Memory: 512
+  WithUnits:
+    Type: AWS::ECS::TaskDefinition
+    Properties:
+      RequiresCompatibilities: [FARGATE]
+      NetworkMode: awsvpc
+      Cpu: 1 vCPU
+      Memory: 3 GB
+  FromParameter:
+    Type: AWS::ECS::TaskDefinition
+    Properties:
+      RequiresCompatibilities: [FARGATE]
+      NetworkMode: awsvpc
+      Cpu: !Ref Cpu
+      Memory: 512
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Unsized/Properties",
+                "Fargate tasks require task-level `Cpu` and `Memory`",
+            ),
+            Diagnostic::error(
+                "Resources/OddCpu/Properties/Cpu",
+                "Fargate does not offer 3000 CPU units, expected one of 256, 512, 1024, 2048, 4096, 8192, 16384",
+            ),
+            Diagnostic::error(
+                "Resources/SmallMemory/Properties/Memory",
+                "Fargate does not offer 512 MiB with 512 CPU units, expected 1024 to 4096 MiB in steps of 1024",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_fargate_memory_for_quarter_vcpu() {
+        let yaml = r#"
+Resources:
+  Task:
+    Type: AWS::ECS::TaskDefinition
+    Properties:
+      RequiresCompatibilities: [FARGATE]
+      NetworkMode: awsvpc
+      Cpu: 256
+      Memory: 1536
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Task/Properties/Memory",
+            "Fargate does not offer 1536 MiB with 256 CPU units, expected one of 512, 1024, 2048 MiB",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_task_log_driver() {
+        let yaml = r#"
+Parameters:
+  LogDriver:
+    Type: String
+Resources:
+  Fargate:
+    Type: AWS::ECS::TaskDefinition
+    Properties:
+      RequiresCompatibilities: [FARGATE]
+      NetworkMode: awsvpc
+      Cpu: 256
+      Memory: 512
+      ContainerDefinitions:
+        - Name: web
+          Image: nginx
+          LogConfiguration:
+            LogDriver: json-file
+  Ec2:
+    Type: AWS::ECS::TaskDefinition
+    Properties:
+      ContainerDefinitions:
+        - Name: files
+          Image: nginx
+          LogConfiguration:
+            LogDriver: json-file
+        - Name: cloud
+          Image: nginx
+          LogConfiguration:
+            LogDriver: cloudwatch
+        - Name: chosen
+          Image: nginx
+          LogConfiguration:
+            LogDriver: !Ref LogDriver
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Fargate/Properties/ContainerDefinitions/0/LogConfiguration/LogDriver",
+                "`json-file` is not a valid Fargate log driver, expected one of awsfirelens, awslogs, splunk",
+            ),
+            Diagnostic::error(
+                "Resources/Ec2/Properties/ContainerDefinitions/1/LogConfiguration/LogDriver",
+                "`cloudwatch` is not a valid log driver, expected one of awsfirelens, awslogs, fluentd, gelf, journald, json-file, splunk, syslog",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_task_container_names() {
+        let yaml = r#"
+Parameters:
+  Name:
+    Type: String
+Resources:
+  Task:
+    Type: AWS::ECS::TaskDefinition
+    Properties:
+      ContainerDefinitions:
+        - Name: web
+          Image: nginx
+        - Name: !Ref Name
+          Image: nginx
+        - Name: web
+          Image: sidecar
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Task/Properties/ContainerDefinitions/2/Name",
+            "container name `web` is used more than once",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_task_awsvpc_host_port() {
+        let yaml = r#"
+Parameters:
+  HostPort:
+    Type: Number
+Resources:
+  Awsvpc:
+    Type: AWS::ECS::TaskDefinition
+    Properties:
+      NetworkMode: awsvpc
+      ContainerDefinitions:
+        - Name: web
+          Image: nginx
+          PortMappings:
+            - {ContainerPort: 80, HostPort: 8080}
+            - {ContainerPort: 443, HostPort: 443}
+            - {ContainerPort: 8443, HostPort: !Ref HostPort}
+  Bridge:
+    Type: AWS::ECS::TaskDefinition
+    Properties:
+      NetworkMode: bridge
+      ContainerDefinitions:
+        - Name: web
+          Image: nginx
+          PortMappings:
+            - {ContainerPort: 80, HostPort: 8080}
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Awsvpc/Properties/ContainerDefinitions/0/PortMappings/0/HostPort",
+            "with `awsvpc`, `HostPort` must equal `ContainerPort` 80, not 8080",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_service_network_configuration() {
+        let yaml = r#"
+Resources:
+  Task:
+    Type: AWS::ECS::TaskDefinition
+    Properties:
+      NetworkMode: awsvpc
+  Service:
+    Type: AWS::ECS::Service
+    Properties:
+      TaskDefinition: !Ref Task
+  Configured:
+    Type: AWS::ECS::Service
+    Properties:
+      TaskDefinition: !Ref Task
+      NetworkConfiguration:
+        AwsvpcConfiguration:
+          Subnets: [subnet-123]
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Service/Properties",
+            "`NetworkConfiguration` is required for a task definition with `awsvpc` networking",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_service_launch_type() {
+        let yaml = r#"
+Parameters:
+  LaunchType:
+    Type: String
+Resources:
+  Task:
+    Type: AWS::ECS::TaskDefinition
+    Properties:
+      RequiresCompatibilities: [EC2]
+  Service:
+    Type: AWS::ECS::Service
+    Properties:
+      LaunchType: FARGATE
+      TaskDefinition: !Ref Task
+  FromParameter:
+    Type: AWS::ECS::Service
+    Properties:
+      LaunchType: !Ref LaunchType
+      TaskDefinition: !Ref Task
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Service/Properties/LaunchType",
+            "the task definition does not list `FARGATE` in `RequiresCompatibilities`",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_service_load_balancers() {
+        let yaml = r#"
+Resources:
+  Task:
+    Type: AWS::ECS::TaskDefinition
+    Properties:
+      ContainerDefinitions:
+        - Name: api
+          Image: api
+          PortMappings:
+            - ContainerPort: 8080
+  Service:
+    Type: AWS::ECS::Service
+    Properties:
+      TaskDefinition: !Ref Task
+      LoadBalancers:
+        - ContainerName: api
+          ContainerPort: 8080
+          TargetGroupArn: !Ref TargetGroup
+        - ContainerName: api
+          ContainerPort: 80
+          TargetGroupArn: !Ref TargetGroup
+        - ContainerName: worker
+          ContainerPort: 8080
+          TargetGroupArn: !Ref TargetGroup
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Service/Properties/LoadBalancers/1/ContainerPort",
+                "container `api` has no port mapping for port 80",
+            ),
+            Diagnostic::error(
+                "Resources/Service/Properties/LoadBalancers/2/ContainerName",
+                "the task definition has no container `worker`",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_service_load_balancers_computed() {
+        let yaml = r#"
+Parameters:
+  Port:
+    Type: Number
+Resources:
+  Sidecar:
+    Type: AWS::ECS::TaskDefinition
+    Properties:
+      ContainerDefinitions:
+        - Name: api
+          Image: api
+          PortMappings:
+            - !If [HasPort, {ContainerPort: 80}, !Ref AWS::NoValue]
+        - !If
+          - HasWorker
+          - Name: worker
+            Image: worker
+          - !Ref AWS::NoValue
+  Service:
+    Type: AWS::ECS::Service
+    Properties:
+      TaskDefinition: !Ref Sidecar
+      LoadBalancers:
+        - ContainerName: api
+          ContainerPort: 80
+          TargetGroupArn: !Ref TargetGroup
+        - ContainerName: worker
+          ContainerPort: 8080
+          TargetGroupArn: !Ref TargetGroup
+        - ContainerName: api
+          ContainerPort: !Ref Port
+          TargetGroupArn: !Ref TargetGroup
+  External:
+    Type: AWS::ECS::Service
+    Properties:
+      TaskDefinition: arn:aws:ecs:us-east-1:123456789012:task-definition/api:1
+      LoadBalancers:
+        - ContainerName: missing
+          ContainerPort: 80
+          TargetGroupArn: !Ref TargetGroup
+"#;
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(Vec::<Diagnostic>::new(), template.validate());
+    }
+}
@@ -0,0 +1,905 @@
+use super::{referenced_resource, Resource, ResourceContainer};
+use crate::{
+    diagnostic::Diagnostic,
+    tag::Tag,
+    validate::{check_boolean, check_one_of, check_range, Validate},
+    value::{Node, Value},
+    Template,
+};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+const LOAD_BALANCER_TYPES: &[&str] = &["application", "gateway", "network"];
+
+const SCHEMES: &[&str] = &["internal", "internet-facing"];
+
+const APPLICATION_PROTOCOLS: &[&str] = &["HTTP", "HTTPS"];
+
+const NETWORK_PROTOCOLS: &[&str] = &["TCP", "TCP_UDP", "TLS", "UDP"];
+
+const HEALTH_CHECK_PROTOCOLS: &[&str] = &["HTTP", "HTTPS", "TCP"];
+
+const TARGET_TYPES: &[&str] = &["alb", "instance", "ip", "lambda"];
+
+const PORTS: RangeInclusive<i64> = 1..=65535;
+
+const PRIORITIES: RangeInclusive<i64> = 1..=50000;
+
+const HEALTH_CHECK_INTERVALS: RangeInclusive<i64> = 5..=300;
+
+const HEALTH_CHECK_TIMEOUTS: RangeInclusive<i64> = 2..=120;
+
+const THRESHOLD_COUNTS: RangeInclusive<i64> = 2..=10;
+
+/// An `AWS::ElasticLoadBalancingV2::LoadBalancer`: an application, network or
+/// gateway load balancer.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct LoadBalancerV2 {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_address_type: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub load_balancer_attributes: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security_groups: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subnet_mappings: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subnets: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+    #[serde(rename = "Type", skip_serializing_if = "Option::is_none")]
+    pub load_balancer_type: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Listener {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alpn_policy: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certificates: Option<Node<Vec<Node<Certificate>>>>,
+    pub default_actions: Node<Vec<Node<Action>>>,
+    pub load_balancer_arn: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ssl_policy: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Certificate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certificate_arn: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Action {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authenticate_cognito_config: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authenticate_oidc_config: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fixed_response_config: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forward_config: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_config: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_group_arn: Option<Value>,
+    #[serde(rename = "Type")]
+    pub action_type: Value,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ListenerRule {
+    pub actions: Node<Vec<Node<Action>>>,
+    pub conditions: Node<Vec<Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub listener_arn: Option<Value>,
+    pub priority: Value,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct TargetGroup {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health_check_enabled: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health_check_interval_seconds: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health_check_path: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health_check_port: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health_check_protocol: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health_check_timeout_seconds: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub healthy_threshold_count: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_address_type: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matcher: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_group_attributes: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_type: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub targets: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unhealthy_threshold_count: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vpc_id: Option<Value>,
+}
+
+impl LoadBalancerV2 {
+    /// The literal type of the load balancer, `application` by default.
+    pub fn load_balancer_type(&self) -> Option<&str> {
+        match &self.load_balancer_type {
+            Some(load_balancer_type) => load_balancer_type.as_str(),
+            None => Some("application"),
+        }
+    }
+
+    /// Whether the scheme makes the load balancer face the internet, which is
+    /// the default.
+    pub fn is_internet_facing(&self) -> Option<bool> {
+        match &self.scheme {
+            Some(scheme) => Some(scheme.as_str()? != "internal"),
+            None => Some(true),
+        }
+    }
+}
+
+impl Validate for LoadBalancerV2 {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        if let Some(load_balancer_type) = &self.load_balancer_type {
+            check_one_of(
+                load_balancer_type,
+                LOAD_BALANCER_TYPES,
+                "load balancer type",
+                &format!("{}/Type", path),
+                diagnostics,
+            );
+        }
+        if let Some(scheme) = &self.scheme {
+            check_one_of(
+                scheme,
+                SCHEMES,
+                "scheme",
+                &format!("{}/Scheme", path),
+                diagnostics,
+            );
+        }
+
+        match (&self.subnets, &self.subnet_mappings) {
+            (Some(_), Some(_)) => diagnostics.push(Diagnostic::error(
+                path,
+                "`Subnets` cannot be used with `SubnetMappings`",
+            )),
+            (Some(Node::Literal(subnets)), None)
+                if subnets.len() < 2 && self.load_balancer_type() == Some("application") =>
+            {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/Subnets", path),
+                    "an application load balancer needs subnets in at least two Availability Zones",
+                ))
+            }
+            _ => {}
+        }
+        if self.load_balancer_type() == Some("gateway") && self.security_groups.is_some() {
+            diagnostics.push(Diagnostic::error(
+                format!("{}/SecurityGroups", path),
+                "a gateway load balancer cannot have security groups",
+            ));
+        }
+    }
+}
+
+impl Listener {
+    /// The load balancer in this template the listener belongs to.
+    fn load_balancer<'a>(&self, template: &'a Template) -> Option<&'a LoadBalancerV2> {
+        match template.resource(referenced_resource(&self.load_balancer_arn)?)? {
+            Resource::LoadBalancerV2(load_balancer) => Some(&load_balancer.properties),
+            _ => None,
+        }
+    }
+}
+
+impl Validate for Listener {
+    fn validate(&self, path: &str, template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        check_range(
+            &self.port,
+            PORTS,
+            "port",
+            &format!("{}/Port", path),
+            diagnostics,
+        );
+
+        let load_balancer_type = self
+            .load_balancer(template)
+            .and_then(LoadBalancerV2::load_balancer_type);
+        let protocols = match load_balancer_type {
+            Some("application") => Some((
+                APPLICATION_PROTOCOLS,
+                "protocol for an application load balancer",
+            )),
+            Some("network") => Some((NETWORK_PROTOCOLS, "protocol for a network load balancer")),
+            Some("gateway") => {
+                if self.port.is_some() || self.protocol.is_some() {
+                    diagnostics.push(Diagnostic::error(
+                        path,
+                        "a gateway load balancer listener takes no `Port` or `Protocol`",
+                    ));
+                }
+                None
+            }
+            _ => None,
+        };
+        if let (Some((protocols, kind)), Some(protocol)) = (protocols, &self.protocol) {
+            check_one_of(
+                protocol,
+                protocols,
+                kind,
+                &format!("{}/Protocol", path),
+                diagnostics,
+            );
+        }
+
+        let Some(protocol) = self.protocol.as_ref().and_then(Value::as_str) else {
+            return;
+        };
+        let has_certificate = self.certificates.as_ref().is_some_and(|certificates| {
+            certificates
+                .literal()
+                .is_none_or(|certificates| !certificates.is_empty())
+        });
+        match protocol {
+            "HTTPS" | "TLS" if !has_certificate => diagnostics.push(Diagnostic::error(
+                path,
+                format!("an `{}` listener requires `Certificates`", protocol),
+            )),
+            "HTTP" | "TCP" | "UDP" | "TCP_UDP" => {
+                for (name, set) in [
+                    ("Certificates", self.certificates.is_some()),
+                    ("SslPolicy", self.ssl_policy.is_some()),
+                ] {
+                    if set {
+                        diagnostics.push(Diagnostic::error(
+                            format!("{}/{}", path, name),
+                            format!("`{}` cannot be used with a `{}` listener", name, protocol),
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Validate for ListenerRule {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        check_range(
+            &Some(self.priority.clone()),
+            PRIORITIES,
+            "priority",
+            &format!("{}/Priority", path),
+            diagnostics,
+        );
+    }
+}
+
+impl Validate for TargetGroup {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        if let Some(target_type) = &self.target_type {
+            check_one_of(
+                target_type,
+                TARGET_TYPES,
+                "target type",
+                &format!("{}/TargetType", path),
+                diagnostics,
+            );
+        }
+        let target_type = match &self.target_type {
+            Some(target_type) => target_type.as_str(),
+            None => Some("instance"),
+        };
+        if target_type.is_some_and(|target_type| target_type != "lambda") {
+            for (name, value) in [
+                ("Port", &self.port),
+                ("Protocol", &self.protocol),
+                ("VpcId", &self.vpc_id),
+            ] {
+                if value.is_none() {
+                    diagnostics.push(Diagnostic::error(
+                        path,
+                        format!("`{}` is required unless `TargetType` is `lambda`", name),
+                    ));
+                }
+            }
+        }
+        check_range(
+            &self.port,
+            PORTS,
+            "port",
+            &format!("{}/Port", path),
+            diagnostics,
+        );
+
+        if let Some(enabled) = &self.health_check_enabled {
+            check_boolean(
+                enabled,
+                &format!("{}/HealthCheckEnabled", path),
+                diagnostics,
+            );
+        }
+        if let Some(protocol) = &self.health_check_protocol {
+            check_one_of(
+                protocol,
+                HEALTH_CHECK_PROTOCOLS,
+                "health check protocol",
+                &format!("{}/HealthCheckProtocol", path),
+                diagnostics,
+            );
+        }
+        let ranges = [
+            (
+                "HealthCheckIntervalSeconds",
+                &self.health_check_interval_seconds,
+                HEALTH_CHECK_INTERVALS,
+                "health check interval in seconds",
+            ),
+            (
+                "HealthCheckTimeoutSeconds",
+                &self.health_check_timeout_seconds,
+                HEALTH_CHECK_TIMEOUTS,
+                "health check timeout in seconds",
+            ),
+            (
+                "HealthyThresholdCount",
+                &self.healthy_threshold_count,
+                THRESHOLD_COUNTS,
+                "healthy threshold count",
+            ),
+            (
+                "UnhealthyThresholdCount",
+                &self.unhealthy_threshold_count,
+                THRESHOLD_COUNTS,
+                "unhealthy threshold count",
+            ),
+        ];
+        for (name, value, range, what) in ranges {
+            check_range(
+                value,
+                range,
+                what,
+                &format!("{}/{}", path, name),
+                diagnostics,
+            );
+        }
+        let interval = self
+            .health_check_interval_seconds
+            .as_ref()
+            .and_then(Value::as_integer);
+        let timeout = self
+            .health_check_timeout_seconds
+            .as_ref()
+            .and_then(Value::as_integer);
+        if let (Some(interval), Some(timeout)) = (interval, timeout) {
+            if timeout >= interval {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/HealthCheckTimeoutSeconds", path),
+                    format!(
+                        "the health check timeout of {} seconds must be shorter than the interval of {}",
+                        timeout, interval
+                    ),
+                ));
+            }
+        }
+
+        // Health checks use the target protocol unless told otherwise.
+        let protocol = self
+            .health_check_protocol
+            .as_ref()
+            .or(self.protocol.as_ref())
+            .and_then(Value::as_str);
+        if matches!(protocol, Some("TCP" | "TLS" | "UDP" | "TCP_UDP")) {
+            for (name, set) in [
+                ("HealthCheckPath", self.health_check_path.is_some()),
+                ("Matcher", self.matcher.is_some()),
+            ] {
+                if set {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/{}", path, name),
+                        format!("`{}` only applies to HTTP and HTTPS health checks", name),
+                    ));
+                }
+            }
+        }
+        if let Some(health_check_path) = self.health_check_path.as_ref().and_then(Value::as_str) {
+            if !health_check_path.starts_with('/') {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/HealthCheckPath", path),
+                    format!(
+                        "health check path `{}` must start with `/`",
+                        health_check_path
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// Reports listener rules that share a priority with an earlier rule of the
+/// same listener, which CloudFormation rejects.
+pub fn validate_listener_rules(template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+    let mut priorities: Vec<(&str, i64, &str)> = Vec::new();
+    for (name, resource) in &template.resources {
+        let Resource::ListenerRule(ResourceContainer {
+            properties: rule, ..
+        }) = resource
+        else {
+            continue;
+        };
+        let listener = rule.listener_arn.as_ref().and_then(referenced_resource);
+        let (Some(listener), Some(priority)) = (listener, rule.priority.as_integer()) else {
+            continue;
+        };
+        let earlier = priorities
+            .iter()
+            .find(|(other_listener, other, _)| *other_listener == listener && *other == priority);
+        if let Some((_, _, other_name)) = earlier {
+            diagnostics.push(Diagnostic::error(
+                format!("Resources/{}/Properties/Priority", name),
+                format!(
+                    "priority {} of listener `{}` is already used by rule `{}`",
+                    priority, listener, other_name
+                ),
+            ));
+        }
+        priorities.push((listener, priority, name));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{diagnostic::Diagnostic, document::Format, Template};
+
+    #[test]
+    fn test_validate_load_balancer_type_and_scheme() {
+        let yaml = r#"
+Parameters:
+  Scheme:
+    Type: String
+Resources:
+  Classic:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Type: classic
+      Scheme: public
+  FromParameter:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Type: network
+      Scheme: !Ref Scheme
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Classic/Properties/Type",
+                "`classic` is not a valid load balancer type, expected one of application, gateway, network",
+            ),
+            Diagnostic::error(
+                "Resources/Classic/Properties/Scheme",
+                "`public` is not a valid scheme, expected one of internal, internet-facing",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_load_balancer_subnets() {
+        let yaml = r#"
+Parameters:
+  Subnets:
+    Type: CommaDelimitedList
+Resources:
+  Application:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Subnets: [!Ref Subnet]
+  Network:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Type: network
+      Subnets: [!Ref Subnet]
+  Both:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Subnets: [subnet-1, subnet-2]
+      SubnetMappings:
+        - SubnetId: subnet-1
+  FromParameter:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Subnets: !Ref Subnets
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Application/Properties/Subnets",
+                "an application load balancer needs subnets in at least two Availability Zones",
+            ),
+            Diagnostic::error(
+                "Resources/Both/Properties",
+                "`Subnets` cannot be used with `SubnetMappings`",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_gateway_load_balancer() {
+        let yaml = r#"
+Resources:
+  Gateway:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Type: gateway
+      Subnets: [subnet-1]
+      SecurityGroups: [sg-123]
+  Listener:
+    Type: AWS::ElasticLoadBalancingV2::Listener
+    Properties:
+      LoadBalancerArn: !Ref Gateway
+      Port: 6081
+      DefaultActions:
+        - Type: forward
+          TargetGroupArn: !Ref Appliances
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Gateway/Properties/SecurityGroups",
+                "a gateway load balancer cannot have security groups",
+            ),
+            Diagnostic::error(
+                "Resources/Listener/Properties",
+                "a gateway load balancer listener takes no `Port` or `Protocol`",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_listener_protocol() {
+        let yaml = r#"
+Parameters:
+  LoadBalancer:
+    Type: String
+Resources:
+  Application:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Subnets: [subnet-1, subnet-2]
+  Network:
+    Type: AWS::ElasticLoadBalancingV2::LoadBalancer
+    Properties:
+      Type: network
+      Subnets: [subnet-1]
+  Tcp:
+    Type: AWS::ElasticLoadBalancingV2::Listener
+    Properties:
+      LoadBalancerArn: !Ref Application
+      Port: 22
+      Protocol: TCP
+      DefaultActions:
+        - Type: forward
+          TargetGroupArn: !Ref Targets
+  Http:
+    Type: AWS::ElasticLoadBalancingV2::Listener
+    Properties:
+      LoadBalancerArn: !Ref Network
+      Port: 70000
+      Protocol: HTTP
+      DefaultActions:
+        - Type: forward
+          TargetGroupArn: !Ref Targets
+  External:
+    Type: AWS::ElasticLoadBalancingV2::Listener
+    Properties:
+      LoadBalancerArn: !Ref LoadBalancer
+      Port: 22
+      Protocol: TCP
+      DefaultActions:
+        - Type: forward
+          TargetGroupArn: !Ref Targets
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Tcp/Properties/Protocol",
+                "`TCP` is not a valid protocol for an application load balancer, expected one of HTTP, HTTPS",
+            ),
+            Diagnostic::error(
+                "Resources/Http/Properties/Port",
+                "port must be between 1 and 65535, not 70000",
+            ),
+            Diagnostic::error(
+                "Resources/Http/Properties/Protocol",
+                "`HTTP` is not a valid protocol for a network load balancer, expected one of TCP, TCP_UDP, TLS, UDP",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_listener_certificates() {
+        let yaml = r#"
+Resources:
+  Https:
+    Type: AWS::ElasticLoadBalancingV2::Listener
+    Properties:
+      LoadBalancerArn: !Ref LoadBalancer
+      Port: 443
+      Protocol: HTTPS
+      DefaultActions:
+        - Type: forward
+          TargetGroupArn: !Ref Targets
+  Empty:
+    Type: AWS::ElasticLoadBalancingV2::Listener
+    Properties:
+      LoadBalancerArn: !Ref LoadBalancer
+      Port: 443
+      Protocol: HTTPS
+      Certificates: []
+      DefaultActions:
+        - Type: forward
+          TargetGroupArn: !Ref Targets
+  Conditional:
+    Type: AWS::ElasticLoadBalancingV2::Listener
+    Properties:
+      LoadBalancerArn: !Ref LoadBalancer
+      Port: 443
+      Protocol: TLS
+      Certificates: !If [HasCertificate, [{CertificateArn: !Ref Certificate}], []]
+      DefaultActions:
+        - Type: forward
+          TargetGroupArn: !Ref Targets
+  Http:
+    Type: AWS::ElasticLoadBalancingV2::Listener
+    Properties:
+      LoadBalancerArn: !Ref LoadBalancer
+      Port: 80
+      Protocol: HTTP
+      SslPolicy: ELBSecurityPolicy-TLS13-1-2-2021-06
+      DefaultActions:
+        - Type: redirect
+          RedirectConfig: {Protocol: HTTPS, Port: "443", StatusCode: HTTP_301}
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Https/Properties",
+                "an `HTTPS` listener requires `Certificates`",
+            ),
+            Diagnostic::error(
+                "Resources/Empty/Properties",
+                "an `HTTPS` listener requires `Certificates`",
+            ),
+            Diagnostic::error(
+                "Resources/Http/Properties/SslPolicy",
+                "`SslPolicy` cannot be used with a `HTTP` listener",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_listener_rule_priorities() {
+        let yaml = r#"
+Parameters:
+  Priority:
+    Type: Number
+Resources:
+  First:
+    Type: AWS::ElasticLoadBalancingV2::ListenerRule
+    Properties:
+      ListenerArn: !Ref Https
+      Priority: 10
+      Actions: [{Type: forward, TargetGroupArn: !Ref Targets}]
+      Conditions: [{Field: path-pattern, Values: [/api/*]}]
+  Second:
+    Type: AWS::ElasticLoadBalancingV2::ListenerRule
+    Properties:
+      ListenerArn: !Ref Https
+      Priority: 10
+      Actions: [{Type: forward, TargetGroupArn: !Ref Targets}]
+      Conditions: [{Field: path-pattern, Values: [/admin/*]}]
+  OtherListener:
+    Type: AWS::ElasticLoadBalancingV2::ListenerRule
+    Properties:
+      ListenerArn: !Ref Http
+      Priority: 10
+      Actions: [{Type: forward, TargetGroupArn: !Ref Targets}]
+      Conditions: [{Field: path-pattern, Values: [/api/*]}]
+  Zero:
+    Type: AWS::ElasticLoadBalancingV2::ListenerRule
+    Properties:
+      ListenerArn: !Ref Http
+      Priority: 0
+      Actions: [{Type: forward, TargetGroupArn: !Ref Targets}]
+      Conditions: [{Field: path-pattern, Values: [/*]}]
+  FromParameter:
+    Type: AWS::ElasticLoadBalancingV2::ListenerRule
+    Properties:
+      ListenerArn: !Ref Https
+      Priority: !Ref Priority
+      Actions: [{Type: forward, TargetGroupArn: !Ref Targets}]
+      Conditions: [{Field: path-pattern, Values: [/static/*]}]
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Zero/Properties/Priority",
+                "priority must be between 1 and 50000, not 0",
+            ),
+            Diagnostic::error(
+                "Resources/Second/Properties/Priority",
+                "priority 10 of listener `Https` is already used by rule `First`",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_target_group_target_type() {
+        let yaml = r#"
+Parameters:
+  TargetType:
+    Type: String
+Resources:
+  Instances:
+    Type: AWS::ElasticLoadBalancingV2::TargetGroup
+    Properties:
+      Port: 8080
+  Containers:
+    Type: AWS::ElasticLoadBalancingV2::TargetGroup
+    Properties:
+      TargetType: container
+      Port: 8080
+      Protocol: HTTP
+      VpcId: !Ref Vpc
+  Function:
+    Type: AWS::ElasticLoadBalancingV2::TargetGroup
+    Properties:
+      TargetType: lambda
+  FromParameter:
+    Type: AWS::ElasticLoadBalancingV2::TargetGroup
+    Properties:
+      TargetType: !Ref TargetType
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Instances/Properties",
+                "`Protocol` is required unless `TargetType` is `lambda`",
+            ),
+            Diagnostic::error(
+                "Resources/Instances/Properties",
+                "`VpcId` is required unless `TargetType` is `lambda`",
+            ),
+            Diagnostic::error(
+                "Resources/Containers/Properties/TargetType",
+                "`container` is not a valid target type, expected one of alb, instance, ip, lambda",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_target_group_health_check() {
+        let yaml = r#"
+Parameters:
+  Interval:
+    Type: Number
+Resources:
+  Http:
+    Type: AWS::ElasticLoadBalancingV2::TargetGroup
+    Properties:
+      Port: 8080
+      Protocol: HTTP
+      VpcId: !Ref Vpc
+      HealthCheckEnabled: "yes"
+      HealthCheckProtocol: UDP
+      HealthCheckPath: health
+      HealthCheckIntervalSeconds: 10
+      HealthCheckTimeoutSeconds: 10
+      UnhealthyThresholdCount: 1
+  Tcp:
+    Type: AWS::ElasticLoadBalancingV2::TargetGroup
+    Properties:
+      Port: 8080
+      Protocol: TCP
+      VpcId: !Ref Vpc
+      HealthCheckPath: /health
+      Matcher: {HttpCode: "200"}
+  TcpCheckingHttp:
+    Type: AWS::ElasticLoadBalancingV2::TargetGroup
+    Properties:
+      Port: 8080
+      Protocol: TCP
+      VpcId: !Ref Vpc
+      HealthCheckProtocol: HTTP
+      HealthCheckPath: /health
+      HealthCheckIntervalSeconds: !Ref Interval
+      HealthCheckTimeoutSeconds: 30
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Http/Properties/HealthCheckEnabled",
+                "expected a boolean, `true` or `false`",
+            ),
+            Diagnostic::error(
+                "Resources/Http/Properties/HealthCheckProtocol",
+                "`UDP` is not a valid health check protocol, expected one of HTTP, HTTPS, TCP",
+            ),
+            Diagnostic::error(
+                "Resources/Http/Properties/UnhealthyThresholdCount",
+                "unhealthy threshold count must be between 2 and 10, not 1",
+            ),
+            Diagnostic::error(
+                "Resources/Http/Properties/HealthCheckTimeoutSeconds",
+                "the health check timeout of 10 seconds must be shorter than the interval of 10",
+            ),
+            Diagnostic::error(
+                "Resources/Http/Properties/HealthCheckPath",
+                "`HealthCheckPath` only applies to HTTP and HTTPS health checks",
+            ),
+            Diagnostic::error(
+                "Resources/Http/Properties/HealthCheckPath",
+                "health check path `health` must start with `/`",
+            ),
+            Diagnostic::error(
+                "Resources/Tcp/Properties/HealthCheckPath",
+                "`HealthCheckPath` only applies to HTTP and HTTPS health checks",
+            ),
+            Diagnostic::error(
+                "Resources/Tcp/Properties/Matcher",
+                "`Matcher` only applies to HTTP and HTTPS health checks",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+}
@@ -0,0 +1,211 @@
+use crate::{
+    diagnostic::Diagnostic,
+    tag::Tag,
+    validate::Validate,
+    value::{Node, Value},
+    Template,
+};
+use serde::{Deserialize, Serialize};
+
+const CONNECTIVITY_TYPES: &[&str] = &["public", "private"];
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct InternetGateway {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct VpcGatewayAttachment {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub internet_gateway_id: Option<Value>,
+    pub vpc_id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vpn_gateway_id: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct NatGateway {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allocation_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connectivity_type: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_ip_address: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secondary_allocation_ids: Option<Node<Vec<Value>>>,
+    pub subnet_id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Eip {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_border_group: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_ipv4_pool: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transfer_address: Option<Value>,
+}
+
+impl Validate for VpcGatewayAttachment {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        if self.internet_gateway_id.is_some() == self.vpn_gateway_id.is_some() {
+            diagnostics.push(Diagnostic::error(
+                path,
+                "exactly one of `InternetGatewayId` and `VpnGatewayId` is required",
+            ));
+        }
+    }
+}
+
+impl Validate for NatGateway {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        let connectivity_type = match &self.connectivity_type {
+            Some(value) => match value.as_str() {
+                Some(connectivity_type) => connectivity_type,
+                None => return,
+            },
+            None => "public",
+        };
+        match connectivity_type {
+            "public" if self.allocation_id.is_none() => diagnostics.push(Diagnostic::error(
+                path,
+                "a public NAT gateway requires `AllocationId`",
+            )),
+            "private" if self.allocation_id.is_some() => diagnostics.push(Diagnostic::error(
+                format!("{}/AllocationId", path),
+                "a private NAT gateway cannot have `AllocationId`",
+            )),
+            "public" | "private" => {}
+            other => diagnostics.push(Diagnostic::error(
+                format!("{}/ConnectivityType", path),
+                format!(
+                    "`{}` is not a valid connectivity type, expected one of {}",
+                    other,
+                    CONNECTIVITY_TYPES.join(", ")
+                ),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{diagnostic::Diagnostic, document::Format, Template};
+
+    #[test]
+    fn test_validate_public_nat_gateway() {
+        let yaml = r#"
+Resources:
+  Address:
+    Type: AWS::EC2::EIP
+    Properties:
+      Domain: vpc
+  Allocated:
+    Type: AWS::EC2::NatGateway
+    Properties:
+      AllocationId: !GetAtt Address.AllocationId
+      SubnetId: subnet-123
+  Unallocated:
+    Type: AWS::EC2::NatGateway
+    Properties:
+      SubnetId: subnet-123
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Unallocated/Properties",
+            "a public NAT gateway requires `AllocationId`",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_private_nat_gateway() {
+        let yaml = r#"
+Resources:
+  Private:
+    Type: AWS::EC2::NatGateway
+    Properties:
+      ConnectivityType: private
+      AllocationId: eipalloc-123
+      SubnetId: subnet-123
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Private/Properties/AllocationId",
+            "a private NAT gateway cannot have `AllocationId`",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_nat_gateway_connectivity_type() {
+        let yaml = r#"
+Parameters:
+  Connectivity:
+    Type: String
+Resources:
+  Internal:
+    Type: AWS::EC2::NatGateway
+    Properties:
+      ConnectivityType: internal
+      SubnetId: subnet-123
+  FromParameter:
+    Type: AWS::EC2::NatGateway
+    Properties:
+      ConnectivityType: !Ref Connectivity
+      SubnetId: subnet-123
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Internal/Properties/ConnectivityType",
+            "`internal` is not a valid connectivity type, expected one of public, private",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_gateway_attachment() {
+        let yaml = r#"
+Resources:
+  Neither:
+    Type: AWS::EC2::VPCGatewayAttachment
+    Properties:
+      VpcId: vpc-123
+  Both:
+    Type: AWS::EC2::VPCGatewayAttachment
+    Properties:
+      VpcId: vpc-123
+      InternetGatewayId: igw-123
+      VpnGatewayId: vgw-123
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Neither/Properties",
+                "exactly one of `InternetGatewayId` and `VpnGatewayId` is required",
+            ),
+            Diagnostic::error(
+                "Resources/Both/Properties",
+                "exactly one of `InternetGatewayId` and `VpnGatewayId` is required",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+}
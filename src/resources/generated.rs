@@ -0,0 +1,10 @@
+// @generated by `cargo xtask codegen` from a CloudFormation resource specification.
+// Do not edit by hand.
+
+use serde::{Deserialize, Serialize};
+
+/// Resource types modelled from the resource specification instead of by hand.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(tag = "Type")]
+#[allow(clippy::large_enum_variant)]
+pub enum GeneratedResource {}
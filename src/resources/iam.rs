@@ -0,0 +1,333 @@
+use super::policy_document::{check_arn, PolicyDocument, PolicyKind};
+use crate::{
+    diagnostic::Diagnostic,
+    tag::Tag,
+    validate::Validate,
+    value::{Node, Value},
+    Template,
+};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// The seconds a role session can last.
+const SESSION_DURATIONS: RangeInclusive<i64> = 3600..=43200;
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Role {
+    pub assume_role_policy_document: Node<PolicyDocument>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub managed_policy_arns: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_session_duration: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions_boundary: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policies: Option<Node<Vec<Node<InlinePolicy>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+}
+
+/// A policy embedded in a role or user.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct InlinePolicy {
+    pub policy_document: Node<PolicyDocument>,
+    pub policy_name: Value,
+}
+
+/// An `AWS::IAM::Policy`, an inline policy attached to groups, roles or
+/// users.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Policy {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Node<Vec<Value>>>,
+    pub policy_document: Node<PolicyDocument>,
+    pub policy_name: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roles: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub users: Option<Node<Vec<Value>>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ManagedPolicy {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub managed_policy_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<Value>,
+    pub policy_document: Node<PolicyDocument>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roles: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub users: Option<Node<Vec<Value>>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct InstanceProfile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_profile_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<Value>,
+    pub roles: Node<Vec<Value>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct User {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub login_profile: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub managed_policy_arns: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions_boundary: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policies: Option<Node<Vec<Node<InlinePolicy>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_name: Option<Value>,
+}
+
+impl Node<PolicyDocument> {
+    /// Validates the document when it is written out.
+    pub fn validate(&self, path: &str, kind: PolicyKind, diagnostics: &mut Vec<Diagnostic>) {
+        if let Node::Literal(document) = self {
+            document.validate(path, kind, diagnostics);
+        }
+    }
+}
+
+/// Checks what roles and users share: inline policies, managed policy ARNs
+/// and the permissions boundary.
+fn check_identity(
+    path: &str,
+    policies: &Option<Node<Vec<Node<InlinePolicy>>>>,
+    managed_policy_arns: &Option<Node<Vec<Value>>>,
+    permissions_boundary: &Option<Value>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for (index, policy) in policies.iter().flat_map(Node::literals) {
+        policy.policy_document.validate(
+            &format!("{}/Policies/{}/PolicyDocument", path, index),
+            PolicyKind::Identity,
+            diagnostics,
+        );
+    }
+    for (index, arn) in managed_policy_arns.iter().flat_map(Node::items).enumerate() {
+        check_arn(
+            arn,
+            &format!("{}/ManagedPolicyArns/{}", path, index),
+            diagnostics,
+        );
+    }
+    if let Some(boundary) = permissions_boundary {
+        check_arn(
+            boundary,
+            &format!("{}/PermissionsBoundary", path),
+            diagnostics,
+        );
+    }
+}
+
+impl Validate for Role {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        self.assume_role_policy_document.validate(
+            &format!("{}/AssumeRolePolicyDocument", path),
+            PolicyKind::Trust,
+            diagnostics,
+        );
+        check_identity(
+            path,
+            &self.policies,
+            &self.managed_policy_arns,
+            &self.permissions_boundary,
+            diagnostics,
+        );
+        let duration = self
+            .max_session_duration
+            .as_ref()
+            .and_then(Value::as_integer);
+        if let Some(duration) = duration {
+            if !SESSION_DURATIONS.contains(&duration) {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/MaxSessionDuration", path),
+                    format!(
+                        "a session must last between {} and {} seconds, not {}",
+                        SESSION_DURATIONS.start(),
+                        SESSION_DURATIONS.end(),
+                        duration
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+impl Validate for Policy {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        self.policy_document.validate(
+            &format!("{}/PolicyDocument", path),
+            PolicyKind::Identity,
+            diagnostics,
+        );
+        if self.groups.is_none() && self.roles.is_none() && self.users.is_none() {
+            diagnostics.push(Diagnostic::error(
+                path,
+                "one of `Groups`, `Roles` and `Users` is required",
+            ));
+        }
+    }
+}
+
+impl Validate for ManagedPolicy {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        self.policy_document.validate(
+            &format!("{}/PolicyDocument", path),
+            PolicyKind::Identity,
+            diagnostics,
+        );
+    }
+}
+
+impl Validate for InstanceProfile {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        let Node::Literal(roles) = &self.roles else {
+            return;
+        };
+        if roles.len() != 1 {
+            diagnostics.push(Diagnostic::error(
+                format!("{}/Roles", path),
+                format!(
+                    "an instance profile takes exactly one role, not {}",
+                    roles.len()
+                ),
+            ));
+        }
+    }
+}
+
+impl Validate for User {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        check_identity(
+            path,
+            &self.policies,
+            &self.managed_policy_arns,
+            &self.permissions_boundary,
+            diagnostics,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{diagnostic::Diagnostic, document::Format, Template};
+
+    #[test]
+    fn test_validate_iam() {
+        let yaml = r#"
+Resources:
+  Role:
+    Type: AWS::IAM::Role
+    Properties:
+      AssumeRolePolicyDocument:
+        Version: "2012-10-17"
+        Statement:
+          Effect: Allow
+          Principal:
+            Service: !Sub ec2.${AWS::URLSuffix}
+          Action: sts:AssumeRole
+      ManagedPolicyArns:
+        - !Sub arn:${AWS::Partition}:iam::aws:policy/ReadOnlyAccess
+        - ReadOnlyAccess
+      MaxSessionDuration: 60
+      Policies:
+        - PolicyName: logs
+          PolicyDocument:
+            Version: "2012-10-17"
+            Statement:
+              - Effect: Allow
+                Action: logs:PutLogEvents
+                Resource: !GetAtt LogGroup.Arn
+  TrustsNobody:
+    Type: AWS::IAM::Role
+    Properties:
+      AssumeRolePolicyDocument:
+        Version: "2012-10-17"
+        Statement:
+          - Effect: Allow
+            Action: sts:AssumeRole
+  Policy:
+    Type: AWS::IAM::Policy
+    Properties:
+      PolicyName: orphan
+      PolicyDocument: !If
+        - IsProduction
+        - {Version: "2012-10-17", Statement: []}
+        - {Version: "2012-10-17", Statement: []}
+  ManagedPolicy:
+    Type: AWS::IAM::ManagedPolicy
+    Properties:
+      PolicyDocument:
+        Version: "2012-10-18"
+        Statement:
+          - Effect: Allow
+            Action: s3:*
+            Resource: "*"
+  InstanceProfile:
+    Type: AWS::IAM::InstanceProfile
+    Properties:
+      Roles: [!Ref Role, !Ref TrustsNobody]
+  User:
+    Type: AWS::IAM::User
+    Properties:
+      PermissionsBoundary: arn:aws:iam::123456789012:policy/boundary
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Role/Properties/ManagedPolicyArns/1",
+                "`ReadOnlyAccess` is not an ARN",
+            ),
+            Diagnostic::error(
+                "Resources/Role/Properties/MaxSessionDuration",
+                "a session must last between 3600 and 43200 seconds, not 60",
+            ),
+            Diagnostic::error(
+                "Resources/TrustsNobody/Properties/AssumeRolePolicyDocument/Statement/0",
+                "one of `Principal` and `NotPrincipal` is required",
+            ),
+            Diagnostic::error(
+                "Resources/Policy/Properties",
+                "one of `Groups`, `Roles` and `Users` is required",
+            ),
+            Diagnostic::error(
+                "Resources/ManagedPolicy/Properties/PolicyDocument/Version",
+                "`2012-10-18` is not a policy version, expected 2012-10-17 or 2008-10-17",
+            ),
+            Diagnostic::error(
+                "Resources/InstanceProfile/Properties/Roles",
+                "an instance profile takes exactly one role, not 2",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+}
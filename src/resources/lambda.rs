@@ -0,0 +1,911 @@
+use super::{policy_document::check_arn, Resource};
+use crate::{
+    diagnostic::Diagnostic,
+    tag::Tag,
+    validate::{check_boolean, check_one_of, check_range, Validate},
+    value::{Node, Value},
+    Template,
+};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// Runtimes Lambda no longer supports or is phasing out, with the runtime to
+/// move to.
+const DEPRECATED_RUNTIMES: &[(&str, &str)] = &[
+    ("dotnet5.0", "dotnet8"),
+    ("dotnet6", "dotnet8"),
+    ("dotnet7", "dotnet8"),
+    ("dotnetcore1.0", "dotnet8"),
+    ("dotnetcore2.0", "dotnet8"),
+    ("dotnetcore2.1", "dotnet8"),
+    ("dotnetcore3.1", "dotnet8"),
+    ("go1.x", "provided.al2023"),
+    ("java8", "java21"),
+    ("nodejs", "nodejs22.x"),
+    ("nodejs4.3", "nodejs22.x"),
+    ("nodejs4.3-edge", "nodejs22.x"),
+    ("nodejs6.10", "nodejs22.x"),
+    ("nodejs8.10", "nodejs22.x"),
+    ("nodejs10.x", "nodejs22.x"),
+    ("nodejs12.x", "nodejs22.x"),
+    ("nodejs14.x", "nodejs22.x"),
+    ("nodejs16.x", "nodejs22.x"),
+    ("nodejs18.x", "nodejs22.x"),
+    ("provided", "provided.al2023"),
+    ("python2.7", "python3.13"),
+    ("python3.6", "python3.13"),
+    ("python3.7", "python3.13"),
+    ("python3.8", "python3.13"),
+    ("python3.9", "python3.13"),
+    ("ruby2.5", "ruby3.4"),
+    ("ruby2.7", "ruby3.4"),
+    ("ruby3.2", "ruby3.4"),
+];
+
+const ARCHITECTURES: &[&str] = &["arm64", "x86_64"];
+
+const PACKAGE_TYPES: &[&str] = &["Image", "Zip"];
+
+const STARTING_POSITIONS: &[&str] = &["AT_TIMESTAMP", "LATEST", "TRIM_HORIZON"];
+
+const MEMORY_SIZES: RangeInclusive<i64> = 128..=10240;
+
+/// Seconds a function can run.
+const TIMEOUTS: RangeInclusive<i64> = 1..=900;
+
+const BATCH_SIZES: RangeInclusive<i64> = 1..=10000;
+
+/// The largest inline `ZipFile` source, in bytes.
+const MAX_ZIP_FILE_SIZE: usize = 4096;
+
+const MAX_LAYERS: usize = 5;
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Function {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub architectures: Option<Node<Vec<Value>>>,
+    pub code: Node<Code>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dead_letter_config: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub environment: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ephemeral_storage: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handler: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_config: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kms_key_arn: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layers: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_size: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package_type: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reserved_concurrent_executions: Option<Value>,
+    pub role: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runtime: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracing_config: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vpc_config: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Code {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_uri: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_bucket: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_key: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_object_version: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zip_file: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Permission {
+    pub action: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_source_token: Option<Value>,
+    pub function_name: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function_url_auth_type: Option<Value>,
+    pub principal: Value,
+    #[serde(rename = "PrincipalOrgID", skip_serializing_if = "Option::is_none")]
+    pub principal_org_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_account: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_arn: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct EventSourceMapping {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub batch_size: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bisect_batch_on_function_error: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_config: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_source_arn: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_criteria: Option<Value>,
+    pub function_name: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function_response_types: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maximum_batching_window_in_seconds: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maximum_retry_attempts: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallelization_factor: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starting_position: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starting_position_timestamp: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct LayerVersion {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compatible_architectures: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compatible_runtimes: Option<Node<Vec<Value>>>,
+    pub content: Node<LayerContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layer_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license_info: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct LayerContent {
+    pub s3_bucket: Value,
+    pub s3_key: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_object_version: Option<Value>,
+}
+
+/// Warns about a literal runtime that is deprecated.
+fn check_runtime(runtime: &Value, path: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(runtime) = runtime.as_str() else {
+        return;
+    };
+    let deprecated = DEPRECATED_RUNTIMES
+        .iter()
+        .find(|(name, _)| *name == runtime);
+    if let Some((_, replacement)) = deprecated {
+        diagnostics.push(Diagnostic::warning(
+            path,
+            format!(
+                "runtime `{}` is deprecated; use `{}` instead",
+                runtime, replacement
+            ),
+        ));
+    }
+}
+
+/// Reports an execution role that is not the ARN of an IAM role: a
+/// `Fn::GetAtt` of a role's `Arn`, a parameter or a literal ARN.
+fn check_role(role: &Value, path: &str, template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+    match role {
+        Value::Ref { r#ref } if template.parameter(r#ref).is_some() => {}
+        Value::Ref { r#ref } => {
+            let message = match template.resource(r#ref) {
+                Some(Resource::Role(_)) => format!(
+                    "`Ref` of role `{}` returns its name, not its ARN; use `!GetAtt {}.Arn`",
+                    r#ref, r#ref
+                ),
+                _ => format!("`{}` is not an IAM role or a parameter", r#ref),
+            };
+            diagnostics.push(Diagnostic::error(path, message));
+        }
+        Value::GetAtt { get_att } => match (template.resource(&get_att[0]), get_att.get(1)) {
+            (Some(Resource::Role(_)), Some(attribute)) if attribute == "Arn" => {}
+            (Some(Resource::Role(_)), _) => diagnostics.push(Diagnostic::error(
+                path,
+                format!("use `!GetAtt {}.Arn` for the role's ARN", get_att[0]),
+            )),
+            _ => diagnostics.push(Diagnostic::error(
+                path,
+                format!("`{}` is not an IAM role", get_att[0]),
+            )),
+        },
+        _ => check_arn(role, path, diagnostics),
+    }
+}
+
+impl Validate for Function {
+    fn validate(&self, path: &str, template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        let package_type = self.package_type.as_ref();
+        if let Some(package_type) = package_type {
+            check_one_of(
+                package_type,
+                PACKAGE_TYPES,
+                "package type",
+                &format!("{}/PackageType", path),
+                diagnostics,
+            );
+        }
+        let code = self.code.literal();
+        match package_type.and_then(Value::as_str).unwrap_or("Zip") {
+            "Image" if code.is_some_and(|code| code.image_uri.is_none()) => {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/Code", path),
+                    "an `Image` package requires `ImageUri`",
+                ))
+            }
+            "Zip" => {
+                let runtime = self.runtime.as_ref().and_then(Value::as_str);
+                match (&self.runtime, &self.handler) {
+                    (None, _) => diagnostics.push(Diagnostic::error(
+                        path,
+                        "`Runtime` is required for a `Zip` package",
+                    )),
+                    // Custom runtimes find the handler themselves.
+                    (Some(_), None) if !runtime.is_some_and(|r| r.starts_with("provided")) => {
+                        diagnostics.push(Diagnostic::error(
+                            path,
+                            "`Handler` is required for a `Zip` package",
+                        ))
+                    }
+                    _ => {}
+                }
+                let sources = code.map(|code| {
+                    [
+                        code.zip_file.is_some(),
+                        code.s3_bucket.is_some() || code.s3_key.is_some(),
+                    ]
+                });
+                if sources.is_some_and(|sources| sources.iter().filter(|set| **set).count() != 1) {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/Code", path),
+                        "exactly one of `ZipFile` and `S3Bucket` with `S3Key` is required",
+                    ));
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(runtime) = &self.runtime {
+            check_runtime(runtime, &format!("{}/Runtime", path), diagnostics);
+        }
+        check_range(
+            &self.memory_size,
+            MEMORY_SIZES,
+            "memory size in MB",
+            &format!("{}/MemorySize", path),
+            diagnostics,
+        );
+        check_range(
+            &self.timeout,
+            TIMEOUTS,
+            "timeout in seconds",
+            &format!("{}/Timeout", path),
+            diagnostics,
+        );
+
+        let zip_file = match code.and_then(|code| code.zip_file.as_ref()) {
+            Some(Value::String(source)) | Some(Value::Sub { sub: source, .. }) => Some(source),
+            _ => None,
+        };
+        if let Some(source) = zip_file.filter(|source| source.len() > MAX_ZIP_FILE_SIZE) {
+            diagnostics.push(Diagnostic::error(
+                format!("{}/Code/ZipFile", path),
+                format!(
+                    "inline code is {} bytes, more than the limit of {}",
+                    source.len(),
+                    MAX_ZIP_FILE_SIZE
+                ),
+            ));
+        }
+
+        if let Some(Node::Literal(architectures)) = &self.architectures {
+            if architectures.len() > 1 {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/Architectures", path),
+                    "a function has only one architecture",
+                ));
+            }
+            for (index, architecture) in architectures.iter().enumerate() {
+                check_one_of(
+                    architecture,
+                    ARCHITECTURES,
+                    "architecture",
+                    &format!("{}/Architectures/{}", path, index),
+                    diagnostics,
+                );
+            }
+        }
+        let layers = self.layers.as_ref().and_then(Node::literal);
+        if let Some(layers) = layers.filter(|layers| layers.len() > MAX_LAYERS) {
+            diagnostics.push(Diagnostic::error(
+                format!("{}/Layers", path),
+                format!(
+                    "a function can use at most {} layers, not {}",
+                    MAX_LAYERS,
+                    layers.len()
+                ),
+            ));
+        }
+
+        check_role(&self.role, &format!("{}/Role", path), template, diagnostics);
+    }
+}
+
+impl Validate for Permission {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        if let Some(action) = self.action.as_str() {
+            if !action.starts_with("lambda:") {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/Action", path),
+                    format!("`{}` is not a Lambda action", action),
+                ));
+            }
+        }
+        if let Some(auth_type) = &self.function_url_auth_type {
+            check_one_of(
+                auth_type,
+                &["AWS_IAM", "NONE"],
+                "function URL auth type",
+                &format!("{}/FunctionUrlAuthType", path),
+                diagnostics,
+            );
+        }
+    }
+}
+
+impl Validate for EventSourceMapping {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        check_range(
+            &self.batch_size,
+            BATCH_SIZES,
+            "batch size",
+            &format!("{}/BatchSize", path),
+            diagnostics,
+        );
+        if let Some(enabled) = &self.enabled {
+            check_boolean(enabled, &format!("{}/Enabled", path), diagnostics);
+        }
+        if let Some(position) = &self.starting_position {
+            check_one_of(
+                position,
+                STARTING_POSITIONS,
+                "starting position",
+                &format!("{}/StartingPosition", path),
+                diagnostics,
+            );
+        }
+        let at_timestamp =
+            self.starting_position.as_ref().and_then(Value::as_str) == Some("AT_TIMESTAMP");
+        if at_timestamp != self.starting_position_timestamp.is_some() {
+            diagnostics.push(Diagnostic::error(
+                path,
+                "`StartingPositionTimestamp` is required with, and only with, `AT_TIMESTAMP`",
+            ));
+        }
+    }
+}
+
+impl Validate for LayerVersion {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        let runtimes = self.compatible_runtimes.iter().flat_map(Node::items);
+        for (index, runtime) in runtimes.enumerate() {
+            check_runtime(
+                runtime,
+                &format!("{}/CompatibleRuntimes/{}", path, index),
+                diagnostics,
+            );
+        }
+        let architectures = self.compatible_architectures.iter().flat_map(Node::items);
+        for (index, architecture) in architectures.enumerate() {
+            check_one_of(
+                architecture,
+                ARCHITECTURES,
+                "architecture",
+                &format!("{}/CompatibleArchitectures/{}", path, index),
+                diagnostics,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{diagnostic::Diagnostic, document::Format, Template};
+
+    #[test]
+    fn test_validate_function_memory_and_timeout() {
+        let yaml = r#"
+Parameters:
+  Memory:
+    Type: Number
+Resources:
+  Function:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: python3.13
+      Handler: index.handler
+      Role: arn:aws:iam::123456789012:role/lambda
+      MemorySize: 64
+      Timeout: 901
+      Code:
+        ZipFile: print("hello")
+  Sized:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: python3.13
+      Handler: index.handler
+      Role: arn:aws:iam::123456789012:role/lambda
+      MemorySize: !Ref Memory
+      Code:
+        ZipFile: print("hello")
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Function/Properties/MemorySize",
+                "memory size in MB must be between 128 and 10240, not 64",
+            ),
+            Diagnostic::error(
+                "Resources/Function/Properties/Timeout",
+                "timeout in seconds must be between 1 and 900, not 901",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_function_inline_code_size() {
+        let yaml = format!(
+            r#"
+Resources:
+  Plain:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: python3.13
+      Handler: index.handler
+      Role: arn:aws:iam::123456789012:role/lambda
+      Code:
+        ZipFile: "{source}"
+  Substituted:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: python3.13
+      Handler: index.handler
+      Role: arn:aws:iam::123456789012:role/lambda
+      Code:
+        ZipFile: !Sub "${{AWS::Region}}{source}"
+  Conditional:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: python3.13
+      Handler: index.handler
+      Role: arn:aws:iam::123456789012:role/lambda
+      Code: !If [UseBucket, {{S3Bucket: artifacts, S3Key: code.zip}}, {{ZipFile: "{source}"}}]
+"#,
+            source = "x".repeat(5000)
+        );
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Plain/Properties/Code/ZipFile",
+                "inline code is 5000 bytes, more than the limit of 4096",
+            ),
+            Diagnostic::error(
+                "Resources/Substituted/Properties/Code/ZipFile",
+                "inline code is 5014 bytes, more than the limit of 4096",
+            ),
+        ];
+
+        let template = Template::parse(&yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_function_zip_package() {
+        let yaml = r#"
+Resources:
+  NoRuntime:
+    Type: AWS::Lambda::Function
+    Properties:
+      Handler: index.handler
+      Role: arn:aws:iam::123456789012:role/lambda
+      Code:
+        ZipFile: print("hello")
+  NoHandler:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: python3.13
+      Role: arn:aws:iam::123456789012:role/lambda
+      Code:
+        ZipFile: print("hello")
+  CustomRuntime:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: provided.al2023
+      Role: arn:aws:iam::123456789012:role/lambda
+      Code:
+        S3Bucket: artifacts
+        S3Key: custom.zip
+  TwoSources:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: python3.13
+      Handler: index.handler
+      Role: arn:aws:iam::123456789012:role/lambda
+      Code:
+        ZipFile: print("hello")
+        S3Bucket: artifacts
+        S3Key: code.zip
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/NoRuntime/Properties",
+                "`Runtime` is required for a `Zip` package",
+            ),
+            Diagnostic::error(
+                "Resources/NoHandler/Properties",
+                "`Handler` is required for a `Zip` package",
+            ),
+            Diagnostic::error(
+                "Resources/TwoSources/Properties/Code",
+                "exactly one of `ZipFile` and `S3Bucket` with `S3Key` is required",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_function_image_package() {
+        let yaml = r#"
+Resources:
+  Image:
+    Type: AWS::Lambda::Function
+    Properties:
+      PackageType: Image
+      Role: arn:aws:iam::123456789012:role/lambda
+      Code:
+        ImageUri: 123456789012.dkr.ecr.us-east-1.amazonaws.com/app:latest
+  NoImage:
+    Type: AWS::Lambda::Function
+    Properties:
+      PackageType: Image
+      Role: arn:aws:iam::123456789012:role/lambda
+      Code:
+        S3Bucket: artifacts
+  Docker:
+    Type: AWS::Lambda::Function
+    Properties:
+      PackageType: Docker
+      Role: arn:aws:iam::123456789012:role/lambda
+      Code:
+        ImageUri: 123456789012.dkr.ecr.us-east-1.amazonaws.com/app:latest
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/NoImage/Properties/Code",
+                "an `Image` package requires `ImageUri`",
+            ),
+            Diagnostic::error(
+                "Resources/Docker/Properties/PackageType",
+                "`Docker` is not a valid package type, expected one of Image, Zip",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_function_deprecated_runtime() {
+        let yaml = r#"
+Parameters:
+  Runtime:
+    Type: String
+Resources:
+  Legacy:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: nodejs16.x
+      Handler: index.handler
+      Role: arn:aws:iam::123456789012:role/lambda
+      Code:
+        ZipFile: exports.handler = async () => {};
+  FromParameter:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: !Ref Runtime
+      Handler: index.handler
+      Role: arn:aws:iam::123456789012:role/lambda
+      Code:
+        ZipFile: exports.handler = async () => {};
+"#;
+        let expected = vec![Diagnostic::warning(
+            "Resources/Legacy/Properties/Runtime",
+            "runtime `nodejs16.x` is deprecated; use `nodejs22.x` instead",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_function_architectures_and_layers() {
+        let yaml = r#"
+Resources:
+  Function:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: python3.13
+      Handler: index.handler
+      Role: arn:aws:iam::123456789012:role/lambda
+      Architectures: [arm64, x86]
+      Layers: [a, b, c, d, e, f]
+      Code:
+        ZipFile: print("hello")
+  Conditional:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: python3.13
+      Handler: index.handler
+      Role: arn:aws:iam::123456789012:role/lambda
+      Architectures: !If [UseGraviton, [arm64], [x86_64]]
+      Layers: !Split [",", !Ref AWS::NoValue]
+      Code:
+        ZipFile: print("hello")
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Function/Properties/Architectures",
+                "a function has only one architecture",
+            ),
+            Diagnostic::error(
+                "Resources/Function/Properties/Architectures/1",
+                "`x86` is not a valid architecture, expected one of arm64, x86_64",
+            ),
+            Diagnostic::error(
+                "Resources/Function/Properties/Layers",
+                "a function can use at most 5 layers, not 6",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_function_role() {
+        let yaml = r#"
+Parameters:
+  RoleArn:
+    Type: String
+Resources:
+  Role:
+    Type: AWS::IAM::Role
+    Properties:
+      AssumeRolePolicyDocument:
+        Version: "2012-10-17"
+        Statement:
+          - Effect: Allow
+            Principal: {Service: lambda.amazonaws.com}
+            Action: sts:AssumeRole
+  Arn:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: python3.13
+      Handler: index.handler
+      Role: !GetAtt Role.Arn
+      Code:
+        ZipFile: print("hello")
+  Parameter:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: python3.13
+      Handler: index.handler
+      Role: !Ref RoleArn
+      Code:
+        ZipFile: print("hello")
+  Name:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: python3.13
+      Handler: index.handler
+      Role: !Ref Role
+      Code:
+        ZipFile: print("hello")
+  RoleId:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: python3.13
+      Handler: index.handler
+      Role: !GetAtt Role.RoleId
+      Code:
+        ZipFile: print("hello")
+  NotARole:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: python3.13
+      Handler: index.handler
+      Role: !GetAtt Arn.Arn
+      Code:
+        ZipFile: print("hello")
+  Undefined:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: python3.13
+      Handler: index.handler
+      Role: !Ref Missing
+      Code:
+        ZipFile: print("hello")
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Name/Properties/Role",
+                "`Ref` of role `Role` returns its name, not its ARN; use `!GetAtt Role.Arn`",
+            ),
+            Diagnostic::error(
+                "Resources/RoleId/Properties/Role",
+                "use `!GetAtt Role.Arn` for the role's ARN",
+            ),
+            Diagnostic::error(
+                "Resources/NotARole/Properties/Role",
+                "`Arn` is not an IAM role",
+            ),
+            Diagnostic::error(
+                "Resources/Undefined/Properties/Role",
+                "`Missing` is not an IAM role or a parameter",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_permission() {
+        let yaml = r#"
+Resources:
+  Permission:
+    Type: AWS::Lambda::Permission
+    Properties:
+      Action: InvokeFunction
+      FunctionName: my-function
+      Principal: s3.amazonaws.com
+  Url:
+    Type: AWS::Lambda::Permission
+    Properties:
+      Action: lambda:InvokeFunctionUrl
+      FunctionName: my-function
+      Principal: "*"
+      FunctionUrlAuthType: OPEN
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Permission/Properties/Action",
+                "`InvokeFunction` is not a Lambda action",
+            ),
+            Diagnostic::error(
+                "Resources/Url/Properties/FunctionUrlAuthType",
+                "`OPEN` is not a valid function URL auth type, expected one of AWS_IAM, NONE",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_event_source_mapping() {
+        let yaml = r#"
+Resources:
+  Batch:
+    Type: AWS::Lambda::EventSourceMapping
+    Properties:
+      FunctionName: my-function
+      BatchSize: 0
+      Enabled: "yes"
+  Timestamp:
+    Type: AWS::Lambda::EventSourceMapping
+    Properties:
+      FunctionName: my-function
+      StartingPosition: AT_TIMESTAMP
+  Latest:
+    Type: AWS::Lambda::EventSourceMapping
+    Properties:
+      FunctionName: my-function
+      StartingPosition: LATEST
+      StartingPositionTimestamp: 1700000000
+  Oldest:
+    Type: AWS::Lambda::EventSourceMapping
+    Properties:
+      FunctionName: my-function
+      StartingPosition: OLDEST
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Batch/Properties/BatchSize",
+                "batch size must be between 1 and 10000, not 0",
+            ),
+            Diagnostic::error(
+                "Resources/Batch/Properties/Enabled",
+                "expected a boolean, `true` or `false`",
+            ),
+            Diagnostic::error(
+                "Resources/Timestamp/Properties",
+                "`StartingPositionTimestamp` is required with, and only with, `AT_TIMESTAMP`",
+            ),
+            Diagnostic::error(
+                "Resources/Latest/Properties",
+                "`StartingPositionTimestamp` is required with, and only with, `AT_TIMESTAMP`",
+            ),
+            Diagnostic::error(
+                "Resources/Oldest/Properties/StartingPosition",
+                "`OLDEST` is not a valid starting position, expected one of AT_TIMESTAMP, LATEST, TRIM_HORIZON",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_layer_version() {
+        let yaml = r#"
+Resources:
+  Layer:
+    Type: AWS::Lambda::LayerVersion
+    Properties:
+      CompatibleRuntimes: [python3.13, python2.7]
+      CompatibleArchitectures: [arm64, arm]
+      Content:
+        S3Bucket: artifacts
+        S3Key: layer.zip
+  Conditional:
+    Type: AWS::Lambda::LayerVersion
+    Properties:
+      CompatibleRuntimes: !If [Legacy, [python2.7], [python3.13]]
+      Content: !If [Legacy, {S3Bucket: old, S3Key: layer.zip}, {S3Bucket: new, S3Key: layer.zip}]
+"#;
+        let expected = vec![
+            Diagnostic::warning(
+                "Resources/Layer/Properties/CompatibleRuntimes/1",
+                "runtime `python2.7` is deprecated; use `python3.13` instead",
+            ),
+            Diagnostic::error(
+                "Resources/Layer/Properties/CompatibleArchitectures/1",
+                "`arm` is not a valid architecture, expected one of arm64, x86_64",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+}
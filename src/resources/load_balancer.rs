@@ -0,0 +1,58 @@
+use crate::{
+    tag::Tag,
+    value::{Node, Value},
+};
+use serde::{Deserialize, Serialize};
+
+/// An `AWS::ElasticLoadBalancing::LoadBalancer`, the classic load balancer.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct LoadBalancer {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub availability_zones: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cross_zone: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health_check: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instances: Option<Node<Vec<Value>>>,
+    pub listeners: Node<Vec<Node<Listener>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub load_balancer_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security_groups: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subnets: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Listener {
+    pub instance_port: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_protocol: Option<Value>,
+    pub load_balancer_port: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy_names: Option<Node<Vec<Value>>>,
+    pub protocol: Value,
+    #[serde(rename = "SSLCertificateId", skip_serializing_if = "Option::is_none")]
+    pub ssl_certificate_id: Option<Value>,
+}
+
+impl LoadBalancer {
+    /// Whether the scheme makes the load balancer face the internet, which is
+    /// the default.
+    pub fn is_internet_facing(&self) -> Option<bool> {
+        match &self.scheme {
+            Some(scheme) => match scheme.as_str()? {
+                "internal" => Some(false),
+                _ => Some(true),
+            },
+            None => Some(true),
+        }
+    }
+}
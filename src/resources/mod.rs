@@ -1,38 +1,456 @@
-use self::{ec2::Ec2, security_group::SecurityGroup, vpc::Vpc};
-use serde::Deserialize;
+use self::{
+    autoscaling::{AutoScalingGroup, LaunchConfiguration},
+    cloudwatch::Alarm,
+    dynamodb::Table,
+    ecs::{Cluster, Service},
+    elbv2::{Listener, ListenerRule, TargetGroup},
+    gateway::{Eip, InternetGateway, NatGateway, VpcGatewayAttachment},
+    generated::GeneratedResource,
+    iam::{InstanceProfile, ManagedPolicy, Policy, Role, User},
+    lambda::{EventSourceMapping, Function, LayerVersion, Permission},
+    rds::DbSubnetGroup,
+    route::{Route, RouteTable, SubnetRouteTableAssociation},
+    s3::BucketPolicy,
+    security_group::{SecurityGroup, SecurityGroupEgress, SecurityGroupIngress},
+    sns::{Subscription, Topic, TopicPolicy},
+    sqs::{Queue, QueuePolicy},
+    vpc::{Vpc, VpcCidrBlock},
+};
+use crate::{diagnostic::Diagnostic, validate::Validate, value::Value, Template};
+use indexmap::IndexMap;
+use serde::{
+    de::{self, DeserializeOwned},
+    ser, Deserialize, Deserializer, Serialize, Serializer,
+};
 
+mod autoscaling;
+mod cloudwatch;
+mod dynamodb;
 mod ec2;
+mod ecs;
+mod elbv2;
+mod gateway;
+mod generated;
+mod iam;
+mod lambda;
+mod load_balancer;
+mod policy_document;
+mod rds;
+mod route;
+mod s3;
 mod security_group;
+mod sns;
+mod sqs;
+mod subnet;
 mod vpc;
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
-#[serde(rename_all = "PascalCase")]
+pub use self::{
+    autoscaling::{validate_auto_scaling_groups, CreationPolicy, UpdatePolicy},
+    ec2::Ec2,
+    ecs::TaskDefinition,
+    elbv2::{validate_listener_rules, LoadBalancerV2},
+    load_balancer::LoadBalancer,
+    policy_document::{policy_documents, strings, PolicyKind},
+    rds::{DbCluster, DbInstance},
+    route::validate_routes,
+    s3::Bucket,
+    security_group::{
+        group_rules, validate_security_groups, Egress, Ingress, Ports, Protocol, Traffic,
+    },
+    sqs::validate_queue_consumers,
+    subnet::{validate_subnets, Subnet},
+};
+
+/// A resource, typed by its `Type`. Types without a hand-written model use
+/// the one generated from the resource specification, if there is one, and
+/// are otherwise kept as written in [`Resource::Other`].
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(remote = "Self", rename_all = "PascalCase")]
 #[serde(tag = "Type")]
+#[allow(clippy::large_enum_variant)]
 pub enum Resource {
     #[serde(rename = "AWS::EC2::Instance")]
     Ec2(ResourceContainer<Ec2>),
     #[serde(rename = "AWS::EC2::VPC")]
     Vpc(ResourceContainer<Vpc>),
+    #[serde(rename = "AWS::EC2::VPCCidrBlock")]
+    VpcCidrBlock(ResourceContainer<VpcCidrBlock>),
     #[serde(rename = "AWS::SNS::Topic")]
-    Topic,
+    Topic(ResourceContainer<Option<Topic>>),
     #[serde(rename = "AWS::AutoScaling::AutoScalingGroup")]
-    AutoScalingGroup,
+    AutoScalingGroup(ResourceContainer<AutoScalingGroup>),
     #[serde(rename = "AWS::AutoScaling::LaunchConfiguration")]
-    LaunchConfiguration,
+    LaunchConfiguration(ResourceContainer<LaunchConfiguration>),
     #[serde(rename = "AWS::AutoScaling::ScalingPolicy")]
     ScalingPolicy,
     #[serde(rename = "AWS::CloudWatch::Alarm")]
-    Alarm,
+    Alarm(ResourceContainer<Alarm>),
     #[serde(rename = "AWS::ElasticLoadBalancing::LoadBalancer")]
-    LoadBalancer,
+    LoadBalancer(ResourceContainer<LoadBalancer>),
     #[serde(rename = "AWS::EC2::SecurityGroup")]
     SecurityGroup(ResourceContainer<SecurityGroup>),
+    #[serde(rename = "AWS::EC2::SecurityGroupIngress")]
+    SecurityGroupIngress(ResourceContainer<SecurityGroupIngress>),
+    #[serde(rename = "AWS::EC2::SecurityGroupEgress")]
+    SecurityGroupEgress(ResourceContainer<SecurityGroupEgress>),
+    #[serde(rename = "AWS::EC2::Subnet")]
+    Subnet(ResourceContainer<Subnet>),
+    #[serde(rename = "AWS::EC2::RouteTable")]
+    RouteTable(ResourceContainer<RouteTable>),
+    #[serde(rename = "AWS::EC2::Route")]
+    Route(ResourceContainer<Route>),
+    #[serde(rename = "AWS::EC2::SubnetRouteTableAssociation")]
+    SubnetRouteTableAssociation(ResourceContainer<SubnetRouteTableAssociation>),
+    #[serde(rename = "AWS::EC2::InternetGateway")]
+    InternetGateway(ResourceContainer<Option<InternetGateway>>),
+    #[serde(rename = "AWS::EC2::VPCGatewayAttachment")]
+    VpcGatewayAttachment(ResourceContainer<VpcGatewayAttachment>),
+    #[serde(rename = "AWS::EC2::NatGateway")]
+    NatGateway(ResourceContainer<NatGateway>),
+    #[serde(rename = "AWS::EC2::EIP")]
+    Eip(ResourceContainer<Option<Eip>>),
+    #[serde(rename = "AWS::IAM::Role")]
+    Role(ResourceContainer<Role>),
+    #[serde(rename = "AWS::IAM::Policy")]
+    Policy(ResourceContainer<Policy>),
+    #[serde(rename = "AWS::IAM::ManagedPolicy")]
+    ManagedPolicy(ResourceContainer<ManagedPolicy>),
+    #[serde(rename = "AWS::IAM::InstanceProfile")]
+    InstanceProfile(ResourceContainer<InstanceProfile>),
+    #[serde(rename = "AWS::IAM::User")]
+    User(ResourceContainer<Option<User>>),
+    #[serde(rename = "AWS::S3::Bucket")]
+    Bucket(ResourceContainer<Option<Bucket>>),
+    #[serde(rename = "AWS::S3::BucketPolicy")]
+    BucketPolicy(ResourceContainer<BucketPolicy>),
+    #[serde(rename = "AWS::Lambda::Function")]
+    Function(ResourceContainer<Function>),
+    #[serde(rename = "AWS::Lambda::Permission")]
+    Permission(ResourceContainer<Permission>),
+    #[serde(rename = "AWS::Lambda::EventSourceMapping")]
+    EventSourceMapping(ResourceContainer<EventSourceMapping>),
+    #[serde(rename = "AWS::Lambda::LayerVersion")]
+    LayerVersion(ResourceContainer<LayerVersion>),
+    #[serde(rename = "AWS::RDS::DBInstance")]
+    DbInstance(ResourceContainer<DbInstance>),
+    #[serde(rename = "AWS::RDS::DBCluster")]
+    DbCluster(ResourceContainer<DbCluster>),
+    #[serde(rename = "AWS::RDS::DBSubnetGroup")]
+    DbSubnetGroup(ResourceContainer<DbSubnetGroup>),
+    #[serde(rename = "AWS::DynamoDB::Table")]
+    Table(ResourceContainer<Table>),
+    #[serde(rename = "AWS::ECS::Cluster")]
+    Cluster(ResourceContainer<Option<Cluster>>),
+    #[serde(rename = "AWS::ECS::TaskDefinition")]
+    TaskDefinition(ResourceContainer<Option<TaskDefinition>>),
+    #[serde(rename = "AWS::ECS::Service")]
+    Service(ResourceContainer<Option<Service>>),
+    #[serde(rename = "AWS::ElasticLoadBalancingV2::LoadBalancer")]
+    LoadBalancerV2(ResourceContainer<LoadBalancerV2>),
+    #[serde(rename = "AWS::ElasticLoadBalancingV2::Listener")]
+    Listener(ResourceContainer<Listener>),
+    #[serde(rename = "AWS::ElasticLoadBalancingV2::ListenerRule")]
+    ListenerRule(ResourceContainer<ListenerRule>),
+    #[serde(rename = "AWS::ElasticLoadBalancingV2::TargetGroup")]
+    TargetGroup(ResourceContainer<Option<TargetGroup>>),
+    #[serde(rename = "AWS::SNS::Subscription")]
+    Subscription(ResourceContainer<Subscription>),
+    #[serde(rename = "AWS::SNS::TopicPolicy")]
+    TopicPolicy(ResourceContainer<TopicPolicy>),
+    #[serde(rename = "AWS::SQS::Queue")]
+    Queue(ResourceContainer<Option<Queue>>),
+    #[serde(rename = "AWS::SQS::QueuePolicy")]
+    QueuePolicy(ResourceContainer<QueuePolicy>),
+    #[serde(skip)]
+    Generated(GeneratedResource),
+    #[serde(skip)]
+    Other(OtherResource),
+}
+
+/// A resource of a type that has no typed model, such as
+/// `AWS::Logs::LogGroup` or `Custom::Certificate`.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct OtherResource {
+    #[serde(rename = "Type")]
+    pub resource_type: String,
+    #[serde(flatten)]
+    pub resource: ResourceContainer<Option<Value>>,
+}
+
+impl Serialize for Resource {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Resource::Generated(generated) => generated.serialize(serializer),
+            Resource::Other(other) => other.serialize(serializer),
+            _ => Resource::serialize(self, serializer),
+        }
+    }
 }
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
-#[serde(rename_all = "PascalCase")]
+impl<'de> Deserialize<'de> for Resource {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Resource, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        // Only an unknown `Type` falls back to the next model; a known type
+        // with bad properties is still an error.
+        match Resource::deserialize(&value) {
+            Err(error) if is_unknown_type(&value, &error) => {}
+            resource => return resource.map_err(de::Error::custom),
+        }
+        match GeneratedResource::deserialize(&value) {
+            Err(error) if is_unknown_type(&value, &error) => {}
+            resource => return resource.map(Resource::Generated).map_err(de::Error::custom),
+        }
+        OtherResource::deserialize(value)
+            .map(Resource::Other)
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Deserializes the `Resources` section. A resource that fails to
+/// deserialize is reported with its logical ID, since serde's own errors
+/// only name the offending value.
+pub fn deserialize_resources<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<IndexMap<String, Resource>, D::Error> {
+    let resources = IndexMap::<String, serde_json::Value>::deserialize(deserializer)?;
+    resources
+        .into_iter()
+        .map(
+            |(name, value)| match <Resource as Deserialize>::deserialize(value) {
+                Ok(resource) => Ok((name, resource)),
+                Err(error) => Err(de::Error::custom(format!("Resources/{}: {}", name, error))),
+            },
+        )
+        .collect()
+}
+
+impl Resource {
+    /// The resource's `Type`, such as `AWS::SNS::Topic`.
+    pub fn resource_type(&self) -> String {
+        if let Resource::Other(other) = self {
+            return other.resource_type.clone();
+        }
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|value| value.get("Type")?.as_str().map(str::to_string))
+            .expect("resources serialize with their type")
+    }
+}
+
+fn is_unknown_type(value: &serde_json::Value, error: &serde_json::Error) -> bool {
+    value
+        .get("Type")
+        .and_then(serde_json::Value::as_str)
+        .is_some_and(|resource_type| {
+            let unknown = format!("unknown variant `{}`", resource_type);
+            error.to_string().starts_with(&unknown)
+        })
+}
+
+/// Whether a resource type has a hand-written model, which takes the place
+/// of a generated one.
+pub(crate) fn is_modelled(resource_type: &str) -> bool {
+    let value = serde_json::json!({ "Type": resource_type });
+    match Resource::deserialize(&value) {
+        Ok(_) => true,
+        Err(error) => !is_unknown_type(&value, &error),
+    }
+}
+
+/// A resource's attributes. Resources whose properties are all optional use
+/// `Option<T>` so `Properties` can be left out.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(remote = "Self", rename_all = "PascalCase")]
 pub struct ResourceContainer<T> {
-    properties: T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depends_on: Option<DependsOn>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deletion_policy: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_replace_policy: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creation_policy: Option<CreationPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_policy: Option<UpdatePolicy>,
+    pub properties: T,
+    /// `Properties` as written, including keys the typed model ignores, such
+    /// as misspelled or unmodelled properties.
+    #[serde(skip)]
+    pub raw_properties: Option<Value>,
+}
+
+impl<T: Serialize> Serialize for ResourceContainer<T> {
+    /// Writes `Properties` back as they were read, so nothing the typed
+    /// model ignores is lost.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut value = ResourceContainer::serialize(self, serde_json::value::Serializer)
+            .map_err(ser::Error::custom)?;
+        if let (Some(raw), Some(map)) = (&self.raw_properties, value.as_object_mut()) {
+            let raw = serde_json::to_value(raw).map_err(ser::Error::custom)?;
+            map.insert("Properties".to_string(), raw);
+        }
+        value.serialize(serializer)
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for ResourceContainer<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let raw_properties = value
+            .get("Properties")
+            .map(Value::deserialize)
+            .transpose()
+            .map_err(de::Error::custom)?;
+        let mut container = ResourceContainer::deserialize(value).map_err(de::Error::custom)?;
+        container.raw_properties = raw_properties;
+        Ok(container)
+    }
+}
+
+/// `DependsOn`, which is either one resource name or a list of them.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum DependsOn {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl<T> ResourceContainer<T> {
+    /// The resources this one explicitly depends on.
+    pub fn depends_on(&self) -> Vec<&str> {
+        match &self.depends_on {
+            Some(DependsOn::One(name)) => vec![name],
+            Some(DependsOn::Many(names)) => names.iter().map(String::as_str).collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// The logical name a `Ref` or `Fn::GetAtt` points at.
+pub fn referenced_resource(value: &Value) -> Option<&str> {
+    match value {
+        Value::Ref { r#ref } => Some(r#ref),
+        Value::GetAtt { get_att } => get_att.first().map(String::as_str),
+        _ => None,
+    }
+}
+
+impl Validate for Resource {
+    fn validate(&self, path: &str, template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        let path = format!("{}/Properties", path);
+        match self {
+            Resource::Ec2(resource) => resource.properties.validate(&path, template, diagnostics),
+            Resource::Vpc(resource) => resource.properties.validate(&path, template, diagnostics),
+            Resource::SecurityGroup(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::SecurityGroupIngress(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::SecurityGroupEgress(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::Subnet(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::Route(resource) => resource.properties.validate(&path, template, diagnostics),
+            Resource::VpcGatewayAttachment(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::NatGateway(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::Role(resource) => resource.properties.validate(&path, template, diagnostics),
+            Resource::Policy(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::ManagedPolicy(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::InstanceProfile(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::User(ResourceContainer {
+                properties: Some(user),
+                ..
+            }) => user.validate(&path, template, diagnostics),
+            Resource::Bucket(ResourceContainer {
+                properties: Some(bucket),
+                ..
+            }) => bucket.validate(&path, template, diagnostics),
+            Resource::BucketPolicy(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::Function(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::Permission(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::EventSourceMapping(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::LayerVersion(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::DbInstance(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::DbCluster(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::DbSubnetGroup(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::Table(resource) => resource.properties.validate(&path, template, diagnostics),
+            Resource::TaskDefinition(ResourceContainer {
+                properties: Some(task),
+                ..
+            }) => task.validate(&path, template, diagnostics),
+            Resource::Service(ResourceContainer {
+                properties: Some(service),
+                ..
+            }) => service.validate(&path, template, diagnostics),
+            Resource::AutoScalingGroup(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::LaunchConfiguration(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::Alarm(resource) => resource.properties.validate(&path, template, diagnostics),
+            Resource::LoadBalancerV2(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::Listener(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::ListenerRule(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::TargetGroup(ResourceContainer {
+                properties: Some(target_group),
+                ..
+            }) => target_group.validate(&path, template, diagnostics),
+            Resource::Topic(ResourceContainer {
+                properties: Some(topic),
+                ..
+            }) => topic.validate(&path, template, diagnostics),
+            Resource::Subscription(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::TopicPolicy(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            Resource::Queue(ResourceContainer {
+                properties: Some(queue),
+                ..
+            }) => queue.validate(&path, template, diagnostics),
+            Resource::QueuePolicy(resource) => {
+                resource.properties.validate(&path, template, diagnostics)
+            }
+            _ => {}
+        }
+    }
 }
 
 #[cfg(test)]
@@ -52,11 +470,18 @@ mod tests {
 }
         "#;
         let expected = Resource::Ec2(ResourceContainer {
+            depends_on: None,
+            deletion_policy: None,
+            update_replace_policy: None,
+            creation_policy: None,
+            update_policy: None,
             properties: Ec2 {
                 key_name: Some(Value::String("myKey".to_string())),
-                security_groups: None,
-                image_id: None,
+                ..Ec2::default()
             },
+            raw_properties: Some(Value::Map(
+                [("KeyName".to_string(), Value::String("myKey".to_string()))].into(),
+            )),
         });
 
         let actual = serde_json::from_str(json).unwrap();
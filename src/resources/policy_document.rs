@@ -0,0 +1,613 @@
+use super::{Resource, ResourceContainer};
+use crate::{
+    diagnostic::Diagnostic,
+    value::{Node, Value},
+    Template,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+const VERSIONS: &[&str] = &["2012-10-17", "2008-10-17"];
+
+const PRINCIPAL_TYPES: &[&str] = &["AWS", "CanonicalUser", "Federated", "Service"];
+
+const CONDITION_OPERATORS: &[&str] = &[
+    "ArnEquals",
+    "ArnLike",
+    "ArnNotEquals",
+    "ArnNotLike",
+    "BinaryEquals",
+    "Bool",
+    "DateEquals",
+    "DateGreaterThan",
+    "DateGreaterThanEquals",
+    "DateLessThan",
+    "DateLessThanEquals",
+    "DateNotEquals",
+    "IpAddress",
+    "NotIpAddress",
+    "Null",
+    "NumericEquals",
+    "NumericGreaterThan",
+    "NumericGreaterThanEquals",
+    "NumericLessThan",
+    "NumericLessThanEquals",
+    "NumericNotEquals",
+    "StringEquals",
+    "StringEqualsIgnoreCase",
+    "StringLike",
+    "StringNotEquals",
+    "StringNotEqualsIgnoreCase",
+    "StringNotLike",
+];
+
+/// A single element, or a list of them.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct PolicyDocument {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub statement: Option<OneOrMany<Node<Statement>>>,
+    /// Keys IAM does not know about, kept to report them.
+    #[serde(flatten)]
+    pub other: IndexMap<String, Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Statement {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sid: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effect: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub principal: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not_principal: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not_action: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not_resource: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<Value>,
+    #[serde(flatten)]
+    pub other: IndexMap<String, Value>,
+}
+
+/// Where a policy is attached, which decides the elements it needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyKind {
+    /// Attached to a user, group or role: no principal, a resource.
+    Identity,
+    /// Attached to a resource such as a bucket: a principal and a resource.
+    Resource,
+    /// A role's trust policy: a principal and no resource.
+    Trust,
+}
+
+impl PolicyDocument {
+    /// The statements with their paths, leaving out computed ones.
+    pub fn statements<'a>(&'a self, path: &str) -> Vec<(String, &'a Statement)> {
+        let statements = match &self.statement {
+            Some(OneOrMany::One(Node::Literal(statement))) => {
+                return vec![(format!("{}/Statement", path), statement)]
+            }
+            Some(OneOrMany::Many(statements)) => statements,
+            _ => return Vec::new(),
+        };
+        statements
+            .iter()
+            .enumerate()
+            .filter_map(|(index, statement)| match statement {
+                Node::Literal(statement) => {
+                    Some((format!("{}/Statement/{}", path, index), statement))
+                }
+                Node::Computed(_) => None,
+            })
+            .collect()
+    }
+
+    pub fn validate(&self, path: &str, kind: PolicyKind, diagnostics: &mut Vec<Diagnostic>) {
+        for key in self.other.keys() {
+            diagnostics.push(Diagnostic::error(
+                format!("{}/{}", path, key),
+                format!("`{}` is not a policy element", key),
+            ));
+        }
+        match self.version.as_ref().map(Value::as_str) {
+            None => diagnostics.push(Diagnostic::warning(
+                path,
+                "`Version` is missing, so IAM uses 2008-10-17 which has no policy variables; set it to 2012-10-17",
+            )),
+            Some(Some(version)) if !VERSIONS.contains(&version) => {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/Version", path),
+                    format!(
+                        "`{}` is not a policy version, expected {}",
+                        version,
+                        VERSIONS.join(" or ")
+                    ),
+                ))
+            }
+            _ => {}
+        }
+        if self.statement.is_none() {
+            diagnostics.push(Diagnostic::error(path, "`Statement` is required"));
+        }
+        for (path, statement) in self.statements(path) {
+            statement.validate(&path, kind, diagnostics);
+        }
+    }
+}
+
+impl Statement {
+    /// Whether the statement grants, rather than denies, access.
+    pub fn is_allow(&self) -> bool {
+        self.effect.as_ref().and_then(Value::as_str) == Some("Allow")
+    }
+
+    fn validate(&self, path: &str, kind: PolicyKind, diagnostics: &mut Vec<Diagnostic>) {
+        for key in self.other.keys() {
+            diagnostics.push(Diagnostic::error(
+                format!("{}/{}", path, key),
+                format!("`{}` is not a statement element", key),
+            ));
+        }
+
+        match self.effect.as_ref().map(Value::as_str) {
+            None => diagnostics.push(Diagnostic::error(path, "`Effect` is required")),
+            Some(Some("Allow" | "Deny")) | Some(None) => {}
+            Some(Some(effect)) => diagnostics.push(Diagnostic::error(
+                format!("{}/Effect", path),
+                format!("`{}` is not an effect, expected Allow or Deny", effect),
+            )),
+        }
+
+        let principal = exclusive(
+            path,
+            ("Principal", &self.principal),
+            ("NotPrincipal", &self.not_principal),
+            diagnostics,
+        );
+        match (kind, principal) {
+            (PolicyKind::Identity, Some((name, _))) => diagnostics.push(Diagnostic::error(
+                format!("{}/{}", path, name),
+                format!("`{}` cannot be used in an identity-based policy", name),
+            )),
+            (PolicyKind::Resource | PolicyKind::Trust, None) => diagnostics.push(
+                Diagnostic::error(path, "one of `Principal` and `NotPrincipal` is required"),
+            ),
+            (_, Some((name, principal))) => {
+                check_principal(principal, &format!("{}/{}", path, name), diagnostics)
+            }
+            _ => {}
+        }
+
+        match exclusive(
+            path,
+            ("Action", &self.action),
+            ("NotAction", &self.not_action),
+            diagnostics,
+        ) {
+            Some((name, actions)) => {
+                for (index, action) in items(actions) {
+                    check_action(action, &item_path(path, name, index), diagnostics);
+                }
+            }
+            None => diagnostics.push(Diagnostic::error(
+                path,
+                "one of `Action` and `NotAction` is required",
+            )),
+        }
+
+        match exclusive(
+            path,
+            ("Resource", &self.resource),
+            ("NotResource", &self.not_resource),
+            diagnostics,
+        ) {
+            Some((name, resources)) => {
+                for (index, resource) in items(resources) {
+                    if resource.as_str() != Some("*") {
+                        check_arn(resource, &item_path(path, name, index), diagnostics);
+                    }
+                }
+            }
+            None if kind != PolicyKind::Trust => diagnostics.push(Diagnostic::error(
+                path,
+                "one of `Resource` and `NotResource` is required",
+            )),
+            None => {}
+        }
+
+        if let Some(condition) = &self.condition {
+            check_condition(condition, &format!("{}/Condition", path), diagnostics);
+        }
+    }
+}
+
+/// The written-out policy documents of the template's IAM roles, users and
+/// policies and its bucket, topic and queue policies, with their paths.
+pub fn policy_documents(template: &Template) -> Vec<(String, PolicyKind, &PolicyDocument)> {
+    let mut documents = Vec::new();
+    for (name, resource) in &template.resources {
+        let path = format!("Resources/{}/Properties", name);
+        let policies = match resource {
+            Resource::Role(role) => {
+                documents.push((
+                    format!("{}/AssumeRolePolicyDocument", path),
+                    PolicyKind::Trust,
+                    &role.properties.assume_role_policy_document,
+                ));
+                &role.properties.policies
+            }
+            Resource::User(ResourceContainer {
+                properties: Some(user),
+                ..
+            }) => &user.policies,
+            Resource::Policy(policy) => {
+                documents.push((
+                    format!("{}/PolicyDocument", path),
+                    PolicyKind::Identity,
+                    &policy.properties.policy_document,
+                ));
+                continue;
+            }
+            Resource::ManagedPolicy(policy) => {
+                documents.push((
+                    format!("{}/PolicyDocument", path),
+                    PolicyKind::Identity,
+                    &policy.properties.policy_document,
+                ));
+                continue;
+            }
+            Resource::BucketPolicy(policy) => {
+                documents.push((
+                    format!("{}/PolicyDocument", path),
+                    PolicyKind::Resource,
+                    &policy.properties.policy_document,
+                ));
+                continue;
+            }
+            Resource::TopicPolicy(policy) => {
+                documents.push((
+                    format!("{}/PolicyDocument", path),
+                    PolicyKind::Resource,
+                    &policy.properties.policy_document,
+                ));
+                continue;
+            }
+            Resource::QueuePolicy(policy) => {
+                documents.push((
+                    format!("{}/PolicyDocument", path),
+                    PolicyKind::Resource,
+                    &policy.properties.policy_document,
+                ));
+                continue;
+            }
+            _ => continue,
+        };
+        for (index, policy) in policies.iter().flat_map(Node::literals) {
+            documents.push((
+                format!("{}/Policies/{}/PolicyDocument", path, index),
+                PolicyKind::Identity,
+                &policy.policy_document,
+            ));
+        }
+    }
+    documents
+        .into_iter()
+        .filter_map(|(path, kind, document)| match document {
+            Node::Literal(document) => Some((path, kind, document)),
+            Node::Computed(_) => None,
+        })
+        .collect()
+}
+
+/// Returns whichever of two exclusive elements is set, reporting both being
+/// set.
+fn exclusive<'a>(
+    path: &str,
+    (name, value): (&'static str, &'a Option<Value>),
+    (other_name, other): (&'static str, &'a Option<Value>),
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<(&'static str, &'a Value)> {
+    match (value, other) {
+        (Some(value), None) => Some((name, value)),
+        (None, Some(other)) => Some((other_name, other)),
+        (Some(value), Some(_)) => {
+            diagnostics.push(Diagnostic::error(
+                format!("{}/{}", path, other_name),
+                format!("`{}` cannot be used with `{}`", other_name, name),
+            ));
+            Some((name, value))
+        }
+        (None, None) => None,
+    }
+}
+
+/// The elements of a value that may be one item or a list, with their
+/// indexes in the list.
+pub fn items(value: &Value) -> Vec<(Option<usize>, &Value)> {
+    match value {
+        Value::List(values) => values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (Some(i), v))
+            .collect(),
+        value => vec![(None, value)],
+    }
+}
+
+/// The string literals of a value that may be one item or a list.
+pub fn strings(value: &Option<Value>) -> Vec<&str> {
+    match value {
+        Some(value) => items(value)
+            .into_iter()
+            .filter_map(|(_, value)| value.as_str())
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+fn item_path(path: &str, name: &str, index: Option<usize>) -> String {
+    match index {
+        Some(index) => format!("{}/{}/{}", path, name, index),
+        None => format!("{}/{}", path, name),
+    }
+}
+
+/// The text of a string literal or an `Fn::Sub` template, with `${...}`
+/// placeholders replaced so the shape can still be checked. A template that
+/// starts with a placeholder, like `${Bucket.Arn}/*`, may get its whole shape
+/// from it and is left out.
+fn literal_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(string) => Some(string.clone()),
+        Value::Sub { sub, .. } if !sub.starts_with("${") => {
+            let mut text = String::new();
+            let mut rest = sub.as_str();
+            while let Some(start) = rest.find("${") {
+                text.push_str(&rest[..start]);
+                match rest[start..].find('}') {
+                    Some(end) => {
+                        text.push('x');
+                        rest = &rest[start + end + 1..];
+                    }
+                    None => {
+                        rest = &rest[start..];
+                        break;
+                    }
+                }
+            }
+            text.push_str(rest);
+            Some(text)
+        }
+        _ => None,
+    }
+}
+
+/// Reports a literal that lacks the `arn:partition:service:region:account:resource`
+/// shape. `Fn::Sub` templates are checked with their placeholders filled in.
+pub fn check_arn(value: &Value, path: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(text) = literal_text(value) else {
+        return;
+    };
+    let parts: Vec<&str> = text.splitn(6, ':').collect();
+    if parts.len() != 6 || parts[0] != "arn" || parts[1].is_empty() || parts[2].is_empty() {
+        let written = match value {
+            Value::Sub { sub, .. } => sub,
+            _ => &text,
+        };
+        diagnostics.push(Diagnostic::error(
+            path,
+            format!("`{}` is not an ARN", written),
+        ));
+    }
+}
+
+fn check_action(action: &Value, path: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(action) = action.as_str() else {
+        return;
+    };
+    let valid = action == "*"
+        || action.split_once(':').is_some_and(|(service, name)| {
+            !service.is_empty()
+                && !name.is_empty()
+                && service
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-')
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '*' || c == '?')
+        });
+    if !valid {
+        diagnostics.push(Diagnostic::error(
+            path,
+            format!("`{}` is not an action, expected `service:Action`", action),
+        ));
+    }
+}
+
+fn check_principal(principal: &Value, path: &str, diagnostics: &mut Vec<Diagnostic>) {
+    match principal {
+        Value::String(principal) if principal == "*" => {}
+        Value::Map(types) => {
+            for key in types.keys() {
+                if !PRINCIPAL_TYPES.contains(&key.as_str()) {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/{}", path, key),
+                        format!(
+                            "`{}` is not a principal type, expected {}",
+                            key,
+                            PRINCIPAL_TYPES.join(", ")
+                        ),
+                    ));
+                }
+            }
+        }
+        Value::String(_) | Value::List(_) | Value::Number(_) | Value::Bool(_) => {
+            diagnostics.push(Diagnostic::error(
+                path,
+                "a principal must be `*` or map principal types to identifiers",
+            ))
+        }
+        _ => {}
+    }
+}
+
+fn check_condition(condition: &Value, path: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let operators = match condition {
+        Value::Map(operators) => operators,
+        value if value.as_intrinsic().is_some() => return,
+        _ => {
+            diagnostics.push(Diagnostic::error(
+                path,
+                "a condition must map operators to condition keys",
+            ));
+            return;
+        }
+    };
+    for (operator, keys) in operators {
+        let path = format!("{}/{}", path, operator);
+        if !is_condition_operator(operator) {
+            diagnostics.push(Diagnostic::error(
+                &path,
+                format!("`{}` is not a condition operator", operator),
+            ));
+        }
+        if !matches!(keys, Value::Map(_)) && keys.as_intrinsic().is_none() {
+            diagnostics.push(Diagnostic::error(
+                path,
+                format!("`{}` must map condition keys to values", operator),
+            ));
+        }
+    }
+}
+
+/// Accepts an operator with an optional `ForAllValues:` or `ForAnyValue:`
+/// set prefix and an `IfExists` suffix, which `Null` does not take.
+fn is_condition_operator(operator: &str) -> bool {
+    let operator = operator
+        .strip_prefix("ForAllValues:")
+        .or_else(|| operator.strip_prefix("ForAnyValue:"))
+        .unwrap_or(operator);
+    match operator.strip_suffix("IfExists") {
+        Some("Null") => false,
+        Some(operator) => CONDITION_OPERATORS.contains(&operator),
+        None => CONDITION_OPERATORS.contains(&operator),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OneOrMany, PolicyDocument, PolicyKind};
+    use crate::{
+        diagnostic::Diagnostic,
+        value::{Node, Value},
+    };
+
+    fn parse(yaml: &str) -> PolicyDocument {
+        let document = crate::yaml::from_str(yaml).unwrap();
+        serde_json::from_value(serde_json::to_value(document).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_deserialize_policy_document() {
+        let document = parse(
+            r#"
+Version: "2012-10-17"
+Statement:
+  - Effect: Allow
+    Action: s3:GetObject
+    Resource: !Sub arn:${AWS::Partition}:s3:::${Bucket}/*
+  - !If
+    - IsProduction
+    - Effect: Deny
+      Action: "*"
+      Resource: "*"
+    - !Ref AWS::NoValue
+"#,
+        );
+        let Some(OneOrMany::Many(statements)) = &document.statement else {
+            panic!("expected a statement list");
+        };
+        assert!(matches!(statements[0], Node::Literal(_)));
+        assert!(matches!(statements[1], Node::Computed(Value::If { .. })));
+        assert_eq!(1, document.statements("Policy").len());
+    }
+
+    #[test]
+    fn test_validate_policy_document() {
+        let document = parse(
+            r#"
+Version: "2012-10-17"
+Statements: []
+Statement:
+  - Effect: allow
+    Principal:
+      Services: ec2.amazonaws.com
+    Action: [s3:GetObject, s3GetObject]
+    Resource: [!Sub "arn:${AWS::Partition}:s3:::${Bucket}/*", "bucket/*"]
+    Condition:
+      StringEqualz:
+        aws:SourceVpc: vpc-123
+      NullIfExists: {aws:TokenIssueTime: true}
+      ForAnyValue:StringLikeIfExists: {aws:TagKeys: [team-*]}
+  - Effect: Deny
+    Action: "*"
+    NotAction: iam:*
+"#,
+        );
+        let expected = vec![
+            Diagnostic::error("Policy/Statements", "`Statements` is not a policy element"),
+            Diagnostic::error(
+                "Policy/Statement/0/Effect",
+                "`allow` is not an effect, expected Allow or Deny",
+            ),
+            Diagnostic::error(
+                "Policy/Statement/0/Principal",
+                "`Principal` cannot be used in an identity-based policy",
+            ),
+            Diagnostic::error(
+                "Policy/Statement/0/Action/1",
+                "`s3GetObject` is not an action, expected `service:Action`",
+            ),
+            Diagnostic::error("Policy/Statement/0/Resource/1", "`bucket/*` is not an ARN"),
+            Diagnostic::error(
+                "Policy/Statement/0/Condition/StringEqualz",
+                "`StringEqualz` is not a condition operator",
+            ),
+            Diagnostic::error(
+                "Policy/Statement/0/Condition/NullIfExists",
+                "`NullIfExists` is not a condition operator",
+            ),
+            Diagnostic::error(
+                "Policy/Statement/1/NotAction",
+                "`NotAction` cannot be used with `Action`",
+            ),
+            Diagnostic::error(
+                "Policy/Statement/1",
+                "one of `Resource` and `NotResource` is required",
+            ),
+        ];
+
+        let mut diagnostics = Vec::new();
+        document.validate("Policy", PolicyKind::Identity, &mut diagnostics);
+        assert_eq!(expected, diagnostics);
+    }
+}
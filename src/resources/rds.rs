@@ -0,0 +1,614 @@
+use crate::{
+    diagnostic::Diagnostic,
+    tag::Tag,
+    validate::{check_boolean, check_one_of, check_range, Validate},
+    value::{Node, Value},
+    Template,
+};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// Engines with the major versions RDS supports, as version prefixes.
+///
+/// The table is maintained by hand from the `MajorEngineVersion`s that
+/// `aws rds describe-db-engine-versions --engine <engine>` lists. RDS adds
+/// and retires versions on its own schedule, so a version missing from it is
+/// only a warning; refresh the table when RDS announces a new major version.
+const ENGINE_VERSIONS: &[(&str, &[&str])] = &[
+    ("aurora-mysql", &["5.7", "8.0"]),
+    (
+        "aurora-postgresql",
+        &["11", "12", "13", "14", "15", "16", "17"],
+    ),
+    ("db2-ae", &["11.5"]),
+    ("db2-se", &["11.5"]),
+    ("mariadb", &["10.4", "10.5", "10.6", "10.11", "11.4"]),
+    ("mysql", &["5.7", "8.0", "8.4"]),
+    ("oracle-ee", &["19", "21"]),
+    ("oracle-ee-cdb", &["19", "21"]),
+    ("oracle-se2", &["19", "21"]),
+    ("oracle-se2-cdb", &["19", "21"]),
+    ("postgres", &["11", "12", "13", "14", "15", "16", "17"]),
+    ("sqlserver-ee", &["13.00", "14.00", "15.00", "16.00"]),
+    ("sqlserver-ex", &["13.00", "14.00", "15.00", "16.00"]),
+    ("sqlserver-se", &["13.00", "14.00", "15.00", "16.00"]),
+    ("sqlserver-web", &["13.00", "14.00", "15.00", "16.00"]),
+];
+
+/// Engines a `DBCluster` can run: Aurora, and Multi-AZ clusters of MySQL and
+/// PostgreSQL.
+const CLUSTER_ENGINES: &[&str] = &["aurora-mysql", "aurora-postgresql", "mysql", "postgres"];
+
+const STORAGE_TYPES: &[&str] = &["gp2", "gp3", "io1", "io2", "standard"];
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct DbInstance {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allocated_storage: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_retention_period: Option<Value>,
+    #[serde(
+        rename = "DBClusterIdentifier",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub db_cluster_identifier: Option<Value>,
+    #[serde(rename = "DBInstanceClass", skip_serializing_if = "Option::is_none")]
+    pub db_instance_class: Option<Value>,
+    #[serde(
+        rename = "DBInstanceIdentifier",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub db_instance_identifier: Option<Value>,
+    #[serde(rename = "DBName", skip_serializing_if = "Option::is_none")]
+    pub db_name: Option<Value>,
+    #[serde(
+        rename = "DBSnapshotIdentifier",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub db_snapshot_identifier: Option<Value>,
+    #[serde(rename = "DBSubnetGroupName", skip_serializing_if = "Option::is_none")]
+    pub db_subnet_group_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deletion_protection: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub engine: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub engine_version: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iops: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kms_key_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manage_master_user_password: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub master_user_password: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub master_username: Option<Value>,
+    #[serde(rename = "MultiAZ", skip_serializing_if = "Option::is_none")]
+    pub multi_az: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publicly_accessible: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_encrypted: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_type: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+    #[serde(rename = "VPCSecurityGroups", skip_serializing_if = "Option::is_none")]
+    pub vpc_security_groups: Option<Node<Vec<Value>>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct DbCluster {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allocated_storage: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_retention_period: Option<Value>,
+    #[serde(
+        rename = "DBClusterIdentifier",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub db_cluster_identifier: Option<Value>,
+    #[serde(
+        rename = "DBClusterInstanceClass",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub db_cluster_instance_class: Option<Value>,
+    #[serde(rename = "DBSubnetGroupName", skip_serializing_if = "Option::is_none")]
+    pub db_subnet_group_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub database_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deletion_protection: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub engine: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub engine_mode: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub engine_version: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iops: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kms_key_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manage_master_user_password: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub master_user_password: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub master_username: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serverless_v2_scaling_configuration: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_identifier: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_encrypted: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_type: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vpc_security_group_ids: Option<Node<Vec<Value>>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct DbSubnetGroup {
+    #[serde(rename = "DBSubnetGroupDescription")]
+    pub db_subnet_group_description: Value,
+    #[serde(rename = "DBSubnetGroupName", skip_serializing_if = "Option::is_none")]
+    pub db_subnet_group_name: Option<Value>,
+    pub subnet_ids: Node<Vec<Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+}
+
+/// Reports a literal engine version that is not a supported major version of
+/// the engine.
+fn check_engine_version(
+    engine: &str,
+    version: &Value,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let (Some(version), Some((_, majors))) = (
+        version.as_str(),
+        ENGINE_VERSIONS.iter().find(|(name, _)| *name == engine),
+    ) else {
+        return;
+    };
+    let supported = majors.iter().any(|major| {
+        version == *major
+            || version
+                .strip_prefix(major)
+                .is_some_and(|rest| rest.starts_with('.'))
+    });
+    if !supported {
+        diagnostics.push(Diagnostic::warning(
+            path,
+            format!(
+                "`{}` is not a known version of `{}`, expected one of {}",
+                version,
+                engine,
+                majors.join(", ")
+            ),
+        ));
+    }
+}
+
+/// The allocated storage in GiB an instance can have.
+fn storage_bounds(engine: &str, storage_type: &str) -> RangeInclusive<i64> {
+    let max = if engine.starts_with("sqlserver") {
+        16384
+    } else {
+        65536
+    };
+    match storage_type {
+        "standard" if engine.starts_with("oracle") => 10..=3072,
+        "standard" => 5..=3072,
+        "io1" | "io2" if !engine.starts_with("sqlserver") => 100..=max,
+        _ => 20..=max,
+    }
+}
+
+impl Validate for DbInstance {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        let booleans = [
+            ("DeletionProtection", &self.deletion_protection),
+            (
+                "ManageMasterUserPassword",
+                &self.manage_master_user_password,
+            ),
+            ("MultiAZ", &self.multi_az),
+            ("PubliclyAccessible", &self.publicly_accessible),
+            ("StorageEncrypted", &self.storage_encrypted),
+        ];
+        for (name, value) in booleans {
+            if let Some(value) = value {
+                check_boolean(value, &format!("{}/{}", path, name), diagnostics);
+            }
+        }
+
+        let engines: Vec<&str> = ENGINE_VERSIONS.iter().map(|(name, _)| *name).collect();
+        if let Some(engine) = &self.engine {
+            check_one_of(
+                engine,
+                &engines,
+                "engine",
+                &format!("{}/Engine", path),
+                diagnostics,
+            );
+        }
+        let engine = self.engine.as_ref().and_then(Value::as_str);
+        // Aurora instances take their version and storage from the cluster.
+        if self.db_cluster_identifier.is_some() || engine.is_some_and(|e| e.starts_with("aurora")) {
+            return;
+        }
+        if let (Some(engine), Some(version)) = (engine, &self.engine_version) {
+            check_engine_version(
+                engine,
+                version,
+                &format!("{}/EngineVersion", path),
+                diagnostics,
+            );
+        }
+
+        if let Some(storage_type) = &self.storage_type {
+            check_one_of(
+                storage_type,
+                STORAGE_TYPES,
+                "storage type",
+                &format!("{}/StorageType", path),
+                diagnostics,
+            );
+        }
+        let storage_type = match &self.storage_type {
+            Some(storage_type) => storage_type.as_str(),
+            None => Some("gp2"),
+        };
+        if matches!(storage_type, Some("io1" | "io2")) && self.iops.is_none() {
+            diagnostics.push(Diagnostic::error(
+                path,
+                "`Iops` is required for `io1` and `io2` storage",
+            ));
+        }
+        if self.db_snapshot_identifier.is_none() && self.allocated_storage.is_none() {
+            diagnostics.push(Diagnostic::error(
+                path,
+                "`AllocatedStorage` is required unless restoring from `DBSnapshotIdentifier`",
+            ));
+        }
+        if let (Some(engine), Some(storage_type)) = (engine, storage_type) {
+            check_range(
+                &self.allocated_storage,
+                storage_bounds(engine, storage_type),
+                &format!("{} storage for `{}` in GiB", storage_type, engine),
+                &format!("{}/AllocatedStorage", path),
+                diagnostics,
+            );
+        }
+    }
+}
+
+impl Validate for DbCluster {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        let booleans = [
+            ("DeletionProtection", &self.deletion_protection),
+            (
+                "ManageMasterUserPassword",
+                &self.manage_master_user_password,
+            ),
+            ("StorageEncrypted", &self.storage_encrypted),
+        ];
+        for (name, value) in booleans {
+            if let Some(value) = value {
+                check_boolean(value, &format!("{}/{}", path, name), diagnostics);
+            }
+        }
+
+        let Some(engine) = &self.engine else {
+            diagnostics.push(Diagnostic::error(path, "`Engine` is required"));
+            return;
+        };
+        check_one_of(
+            engine,
+            CLUSTER_ENGINES,
+            "cluster engine",
+            &format!("{}/Engine", path),
+            diagnostics,
+        );
+        let Some(engine) = engine.as_str() else {
+            return;
+        };
+        if let Some(version) = &self.engine_version {
+            check_engine_version(
+                engine,
+                version,
+                &format!("{}/EngineVersion", path),
+                diagnostics,
+            );
+        }
+        // Multi-AZ clusters size their storage like instances do.
+        if !engine.starts_with("aurora") {
+            for (name, value) in [
+                ("AllocatedStorage", &self.allocated_storage),
+                ("DBClusterInstanceClass", &self.db_cluster_instance_class),
+                ("Iops", &self.iops),
+            ] {
+                if value.is_none() {
+                    diagnostics.push(Diagnostic::error(
+                        path,
+                        format!("`{}` is required for a Multi-AZ `{}` cluster", name, engine),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+impl Validate for DbSubnetGroup {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        if self
+            .subnet_ids
+            .literal()
+            .is_some_and(|subnets| subnets.len() < 2)
+        {
+            diagnostics.push(Diagnostic::error(
+                format!("{}/SubnetIds", path),
+                "a DB subnet group needs subnets in at least two Availability Zones",
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{diagnostic::Diagnostic, document::Format, Template};
+
+    #[test]
+    fn test_validate_db_instance_engine() {
+        let yaml = r#"
+Parameters:
+  Engine:
+    Type: String
+Resources:
+  Oracle:
+    Type: AWS::RDS::DBInstance
+    Properties:
+      Engine: oracle-xe
+      AllocatedStorage: 20
+  FromParameter:
+    Type: AWS::RDS::DBInstance
+    Properties:
+      Engine: !Ref Engine
+      AllocatedStorage: 20
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Oracle/Properties/Engine",
+            "`oracle-xe` is not a valid engine, expected one of aurora-mysql, aurora-postgresql, db2-ae, db2-se, mariadb, mysql, oracle-ee, oracle-ee-cdb, oracle-se2, oracle-se2-cdb, postgres, sqlserver-ee, sqlserver-ex, sqlserver-se, sqlserver-web",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_db_instance_engine_version() {
+        let yaml = r#"
+Resources:
+  Current:
+    Type: AWS::RDS::DBInstance
+    Properties:
+      Engine: postgres
+      EngineVersion: "16.3"
+      AllocatedStorage: 20
+  Legacy:
+    Type: AWS::RDS::DBInstance
+    Properties:
+      Engine: mysql
+      EngineVersion: 5.6.51
+      AllocatedStorage: 20
+  Prefix:
+    Type: AWS::RDS::DBInstance
+    Properties:
+      Engine: mysql
+      EngineVersion: "8.01"
+      AllocatedStorage: 20
+  Member:
+    Type: AWS::RDS::DBInstance
+    Properties:
+      Engine: aurora-postgresql
+      EngineVersion: "10.21"
+      DBClusterIdentifier: cluster
+"#;
+        let expected = vec![
+            Diagnostic::warning(
+                "Resources/Legacy/Properties/EngineVersion",
+                "`5.6.51` is not a known version of `mysql`, expected one of 5.7, 8.0, 8.4",
+            ),
+            Diagnostic::warning(
+                "Resources/Prefix/Properties/EngineVersion",
+                "`8.01` is not a known version of `mysql`, expected one of 5.7, 8.0, 8.4",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_db_instance_storage() {
+        let yaml = r#"
+Resources:
+  Unsized:
+    Type: AWS::RDS::DBInstance
+    Properties:
+      Engine: postgres
+  Restored:
+    Type: AWS::RDS::DBInstance
+    Properties:
+      Engine: postgres
+      DBSnapshotIdentifier: snapshot
+  Provisioned:
+    Type: AWS::RDS::DBInstance
+    Properties:
+      Engine: mysql
+      StorageType: io1
+      AllocatedStorage: 50
+  Oracle:
+    Type: AWS::RDS::DBInstance
+    Properties:
+      Engine: oracle-ee
+      StorageType: standard
+      AllocatedStorage: 5
+  Magnetic:
+    Type: AWS::RDS::DBInstance
+    Properties:
+      Engine: postgres
+      StorageType: magnetic
+      AllocatedStorage: 20
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Unsized/Properties",
+                "`AllocatedStorage` is required unless restoring from `DBSnapshotIdentifier`",
+            ),
+            Diagnostic::error(
+                "Resources/Provisioned/Properties",
+                "`Iops` is required for `io1` and `io2` storage",
+            ),
+            Diagnostic::error(
+                "Resources/Provisioned/Properties/AllocatedStorage",
+                "io1 storage for `mysql` in GiB must be between 100 and 65536, not 50",
+            ),
+            Diagnostic::error(
+                "Resources/Oracle/Properties/AllocatedStorage",
+                "standard storage for `oracle-ee` in GiB must be between 10 and 3072, not 5",
+            ),
+            Diagnostic::error(
+                "Resources/Magnetic/Properties/StorageType",
+                "`magnetic` is not a valid storage type, expected one of gp2, gp3, io1, io2, standard",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_db_instance_booleans() {
+        let yaml = r#"
+Resources:
+  Database:
+    Type: AWS::RDS::DBInstance
+    Properties:
+      Engine: postgres
+      AllocatedStorage: 20
+      StorageEncrypted: "yes"
+      MultiAZ: !If [IsProduction, true, false]
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Database/Properties/StorageEncrypted",
+            "expected a boolean, `true` or `false`",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_db_cluster() {
+        let yaml = r#"
+Parameters:
+  Engine:
+    Type: String
+Resources:
+  Unnamed:
+    Type: AWS::RDS::DBCluster
+    Properties:
+      DatabaseName: app
+  Aurora:
+    Type: AWS::RDS::DBCluster
+    Properties:
+      Engine: aurora-postgresql
+      EngineVersion: "10.21"
+  MultiAz:
+    Type: AWS::RDS::DBCluster
+    Properties:
+      Engine: mysql
+      EngineVersion: 8.0.39
+      AllocatedStorage: 100
+      DBClusterInstanceClass: db.m6gd.large
+  Mariadb:
+    Type: AWS::RDS::DBCluster
+    Properties:
+      Engine: mariadb
+      AllocatedStorage: 100
+      DBClusterInstanceClass: db.m6gd.large
+      Iops: 3000
+  FromParameter:
+    Type: AWS::RDS::DBCluster
+    Properties:
+      Engine: !Ref Engine
+"#;
+        let expected = vec![
+            Diagnostic::error("Resources/Unnamed/Properties", "`Engine` is required"),
+            Diagnostic::warning(
+                "Resources/Aurora/Properties/EngineVersion",
+                "`10.21` is not a known version of `aurora-postgresql`, expected one of 11, 12, 13, 14, 15, 16, 17",
+            ),
+            Diagnostic::error(
+                "Resources/MultiAz/Properties",
+                "`Iops` is required for a Multi-AZ `mysql` cluster",
+            ),
+            Diagnostic::error(
+                "Resources/Mariadb/Properties/Engine",
+                "`mariadb` is not a valid cluster engine, expected one of aurora-mysql, aurora-postgresql, mysql, postgres",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_subnet_group_subnets() {
+        let yaml = r#"
+Resources:
+  SubnetGroup:
+    Type: AWS::RDS::DBSubnetGroup
+    Properties:
+      DBSubnetGroupDescription: Database subnets
+      SubnetIds: [subnet-123]
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/SubnetGroup/Properties/SubnetIds",
+            "a DB subnet group needs subnets in at least two Availability Zones",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_subnet_group_subnets_from_parameter() {
+        let yaml = r#"
+Parameters:
+  Subnets:
+    Type: CommaDelimitedList
+Resources:
+  SubnetGroup:
+    Type: AWS::RDS::DBSubnetGroup
+    Properties:
+      DBSubnetGroupDescription: Database subnets
+      SubnetIds: !Ref Subnets
+"#;
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(Vec::<Diagnostic>::new(), template.validate());
+    }
+}
@@ -0,0 +1,337 @@
+use super::{referenced_resource, Resource};
+use crate::{
+    cidr::Cidr,
+    diagnostic::Diagnostic,
+    tag::Tag,
+    validate::Validate,
+    value::{Node, Value},
+    Template,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct RouteTable {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+    pub vpc_id: Value,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Route {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub carrier_gateway_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub core_network_arn: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_cidr_block: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_ipv6_cidr_block: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_prefix_list_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub egress_only_internet_gateway_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gateway_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_gateway_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nat_gateway_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_interface_id: Option<Value>,
+    pub route_table_id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transit_gateway_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vpc_endpoint_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vpc_peering_connection_id: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct SubnetRouteTableAssociation {
+    pub route_table_id: Value,
+    pub subnet_id: Value,
+}
+
+impl Route {
+    /// The logical name of the internet gateway in this template the route
+    /// sends traffic to.
+    pub fn internet_gateway<'a>(&'a self, template: &Template) -> Option<&'a str> {
+        let name = referenced_resource(self.gateway_id.as_ref()?)?;
+        matches!(template.resource(name), Some(Resource::InternetGateway(_))).then_some(name)
+    }
+}
+
+impl Validate for Route {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        let destinations = [
+            ("DestinationCidrBlock", &self.destination_cidr_block),
+            (
+                "DestinationIpv6CidrBlock",
+                &self.destination_ipv6_cidr_block,
+            ),
+            ("DestinationPrefixListId", &self.destination_prefix_list_id),
+        ];
+        let targets = [
+            ("CarrierGatewayId", &self.carrier_gateway_id),
+            ("CoreNetworkArn", &self.core_network_arn),
+            (
+                "EgressOnlyInternetGatewayId",
+                &self.egress_only_internet_gateway_id,
+            ),
+            ("GatewayId", &self.gateway_id),
+            ("InstanceId", &self.instance_id),
+            ("LocalGatewayId", &self.local_gateway_id),
+            ("NatGatewayId", &self.nat_gateway_id),
+            ("NetworkInterfaceId", &self.network_interface_id),
+            ("TransitGatewayId", &self.transit_gateway_id),
+            ("VpcEndpointId", &self.vpc_endpoint_id),
+            ("VpcPeeringConnectionId", &self.vpc_peering_connection_id),
+        ];
+        for (kind, properties) in [("destination", &destinations[..]), ("target", &targets[..])] {
+            let set: Vec<_> = properties
+                .iter()
+                .filter(|(_, value)| value.is_some())
+                .map(|(name, _)| format!("`{}`", name))
+                .collect();
+            match set.len() {
+                0 => diagnostics.push(Diagnostic::error(path, format!("route has no {}", kind))),
+                1 => {}
+                _ => diagnostics.push(Diagnostic::error(
+                    path,
+                    format!("route has more than one {}: {}", kind, set.join(", ")),
+                )),
+            }
+        }
+
+        let blocks = [
+            ("DestinationCidrBlock", &self.destination_cidr_block, "IPv4"),
+            (
+                "DestinationIpv6CidrBlock",
+                &self.destination_ipv6_cidr_block,
+                "IPv6",
+            ),
+        ];
+        for (name, block, family) in blocks {
+            let Some(block) = block.as_ref().and_then(Value::as_str) else {
+                continue;
+            };
+            let parse = if family == "IPv4" {
+                Cidr::ipv4
+            } else {
+                Cidr::ipv6
+            };
+            if parse(block).is_none() {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/{}", path, name),
+                    format!("`{}` is not a valid {} CIDR block", block, family),
+                ));
+            }
+        }
+    }
+}
+
+/// Reports routes to an internet gateway that do not wait for the gateway to
+/// be attached to the VPC. Without `DependsOn`, CloudFormation may create the
+/// route first and fail.
+pub fn validate_routes(template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+    for (name, resource) in &template.resources {
+        let Resource::Route(route) = resource else {
+            continue;
+        };
+        let Some(gateway) = route.properties.internet_gateway(template) else {
+            continue;
+        };
+
+        let attachments: Vec<&str> = template
+            .resources
+            .iter()
+            .filter_map(|(name, resource)| match resource {
+                Resource::VpcGatewayAttachment(attachment) => {
+                    let attached = attachment.properties.internet_gateway_id.as_ref()?;
+                    (referenced_resource(attached)? == gateway).then_some(name.as_str())
+                }
+                _ => None,
+            })
+            .collect();
+        let path = format!("Resources/{}", name);
+        match attachments.first() {
+            None => diagnostics.push(Diagnostic::error(
+                format!("{}/Properties/GatewayId", path),
+                format!(
+                    "internet gateway `{}` is never attached to a VPC with a `VPCGatewayAttachment`",
+                    gateway
+                ),
+            )),
+            Some(attachment) => {
+                let depends_on = route.depends_on();
+                if !attachments.iter().any(|name| depends_on.contains(name)) {
+                    diagnostics.push(Diagnostic::warning(
+                        path,
+                        format!(
+                            "route uses internet gateway `{}` but does not depend on its attachment; add `DependsOn: {}`",
+                            gateway, attachment
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{diagnostic::Diagnostic, document::Format, Template};
+
+    #[test]
+    fn test_validate_route_targets() {
+        let yaml = r#"
+Resources:
+  Untargeted:
+    Type: AWS::EC2::Route
+    Properties:
+      RouteTableId: rtb-123
+      DestinationCidrBlock: 0.0.0.0/0
+  Ambiguous:
+    Type: AWS::EC2::Route
+    Properties:
+      RouteTableId: rtb-123
+      DestinationCidrBlock: 0.0.0.0/0
+      NatGatewayId: nat-123
+      InstanceId: i-123
+"#;
+        let expected = vec![
+            Diagnostic::error("Resources/Untargeted/Properties", "route has no target"),
+            Diagnostic::error(
+                "Resources/Ambiguous/Properties",
+                "route has more than one target: `InstanceId`, `NatGatewayId`",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_route_destinations() {
+        let yaml = r#"
+Parameters:
+  Destination:
+    Type: String
+Resources:
+  Ambiguous:
+    Type: AWS::EC2::Route
+    Properties:
+      RouteTableId: rtb-123
+      DestinationCidrBlock: 0.0.0.0/0
+      DestinationIpv6CidrBlock: ::/0
+      NatGatewayId: nat-123
+  Invalid:
+    Type: AWS::EC2::Route
+    Properties:
+      RouteTableId: rtb-123
+      DestinationCidrBlock: 0.0.0.0/64
+      NatGatewayId: nat-123
+  InvalidIpv6:
+    Type: AWS::EC2::Route
+    Properties:
+      RouteTableId: rtb-123
+      DestinationIpv6CidrBlock: 10.0.0.0/8
+      NatGatewayId: nat-123
+  FromParameter:
+    Type: AWS::EC2::Route
+    Properties:
+      RouteTableId: rtb-123
+      DestinationCidrBlock: !Ref Destination
+      NatGatewayId: nat-123
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Ambiguous/Properties",
+                "route has more than one destination: `DestinationCidrBlock`, `DestinationIpv6CidrBlock`",
+            ),
+            Diagnostic::error(
+                "Resources/Invalid/Properties/DestinationCidrBlock",
+                "`0.0.0.0/64` is not a valid IPv4 CIDR block",
+            ),
+            Diagnostic::error(
+                "Resources/InvalidIpv6/Properties/DestinationIpv6CidrBlock",
+                "`10.0.0.0/8` is not a valid IPv6 CIDR block",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_route_depends_on_attachment() {
+        let yaml = r#"
+Resources:
+  Gateway:
+    Type: AWS::EC2::InternetGateway
+  Attachment:
+    Type: AWS::EC2::VPCGatewayAttachment
+    Properties:
+      VpcId: vpc-123
+      InternetGatewayId: !Ref Gateway
+  Waiting:
+    Type: AWS::EC2::Route
+    DependsOn: Attachment
+    Properties:
+      RouteTableId: rtb-123
+      DestinationCidrBlock: 0.0.0.0/0
+      GatewayId: !Ref Gateway
+  Racing:
+    Type: AWS::EC2::Route
+    DependsOn: [Gateway]
+    Properties:
+      RouteTableId: rtb-123
+      DestinationIpv6CidrBlock: ::/0
+      GatewayId: !Ref Gateway
+"#;
+        let expected = vec![Diagnostic::warning(
+            "Resources/Racing",
+            "route uses internet gateway `Gateway` but does not depend on its attachment; add `DependsOn: Attachment`",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_route_to_detached_gateway() {
+        let yaml = r#"
+Parameters:
+  Gateway:
+    Type: String
+Resources:
+  Detached:
+    Type: AWS::EC2::InternetGateway
+  DetachedRoute:
+    Type: AWS::EC2::Route
+    Properties:
+      RouteTableId: rtb-123
+      DestinationCidrBlock: 0.0.0.0/0
+      GatewayId: !Ref Detached
+  ParameterRoute:
+    Type: AWS::EC2::Route
+    Properties:
+      RouteTableId: rtb-123
+      DestinationCidrBlock: 0.0.0.0/0
+      GatewayId: !Ref Gateway
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/DetachedRoute/Properties/GatewayId",
+            "internet gateway `Detached` is never attached to a VPC with a `VPCGatewayAttachment`",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+}
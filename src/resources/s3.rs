@@ -0,0 +1,474 @@
+use super::policy_document::{PolicyDocument, PolicyKind};
+use crate::{
+    diagnostic::Diagnostic,
+    tag::Tag,
+    validate::{check_boolean, check_one_of, Validate},
+    value::{Node, Value},
+    Template,
+};
+use serde::{Deserialize, Serialize};
+
+const SSE_ALGORITHMS: &[&str] = &["AES256", "aws:kms", "aws:kms:dsse"];
+
+const CORS_METHODS: &[&str] = &["DELETE", "GET", "HEAD", "POST", "PUT"];
+
+const RESERVED_PREFIXES: &[&str] = &["xn--", "sthree-", "amzn-s3-demo-"];
+
+const RESERVED_SUFFIXES: &[&str] = &["-s3alias", "--ol-s3", ".mrap", "--x-s3"];
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Bucket {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_control: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bucket_encryption: Option<Node<BucketEncryption>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bucket_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cors_configuration: Option<Node<CorsConfiguration>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lifecycle_configuration: Option<Node<LifecycleConfiguration>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logging_configuration: Option<Node<LoggingConfiguration>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notification_configuration: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object_lock_configuration: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object_lock_enabled: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ownership_controls: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_access_block_configuration: Option<Node<PublicAccessBlockConfiguration>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replication_configuration: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub versioning_configuration: Option<Node<VersioningConfiguration>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub website_configuration: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct BucketEncryption {
+    pub server_side_encryption_configuration: Node<Vec<Node<ServerSideEncryptionRule>>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ServerSideEncryptionRule {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bucket_key_enabled: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_side_encryption_by_default: Option<Node<ServerSideEncryptionByDefault>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct ServerSideEncryptionByDefault {
+    #[serde(rename = "KMSMasterKeyID", skip_serializing_if = "Option::is_none")]
+    pub kms_master_key_id: Option<Value>,
+    #[serde(rename = "SSEAlgorithm")]
+    pub sse_algorithm: Value,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct VersioningConfiguration {
+    pub status: Value,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct PublicAccessBlockConfiguration {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_public_acls: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_public_policy: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_public_acls: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restrict_public_buckets: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct LoggingConfiguration {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_bucket_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_file_prefix: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct LifecycleConfiguration {
+    pub rules: Node<Vec<Node<LifecycleRule>>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct LifecycleRule {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abort_incomplete_multipart_upload: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiration_date: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiration_in_days: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub noncurrent_version_expiration: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub noncurrent_version_transitions: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<Value>,
+    pub status: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag_filters: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transitions: Option<Node<Vec<Value>>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct CorsConfiguration {
+    pub cors_rules: Node<Vec<Node<CorsRule>>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct CorsRule {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_headers: Option<Node<Vec<Value>>>,
+    pub allowed_methods: Node<Vec<Value>>,
+    pub allowed_origins: Node<Vec<Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exposed_headers: Option<Node<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct BucketPolicy {
+    pub bucket: Value,
+    pub policy_document: Node<PolicyDocument>,
+}
+
+impl PublicAccessBlockConfiguration {
+    /// The settings that are not turned on.
+    pub fn missing(&self) -> Vec<&'static str> {
+        [
+            ("BlockPublicAcls", &self.block_public_acls),
+            ("BlockPublicPolicy", &self.block_public_policy),
+            ("IgnorePublicAcls", &self.ignore_public_acls),
+            ("RestrictPublicBuckets", &self.restrict_public_buckets),
+        ]
+        .into_iter()
+        .filter(|(_, value)| match value {
+            Some(value) if value.is_no_value() => true,
+            Some(value) => value.as_intrinsic().is_none() && value.as_bool() != Some(true),
+            None => true,
+        })
+        .map(|(name, _)| name)
+        .collect()
+    }
+}
+
+/// Returns what is wrong with a bucket name, following the S3 naming rules
+/// for general purpose buckets.
+fn check_bucket_name(name: &str) -> Option<String> {
+    if !(3..=63).contains(&name.len()) {
+        return Some(format!(
+            "must be between 3 and 63 characters long, not {}",
+            name.len()
+        ));
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_ascii_lowercase() || c.is_ascii_digit() || *c == '.' || *c == '-'))
+    {
+        return Some(format!(
+            "can only contain lowercase letters, digits, `.` and `-`, not `{}`",
+            c
+        ));
+    }
+    let alphanumeric = |c: Option<char>| c.is_some_and(|c| c.is_ascii_alphanumeric());
+    if !alphanumeric(name.chars().next()) || !alphanumeric(name.chars().last()) {
+        return Some("must begin and end with a letter or digit".to_string());
+    }
+    if name.contains("..") {
+        return Some("cannot contain two adjacent periods".to_string());
+    }
+    if name.parse::<std::net::Ipv4Addr>().is_ok() {
+        return Some("cannot be formatted as an IP address".to_string());
+    }
+    if let Some(prefix) = RESERVED_PREFIXES.iter().find(|p| name.starts_with(*p)) {
+        return Some(format!(
+            "cannot start with the reserved prefix `{}`",
+            prefix
+        ));
+    }
+    if let Some(suffix) = RESERVED_SUFFIXES.iter().find(|s| name.ends_with(*s)) {
+        return Some(format!("cannot end with the reserved suffix `{}`", suffix));
+    }
+    None
+}
+
+impl Validate for Bucket {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        if let Some(name) = self.bucket_name.as_ref().and_then(Value::as_str) {
+            if let Some(problem) = check_bucket_name(name) {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/BucketName", path),
+                    format!("bucket name `{}` {}", name, problem),
+                ));
+            }
+        }
+
+        if let Some(encryption) = self.bucket_encryption.as_ref().and_then(Node::literal) {
+            let rules = encryption.server_side_encryption_configuration.literals();
+            for (index, rule) in rules {
+                let path = format!(
+                    "{}/BucketEncryption/ServerSideEncryptionConfiguration/{}",
+                    path, index
+                );
+                if let Some(enabled) = &rule.bucket_key_enabled {
+                    check_boolean(enabled, &format!("{}/BucketKeyEnabled", path), diagnostics);
+                }
+                let Some(default) = rule
+                    .server_side_encryption_by_default
+                    .as_ref()
+                    .and_then(Node::literal)
+                else {
+                    continue;
+                };
+                let path = format!("{}/ServerSideEncryptionByDefault", path);
+                check_one_of(
+                    &default.sse_algorithm,
+                    SSE_ALGORITHMS,
+                    "encryption algorithm",
+                    &format!("{}/SSEAlgorithm", path),
+                    diagnostics,
+                );
+                if default.sse_algorithm.as_str() == Some("AES256")
+                    && default.kms_master_key_id.is_some()
+                {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/KMSMasterKeyID", path),
+                        "`KMSMasterKeyID` requires a KMS algorithm, not `AES256`",
+                    ));
+                }
+            }
+        }
+
+        if let Some(versioning) = self
+            .versioning_configuration
+            .as_ref()
+            .and_then(Node::literal)
+        {
+            check_one_of(
+                &versioning.status,
+                &["Enabled", "Suspended"],
+                "versioning status",
+                &format!("{}/VersioningConfiguration/Status", path),
+                diagnostics,
+            );
+        }
+
+        if let Some(block) = self
+            .public_access_block_configuration
+            .as_ref()
+            .and_then(Node::literal)
+        {
+            let settings = [
+                ("BlockPublicAcls", &block.block_public_acls),
+                ("BlockPublicPolicy", &block.block_public_policy),
+                ("IgnorePublicAcls", &block.ignore_public_acls),
+                ("RestrictPublicBuckets", &block.restrict_public_buckets),
+            ];
+            for (name, value) in settings {
+                if let Some(value) = value {
+                    let path = format!("{}/PublicAccessBlockConfiguration/{}", path, name);
+                    check_boolean(value, &path, diagnostics);
+                }
+            }
+        }
+
+        if let Some(lifecycle) = self
+            .lifecycle_configuration
+            .as_ref()
+            .and_then(Node::literal)
+        {
+            for (index, rule) in lifecycle.rules.literals() {
+                check_one_of(
+                    &rule.status,
+                    &["Enabled", "Disabled"],
+                    "rule status",
+                    &format!("{}/LifecycleConfiguration/Rules/{}/Status", path, index),
+                    diagnostics,
+                );
+            }
+        }
+
+        if let Some(cors) = self.cors_configuration.as_ref().and_then(Node::literal) {
+            for (index, rule) in cors.cors_rules.literals() {
+                let path = format!("{}/CorsConfiguration/CorsRules/{}", path, index);
+                for (index, method) in rule.allowed_methods.items().iter().enumerate() {
+                    check_one_of(
+                        method,
+                        CORS_METHODS,
+                        "CORS method",
+                        &format!("{}/AllowedMethods/{}", path, index),
+                        diagnostics,
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl Validate for BucketPolicy {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        self.policy_document.validate(
+            &format!("{}/PolicyDocument", path),
+            PolicyKind::Resource,
+            diagnostics,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_bucket_name;
+    use crate::{diagnostic::Diagnostic, document::Format, Template};
+
+    #[test]
+    fn test_check_bucket_name() {
+        for name in ["logs", "my.example-bucket", "a1b", "192.168.5.4x"] {
+            assert_eq!(None, check_bucket_name(name), "{}", name);
+        }
+        for name in [
+            "ab",
+            "My-Bucket",
+            "bucket_name",
+            "-bucket",
+            "bucket.",
+            "my..bucket",
+            "192.168.5.4",
+            "xn--bucket",
+            "bucket-s3alias",
+            "bucket--x-s3",
+        ] {
+            assert!(check_bucket_name(name).is_some(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_validate_bucket() {
+        let yaml = r#"
+Resources:
+  Bucket:
+    Type: AWS::S3::Bucket
+    Properties:
+      BucketName: Logs_Bucket
+      BucketEncryption:
+        ServerSideEncryptionConfiguration:
+          - BucketKeyEnabled: true
+            ServerSideEncryptionByDefault:
+              SSEAlgorithm: AES256
+              KMSMasterKeyID: !Ref Key
+          - ServerSideEncryptionByDefault:
+              SSEAlgorithm: aws:kms:sse
+      VersioningConfiguration:
+        Status: "On"
+      PublicAccessBlockConfiguration:
+        BlockPublicAcls: "yes"
+      LifecycleConfiguration:
+        Rules:
+          - Status: Enabled
+            ExpirationInDays: 30
+      CorsConfiguration:
+        CorsRules:
+          - AllowedMethods: [GET, PATCH]
+            AllowedOrigins: ["*"]
+  Plain:
+    Type: AWS::S3::Bucket
+  Policy:
+    Type: AWS::S3::BucketPolicy
+    Properties:
+      Bucket: !Ref Bucket
+      PolicyDocument:
+        Version: "2012-10-17"
+        Statement:
+          - Effect: Allow
+            Action: s3:GetObject
+            Resource: !Sub ${Bucket.Arn}/*
+"#;
+        let path = "Resources/Bucket/Properties";
+        let expected = vec![
+            Diagnostic::error(
+                format!("{}/BucketName", path),
+                "bucket name `Logs_Bucket` can only contain lowercase letters, digits, `.` and `-`, not `L`",
+            ),
+            Diagnostic::error(
+                format!("{}/BucketEncryption/ServerSideEncryptionConfiguration/0/ServerSideEncryptionByDefault/KMSMasterKeyID", path),
+                "`KMSMasterKeyID` requires a KMS algorithm, not `AES256`",
+            ),
+            Diagnostic::error(
+                format!("{}/BucketEncryption/ServerSideEncryptionConfiguration/1/ServerSideEncryptionByDefault/SSEAlgorithm", path),
+                "`aws:kms:sse` is not a valid encryption algorithm, expected one of AES256, aws:kms, aws:kms:dsse",
+            ),
+            Diagnostic::error(
+                format!("{}/VersioningConfiguration/Status", path),
+                "`On` is not a valid versioning status, expected one of Enabled, Suspended",
+            ),
+            Diagnostic::error(
+                format!("{}/PublicAccessBlockConfiguration/BlockPublicAcls", path),
+                "expected a boolean, `true` or `false`",
+            ),
+            Diagnostic::error(
+                format!("{}/CorsConfiguration/CorsRules/0/AllowedMethods/1", path),
+                "`PATCH` is not a valid CORS method, expected one of DELETE, GET, HEAD, POST, PUT",
+            ),
+            Diagnostic::error(
+                "Resources/Policy/Properties/PolicyDocument/Statement/0",
+                "one of `Principal` and `NotPrincipal` is required",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_bucket_conditional_configuration() {
+        let yaml = r#"
+Resources:
+  Bucket:
+    Type: AWS::S3::Bucket
+    Properties:
+      BucketEncryption: !If
+        - IsProduction
+        - ServerSideEncryptionConfiguration:
+            - ServerSideEncryptionByDefault:
+                SSEAlgorithm: aws:kms
+        - !Ref AWS::NoValue
+      VersioningConfiguration: !If [IsProduction, {Status: Enabled}, !Ref AWS::NoValue]
+      LifecycleConfiguration:
+        Rules:
+          - !If [IsProduction, {Status: Enabled, ExpirationInDays: 30}, !Ref AWS::NoValue]
+      Tags:
+        - Key: Name
+          Value: logs
+        - !If [IsProduction, {Key: Stage, Value: production}, !Ref AWS::NoValue]
+"#;
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(Vec::<Diagnostic>::new(), template.validate());
+    }
+}
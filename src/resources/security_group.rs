@@ -1,49 +1,738 @@
-use crate::{tag::Tag, value::Value};
-use serde::Deserialize;
+use super::Resource;
+use crate::{
+    cidr::Cidr,
+    diagnostic::Diagnostic,
+    tag::Tag,
+    validate::{check_references, Validate},
+    value::{Node, Value},
+    Template,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
+/// The default quota for inbound and for outbound rules per security group.
+pub const MAX_RULES: usize = 60;
+
+/// The longest description a group or a rule can have.
+const MAX_DESCRIPTION_LENGTH: usize = 255;
+
+/// The punctuation allowed in descriptions, besides letters, digits and
+/// spaces.
+const DESCRIPTION_PUNCTUATION: &str = "._-:/()#,@[]+=&;{}!$*";
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "PascalCase")]
 pub struct SecurityGroup {
-    group_description: String,
+    group_description: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
     group_name: Option<Value>,
-    security_group_egress: Option<Vec<Egress>>,
-    security_group_ingress: Option<Vec<Ingress>>,
-    tags: Option<Vec<Tag>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    security_group_egress: Option<Node<Vec<Node<Egress>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    security_group_ingress: Option<Node<Vec<Node<Ingress>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<Node<Vec<Node<Tag>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     vpc_id: Option<Value>,
 }
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Egress {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cidr_ip: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cidr_ipv6: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_prefix_list_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_security_group_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_port: Option<Value>,
+    pub ip_protocol: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_port: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Ingress {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cidr_ip: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cidr_ipv6: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_port: Option<Value>,
+    pub ip_protocol: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_prefix_list_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_security_group_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_security_group_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_security_group_owner_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_port: Option<Value>,
+}
+
+/// The parts of an ingress or egress rule that decide which traffic it
+/// allows.
+struct RuleView<'a> {
+    ip_protocol: &'a Value,
+    from_port: Option<&'a Value>,
+    to_port: Option<&'a Value>,
+    /// Every property that names the other side of the rule, set or not.
+    peers: [(&'static str, Option<&'a Value>); 4],
+}
+
+impl Ingress {
+    fn view(&self) -> RuleView<'_> {
+        RuleView {
+            ip_protocol: &self.ip_protocol,
+            from_port: self.from_port.as_ref(),
+            to_port: self.to_port.as_ref(),
+            peers: [
+                ("CidrIp", self.cidr_ip.as_ref()),
+                ("CidrIpv6", self.cidr_ipv6.as_ref()),
+                (
+                    "SourceSecurityGroupId",
+                    self.source_security_group_id
+                        .as_ref()
+                        .or(self.source_security_group_name.as_ref()),
+                ),
+                ("SourcePrefixListId", self.source_prefix_list_id.as_ref()),
+            ],
+        }
+    }
+
+    fn validate(&self, path: &str, diagnostics: &mut Vec<Diagnostic>) {
+        if self.source_security_group_id.is_some() && self.source_security_group_name.is_some() {
+            diagnostics.push(Diagnostic::error(
+                path,
+                "`SourceSecurityGroupId` cannot be used with `SourceSecurityGroupName`",
+            ));
+        }
+        if let Some(description) = &self.description {
+            check_description(description, &format!("{}/Description", path), diagnostics);
+        }
+        self.view().validate(path, diagnostics);
+    }
+
+    /// The traffic the rule allows, if it is known statically.
+    pub fn traffic(&self) -> Option<Traffic<'_>> {
+        self.view().traffic()
+    }
+}
+
+impl Egress {
+    fn view(&self) -> RuleView<'_> {
+        RuleView {
+            ip_protocol: &self.ip_protocol,
+            from_port: self.from_port.as_ref(),
+            to_port: self.to_port.as_ref(),
+            peers: [
+                ("CidrIp", self.cidr_ip.as_ref()),
+                ("CidrIpv6", self.cidr_ipv6.as_ref()),
+                (
+                    "DestinationSecurityGroupId",
+                    self.destination_security_group_id.as_ref(),
+                ),
+                (
+                    "DestinationPrefixListId",
+                    self.destination_prefix_list_id.as_ref(),
+                ),
+            ],
+        }
+    }
+
+    fn validate(&self, path: &str, diagnostics: &mut Vec<Diagnostic>) {
+        if let Some(description) = &self.description {
+            check_description(description, &format!("{}/Description", path), diagnostics);
+        }
+        self.view().validate(path, diagnostics);
+    }
+
+    /// The traffic the rule allows, if it is known statically.
+    pub fn traffic(&self) -> Option<Traffic<'_>> {
+        self.view().traffic()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    /// `-1`, every protocol on every port.
+    All,
+    Tcp,
+    Udp,
+    Icmp,
+    Icmpv6,
+    /// Any other protocol number, which always covers every port.
+    Other(i64),
+}
+
+impl Protocol {
+    /// Reads a literal protocol by name or number. Returns `None` for values
+    /// computed by intrinsic functions, and the literal when it is invalid.
+    fn parse(value: &Value) -> Option<Result<Protocol, String>> {
+        let literal = match value {
+            Value::String(string) => string.to_lowercase(),
+            Value::Number(number) => number.to_string(),
+            _ => return None,
+        };
+        let protocol = match literal.as_str() {
+            "-1" => Protocol::All,
+            "tcp" | "6" => Protocol::Tcp,
+            "udp" | "17" => Protocol::Udp,
+            "icmp" | "1" => Protocol::Icmp,
+            "icmpv6" | "58" => Protocol::Icmpv6,
+            number => match number.parse() {
+                Ok(number @ 0..=255) => Protocol::Other(number),
+                _ => return Some(Err(literal)),
+            },
+        };
+        Some(Ok(protocol))
+    }
+}
+
+/// What a port pair means for a protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ports {
+    All,
+    Range(i64, i64),
+    /// ICMP type and code, where `-1` stands for any.
+    Icmp(i64, i64),
+}
+
+impl Ports {
+    fn overlaps(&self, other: &Ports) -> bool {
+        let matches = |a: i64, b: i64| a == b || a == -1 || b == -1;
+        match (self, other) {
+            (Ports::All, _) | (_, Ports::All) => true,
+            (Ports::Range(from, to), Ports::Range(other_from, other_to)) => {
+                from <= other_to && other_from <= to
+            }
+            (Ports::Icmp(kind, code), Ports::Icmp(other_kind, other_code)) => {
+                matches(*kind, *other_kind) && matches(*code, *other_code)
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Peer<'a> {
+    Cidr(Cidr),
+    /// A security group or prefix list, compared structurally.
+    Other(&'a Value),
+}
+
+/// A rule whose protocol, ports and peer are all known statically.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Traffic<'a> {
+    pub protocol: Protocol,
+    pub ports: Ports,
+    pub peer: Peer<'a>,
+}
+
+impl Peer<'_> {
+    /// Whether the peer is every address, `0.0.0.0/0` or `::/0`.
+    pub fn is_world(&self) -> bool {
+        matches!(self, Peer::Cidr(cidr) if cidr.prefix() == 0)
+    }
+}
+
+impl Traffic<'_> {
+    /// Whether the rule lets TCP traffic through on `port`.
+    pub fn allows_tcp_port(&self, port: i64) -> bool {
+        self.allows_port(Protocol::Tcp, port)
+    }
+
+    /// Whether the rule lets `protocol` traffic through on `port`.
+    pub fn allows_port(&self, protocol: Protocol, port: i64) -> bool {
+        let protocol = self.protocol == protocol || self.protocol == Protocol::All;
+        let port = match self.ports {
+            Ports::All => true,
+            Ports::Range(from, to) => (from..=to).contains(&port),
+            Ports::Icmp(..) => false,
+        };
+        protocol && port
+    }
+
+    /// Whether the rule lets TCP traffic through on every port.
+    pub fn allows_all_tcp_ports(&self) -> bool {
+        let protocol = matches!(self.protocol, Protocol::Tcp | Protocol::All);
+        let ports = match self.ports {
+            Ports::All => true,
+            Ports::Range(from, to) => from <= 0 && to >= 65535,
+            Ports::Icmp(..) => false,
+        };
+        protocol && ports
+    }
+
+    fn overlaps(&self, other: &Traffic) -> bool {
+        let protocols = self.protocol == other.protocol
+            || self.protocol == Protocol::All
+            || other.protocol == Protocol::All;
+        let peers = match (&self.peer, &other.peer) {
+            (Peer::Cidr(cidr), Peer::Cidr(other)) => cidr.overlaps(other),
+            (peer, other) => peer == other,
+        };
+        protocols && peers && self.ports.overlaps(&other.ports)
+    }
+}
+
+impl<'a> RuleView<'a> {
+    fn validate(&self, path: &str, diagnostics: &mut Vec<Diagnostic>) {
+        let peers: Vec<_> = self
+            .peers
+            .iter()
+            .filter(|(_, value)| value.is_some())
+            .collect();
+        if peers.len() != 1 {
+            let names: Vec<_> = self
+                .peers
+                .iter()
+                .map(|(name, _)| format!("`{}`", name))
+                .collect();
+            diagnostics.push(Diagnostic::error(
+                path,
+                format!("exactly one of {} is required", names.join(", ")),
+            ));
+        }
+        for (name, value) in peers {
+            let (parse, family): (fn(&str) -> Option<Cidr>, _) = match *name {
+                "CidrIp" => (Cidr::ipv4, "IPv4"),
+                "CidrIpv6" => (Cidr::ipv6, "IPv6"),
+                _ => continue,
+            };
+            if let Some(cidr) = value.and_then(Value::as_str) {
+                if parse(cidr).is_none() {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/{}", path, name),
+                        format!("`{}` is not a valid {} CIDR block", cidr, family),
+                    ));
+                }
+            }
+        }
+
+        let protocol = match Protocol::parse(self.ip_protocol) {
+            Some(Ok(protocol)) => protocol,
+            Some(Err(literal)) => {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/IpProtocol", path),
+                    format!(
+                        "`{}` is not a valid protocol, expected tcp, udp, icmp, icmpv6, -1 or a protocol number from 0 to 255",
+                        literal
+                    ),
+                ));
+                return;
+            }
+            None => return,
+        };
+        let from_port = self.from_port.and_then(Value::as_integer);
+        let to_port = self.to_port.and_then(Value::as_integer);
+        match protocol {
+            Protocol::Tcp | Protocol::Udp => {
+                if self.from_port.is_none() || self.to_port.is_none() {
+                    diagnostics.push(Diagnostic::error(
+                        path,
+                        "`FromPort` and `ToPort` are required for tcp and udp rules",
+                    ));
+                }
+                let mut in_range = true;
+                for (name, port) in [("FromPort", from_port), ("ToPort", to_port)] {
+                    if let Some(port) = port.filter(|port| !(0..=65535).contains(port)) {
+                        in_range = false;
+                        diagnostics.push(Diagnostic::error(
+                            format!("{}/{}", path, name),
+                            format!("port {} is not between 0 and 65535", port),
+                        ));
+                    }
+                }
+                if let (Some(from), Some(to)) = (from_port, to_port) {
+                    if in_range && from > to {
+                        diagnostics.push(Diagnostic::error(
+                            path,
+                            format!("`FromPort` {} is greater than `ToPort` {}", from, to),
+                        ));
+                    }
+                }
+            }
+            Protocol::Icmp | Protocol::Icmpv6 => {
+                for (name, kind, value) in
+                    [("FromPort", "type", from_port), ("ToPort", "code", to_port)]
+                {
+                    if let Some(value) = value.filter(|value| !(-1..=255).contains(value)) {
+                        diagnostics.push(Diagnostic::error(
+                            format!("{}/{}", path, name),
+                            format!("ICMP {} {} is not -1 or between 0 and 255", kind, value),
+                        ));
+                    }
+                }
+                if from_port == Some(-1) && to_port.is_some_and(|code| code != -1) {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/ToPort", path),
+                        "ICMP code must be -1 when the type is -1",
+                    ));
+                }
+            }
+            Protocol::All => {
+                let ports = [self.from_port, self.to_port];
+                if ports
+                    .iter()
+                    .flatten()
+                    .any(|port| port.as_integer() != Some(-1))
+                {
+                    diagnostics.push(Diagnostic::warning(
+                        path,
+                        "ports are ignored for protocol -1, which allows all traffic",
+                    ));
+                }
+            }
+            Protocol::Other(_) => {}
+        }
+    }
+
+    /// Reduces the rule to the traffic it allows, if that is known
+    /// statically.
+    fn traffic(&self) -> Option<Traffic<'a>> {
+        let protocol = Protocol::parse(self.ip_protocol)?.ok()?;
+        let port = |value: Option<&Value>| value.map_or(Some(-1), Value::as_integer);
+        let ports = match protocol {
+            Protocol::Tcp | Protocol::Udp => {
+                Ports::Range(self.from_port?.as_integer()?, self.to_port?.as_integer()?)
+            }
+            Protocol::Icmp | Protocol::Icmpv6 => {
+                Ports::Icmp(port(self.from_port)?, port(self.to_port)?)
+            }
+            Protocol::All | Protocol::Other(_) => Ports::All,
+        };
+
+        let mut peers = self
+            .peers
+            .iter()
+            .filter_map(|(name, value)| Some((*name, (*value)?)));
+        let peer = match (peers.next()?, peers.next()) {
+            ((name @ ("CidrIp" | "CidrIpv6"), value), None) => {
+                let parse = if name == "CidrIp" {
+                    Cidr::ipv4
+                } else {
+                    Cidr::ipv6
+                };
+                Peer::Cidr(parse(value.as_str()?)?)
+            }
+            ((_, value), None) => Peer::Other(value),
+            _ => return None,
+        };
+        Some(Traffic {
+            protocol,
+            ports,
+            peer,
+        })
+    }
+}
+
+/// Warns about rules that repeat or overlap an earlier rule of the same
+/// group.
+fn check_overlaps(rules: &[(String, RuleView)], diagnostics: &mut Vec<Diagnostic>) {
+    let traffic: Vec<_> = rules.iter().map(|(_, rule)| rule.traffic()).collect();
+    for (index, (path, _)) in rules.iter().enumerate() {
+        let Some(current) = &traffic[index] else {
+            continue;
+        };
+        let earlier = traffic[..index]
+            .iter()
+            .enumerate()
+            .find_map(|(earlier, other)| {
+                let other = other.as_ref()?;
+                if other == current {
+                    Some(("duplicates", earlier))
+                } else if other.overlaps(current) {
+                    Some(("overlaps", earlier))
+                } else {
+                    None
+                }
+            });
+        if let Some((relation, earlier)) = earlier {
+            diagnostics.push(Diagnostic::warning(
+                path,
+                format!("rule {} the rule at `{}`", relation, rules[earlier].0),
+            ));
+        }
+    }
+}
+
+/// Checks the length and characters of a literal description.
+fn check_description(description: &Value, path: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(description) = description.as_str() else {
+        return;
+    };
+    let length = description.chars().count();
+    if length > MAX_DESCRIPTION_LENGTH {
+        diagnostics.push(Diagnostic::error(
+            path,
+            format!(
+                "a description can be at most {} characters, not {}",
+                MAX_DESCRIPTION_LENGTH, length
+            ),
+        ));
+    }
+    let invalid = description.chars().find(|c| {
+        !(c.is_ascii_alphanumeric() || *c == ' ' || DESCRIPTION_PUNCTUATION.contains(*c))
+    });
+    if let Some(invalid) = invalid {
+        diagnostics.push(Diagnostic::error(
+            path,
+            format!("a description cannot contain `{}`", invalid),
+        ));
+    }
+}
+
+impl Validate for SecurityGroup {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        check_description(
+            &self.group_description,
+            &format!("{}/GroupDescription", path),
+            diagnostics,
+        );
+        for (index, rule) in self.security_group_ingress.iter().flat_map(Node::literals) {
+            rule.validate(
+                &format!("{}/SecurityGroupIngress/{}", path, index),
+                diagnostics,
+            );
+        }
+        for (index, rule) in self.security_group_egress.iter().flat_map(Node::literals) {
+            rule.validate(
+                &format!("{}/SecurityGroupEgress/{}", path, index),
+                diagnostics,
+            );
+        }
+    }
+}
+
+/// An `AWS::EC2::SecurityGroupIngress` resource: an ingress rule declared
+/// apart from its group, usually to break a circular reference between groups.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "PascalCase")]
-struct Egress {
-    cidr_ip: Option<Value>,
-    cidr_ipv6: Option<Value>,
-    description: Option<String>,
-    destination_prefix_list_id: Option<Value>,
-    destination_security_group_id: Option<Value>,
-    from_port: Option<Value>,
-    ip_protocol: Value,
-    to_port: Option<Value>,
-}
-
-#[derive(Deserialize, Debug, PartialEq, Eq)]
+pub struct SecurityGroupIngress {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group_name: Option<Value>,
+    #[serde(flatten)]
+    rule: Ingress,
+}
+
+/// An `AWS::EC2::SecurityGroupEgress` resource. Egress rules can only target
+/// a group by ID.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "PascalCase")]
-struct Ingress {
-    cidr_ip: Option<Value>,
-    cidr_ipv6: Option<Value>,
-    description: Option<String>,
-    from_port: Option<Value>,
-    ip_protocol: Value,
-    source_prefix_list_id: Option<Value>,
-    source_security_group_id: Option<Value>,
-    source_security_group_name: Option<Value>,
-    source_security_group_owner_id: Option<Value>,
-    to_port: Option<Value>,
+pub struct SecurityGroupEgress {
+    group_id: Value,
+    #[serde(flatten)]
+    rule: Egress,
+}
+
+impl SecurityGroupIngress {
+    /// The logical name of the security group in this template the rule is
+    /// added to, if it can be told statically.
+    fn target<'a>(&'a self, template: &Template) -> Option<&'a str> {
+        let group = self.group_id.as_ref().or(self.group_name.as_ref())?;
+        security_group(group, template)
+    }
+}
+
+impl SecurityGroupEgress {
+    fn target<'a>(&'a self, template: &Template) -> Option<&'a str> {
+        security_group(&self.group_id, template)
+    }
+}
+
+impl Validate for SecurityGroupIngress {
+    fn validate(&self, path: &str, template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        match (&self.group_id, &self.group_name) {
+            (Some(group), None) => check_group(group, "GroupId", path, template, diagnostics),
+            (None, Some(group)) => check_group(group, "GroupName", path, template, diagnostics),
+            _ => diagnostics.push(Diagnostic::error(
+                path,
+                "exactly one of `GroupId` and `GroupName` is required",
+            )),
+        }
+        self.rule.validate(path, diagnostics);
+    }
+}
+
+impl Validate for SecurityGroupEgress {
+    fn validate(&self, path: &str, template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        check_group(&self.group_id, "GroupId", path, template, diagnostics);
+        self.rule.validate(path, diagnostics);
+    }
+}
+
+/// Reports a standalone rule whose group is missing or is not a security
+/// group.
+fn check_group(
+    group: &Value,
+    property: &str,
+    path: &str,
+    template: &Template,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let path = format!("{}/{}", path, property);
+    check_references(group, &path, template, diagnostics);
+
+    let name = match group {
+        Value::Ref { r#ref } => r#ref,
+        Value::GetAtt { get_att } if get_att.len() == 2 => &get_att[0],
+        _ => return,
+    };
+    match template.resource(name) {
+        Some(Resource::SecurityGroup(_)) | None => {}
+        Some(_) => diagnostics.push(Diagnostic::error(
+            path,
+            format!("`{}` is not a security group", name),
+        )),
+    }
+}
+
+/// Resolves `Ref: Group` or `Fn::GetAtt: [Group, GroupId]` to a security
+/// group defined in the template.
+fn security_group<'a>(group: &'a Value, template: &Template) -> Option<&'a str> {
+    let name = match group {
+        Value::Ref { r#ref } => r#ref,
+        Value::GetAtt { get_att } if get_att.len() == 2 && get_att[1] == "GroupId" => &get_att[0],
+        _ => return None,
+    };
+    match template.resource(name) {
+        Some(Resource::SecurityGroup(_)) => Some(name),
+        _ => None,
+    }
+}
+
+/// Every rule that applies to one security group, whether written inline or
+/// as a standalone resource, with the path each rule is declared at. Rules
+/// computed by an intrinsic function, or a computed rule list, are kept as
+/// `None` so callers can tell the group's rules are not fully known.
+#[derive(Debug, Default)]
+pub struct GroupRules<'a> {
+    pub ingress: Vec<(String, Option<&'a Ingress>)>,
+    pub egress: Vec<(String, Option<&'a Egress>)>,
+}
+
+/// Pushes the inline rules of a group, one `None` for each computed rule and
+/// one for a computed list.
+fn push_inline<'a, T>(
+    rules: &mut Vec<(String, Option<&'a T>)>,
+    path: String,
+    inline: Option<&'a Node<Vec<Node<T>>>>,
+) {
+    match inline {
+        Some(Node::Literal(inline)) => {
+            for (index, rule) in inline.iter().enumerate() {
+                rules.push((format!("{}/{}", path, index), rule.literal()));
+            }
+        }
+        Some(Node::Computed(_)) => rules.push((path, None)),
+        None => {}
+    }
+}
+
+/// Merges inline and standalone rules by the security group they apply to,
+/// in template order. Standalone rules for groups outside the template are
+/// left out.
+pub fn group_rules(template: &Template) -> IndexMap<&str, GroupRules<'_>> {
+    let mut groups: IndexMap<&str, GroupRules> = IndexMap::new();
+    for (name, resource) in &template.resources {
+        if let Resource::SecurityGroup(group) = resource {
+            let group = &group.properties;
+            let rules = groups.entry(name).or_default();
+            let path = format!("Resources/{}/Properties", name);
+            push_inline(
+                &mut rules.ingress,
+                format!("{}/SecurityGroupIngress", path),
+                group.security_group_ingress.as_ref(),
+            );
+            push_inline(
+                &mut rules.egress,
+                format!("{}/SecurityGroupEgress", path),
+                group.security_group_egress.as_ref(),
+            );
+        }
+    }
+
+    for (name, resource) in &template.resources {
+        let path = format!("Resources/{}/Properties", name);
+        match resource {
+            Resource::SecurityGroupIngress(ingress) => {
+                let ingress = &ingress.properties;
+                if let Some(group) = ingress.target(template) {
+                    let rules = groups.entry(group).or_default();
+                    rules.ingress.push((path, Some(&ingress.rule)));
+                }
+            }
+            Resource::SecurityGroupEgress(egress) => {
+                let egress = &egress.properties;
+                if let Some(group) = egress.target(template) {
+                    let rules = groups.entry(group).or_default();
+                    rules.egress.push((path, Some(&egress.rule)));
+                }
+            }
+            _ => {}
+        }
+    }
+    groups
+}
+
+/// Checks the merged rules of every security group for repeated and
+/// overlapping rules, and against the rule quota.
+pub fn validate_security_groups(template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+    for (name, rules) in group_rules(template) {
+        let ingress: Vec<_> = rules
+            .ingress
+            .iter()
+            .filter_map(|(path, rule)| Some((path.clone(), (*rule)?.view())))
+            .collect();
+        check_overlaps(&ingress, diagnostics);
+        let egress: Vec<_> = rules
+            .egress
+            .iter()
+            .filter_map(|(path, rule)| Some((path.clone(), (*rule)?.view())))
+            .collect();
+        check_overlaps(&egress, diagnostics);
+
+        let counts = [
+            ("ingress", rules.ingress.len()),
+            ("egress", rules.egress.len()),
+        ];
+        for (direction, count) in counts {
+            if count > MAX_RULES {
+                diagnostics.push(Diagnostic::warning(
+                    format!("Resources/{}", name),
+                    format!(
+                        "security group has {} {} rules, more than the default quota of {}",
+                        count, direction, MAX_RULES
+                    ),
+                ));
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Egress, Ingress, SecurityGroup};
-    use crate::{tag::Tag, value::Value};
+    use super::{group_rules, Egress, Ingress, SecurityGroup};
+    use crate::{
+        diagnostic::Diagnostic,
+        document::Format,
+        tag::Tag,
+        value::{Node, Value},
+        Template,
+    };
 
     #[test]
     fn test_deserialize_security_group() {
@@ -66,9 +755,9 @@ mod tests {
           Value: mySecurityGroup
         "#;
         let expected = SecurityGroup {
-            group_description: "Allow http to client host".to_string(),
+            group_description: Value::String("Allow http to client host".to_string()),
             group_name: None,
-            security_group_egress: Some(vec![Egress {
+            security_group_egress: Some(Node::Literal(vec![Node::Literal(Egress {
                 cidr_ip: Some(Value::String("0.0.0.0/0".to_string())),
                 cidr_ipv6: None,
                 description: None,
@@ -77,8 +766,8 @@ mod tests {
                 from_port: Some(Value::Number(80)),
                 ip_protocol: Value::String("tcp".to_string()),
                 to_port: Some(Value::Number(80)),
-            }]),
-            security_group_ingress: Some(vec![Ingress {
+            })])),
+            security_group_ingress: Some(Node::Literal(vec![Node::Literal(Ingress {
                 ip_protocol: Value::String("tcp".to_string()),
                 cidr_ip: Some(Value::String("0.0.0.0/0".to_string())),
                 cidr_ipv6: None,
@@ -89,11 +778,11 @@ mod tests {
                 source_security_group_name: None,
                 source_security_group_owner_id: None,
                 to_port: Some(Value::Number(80)),
-            }]),
-            tags: Some(vec![Tag {
+            })])),
+            tags: Some(Node::Literal(vec![Node::Literal(Tag {
                 key: "Name".to_string(),
-                value: "mySecurityGroup".to_string(),
-            }]),
+                value: Value::String("mySecurityGroup".to_string()),
+            })])),
             vpc_id: Some(Value::Ref {
                 r#ref: "myVPC".to_string(),
             }),
@@ -124,7 +813,7 @@ mod tests {
         let expected = Egress {
             cidr_ip: Some(Value::String("0.0.0.0/0".to_string())),
             cidr_ipv6: Some(Value::String("::/0".to_string())),
-            description: Some("Allow any outbound traffic".to_string()),
+            description: Some(Value::String("Allow any outbound traffic".to_string())),
             destination_prefix_list_id: None,
             destination_security_group_id: Some(Value::GetAtt {
                 get_att: vec!["TargetSG".to_string(), "GroupId".to_string()],
@@ -166,7 +855,7 @@ mod tests {
                     r#ref: "SSHLocation".to_string(),
                 }),
                 cidr_ipv6: Some(Value::String("::/0".to_string())),
-                description: Some("Allow HTTP".to_string()),
+                description: Some(Value::String("Allow HTTP".to_string())),
                 from_port: Some(Value::Number(22)),
                 to_port: Some(Value::Number(22)),
                 source_prefix_list_id: None,
@@ -204,4 +893,333 @@ mod tests {
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_group_rules_merges_standalone_rules() {
+        let yaml = r#"
+Resources:
+  WebGroup:
+    Type: AWS::EC2::SecurityGroup
+    Properties:
+      GroupDescription: Web servers
+      SecurityGroupIngress:
+        - IpProtocol: tcp
+          FromPort: 443
+          ToPort: 443
+          CidrIp: 0.0.0.0/0
+  DatabaseGroup:
+    Type: AWS::EC2::SecurityGroup
+    Properties:
+      GroupDescription: Databases
+  WebToDatabase:
+    Type: AWS::EC2::SecurityGroupIngress
+    Properties:
+      GroupId: !GetAtt DatabaseGroup.GroupId
+      IpProtocol: tcp
+      FromPort: 5432
+      ToPort: 5432
+      SourceSecurityGroupId: !Ref WebGroup
+  WebEgress:
+    Type: AWS::EC2::SecurityGroupEgress
+    Properties:
+      GroupId: !Ref WebGroup
+      IpProtocol: tcp
+      FromPort: 5432
+      ToPort: 5432
+      DestinationSecurityGroupId: !GetAtt DatabaseGroup.GroupId
+  ExistingGroupIngress:
+    Type: AWS::EC2::SecurityGroupIngress
+    Properties:
+      GroupId: sg-12345678
+      IpProtocol: tcp
+      FromPort: 80
+      ToPort: 80
+      CidrIp: 10.0.0.0/8
+"#;
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        let groups = group_rules(&template);
+
+        fn paths<T>(rules: &[(String, T)]) -> Vec<&str> {
+            rules.iter().map(|(path, _)| path.as_str()).collect()
+        }
+
+        assert_eq!(
+            vec!["WebGroup", "DatabaseGroup"],
+            groups.keys().copied().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["Resources/WebGroup/Properties/SecurityGroupIngress/0"],
+            paths(&groups["WebGroup"].ingress)
+        );
+        assert_eq!(
+            vec!["Resources/WebEgress/Properties"],
+            paths(&groups["WebGroup"].egress)
+        );
+        assert_eq!(
+            vec!["Resources/WebToDatabase/Properties"],
+            paths(&groups["DatabaseGroup"].ingress)
+        );
+        assert_eq!(
+            Value::Number(5432),
+            *groups["DatabaseGroup"].ingress[0]
+                .1
+                .unwrap()
+                .from_port
+                .as_ref()
+                .unwrap()
+        );
+        assert_eq!(Vec::<Diagnostic>::new(), template.validate());
+    }
+
+    #[test]
+    fn test_validate_standalone_rules() {
+        let yaml = r#"
+Resources:
+  Topic:
+    Type: AWS::SNS::Topic
+  NoGroup:
+    Type: AWS::EC2::SecurityGroupIngress
+    Properties:
+      IpProtocol: tcp
+      FromPort: 22
+      ToPort: 22
+      CidrIp: 10.0.0.0/8
+  WrongGroup:
+    Type: AWS::EC2::SecurityGroupEgress
+    Properties:
+      GroupId: !Ref Topic
+      IpProtocol: "-1"
+      CidrIp: 0.0.0.0/0
+  MissingGroup:
+    Type: AWS::EC2::SecurityGroupIngress
+    Properties:
+      GroupName: !Ref Missing
+      IpProtocol: tcp
+      FromPort: 22
+      ToPort: 22
+      CidrIp: 10.0.0.0/8
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/NoGroup/Properties",
+                "exactly one of `GroupId` and `GroupName` is required",
+            ),
+            Diagnostic::error(
+                "Resources/WrongGroup/Properties/GroupId",
+                "`Topic` is not a security group",
+            ),
+            Diagnostic::error(
+                "Resources/MissingGroup/Properties/GroupName",
+                "references resource or parameter `Missing` that does not exist",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_rules() {
+        let yaml = r#"
+Resources:
+  Group:
+    Type: AWS::EC2::SecurityGroup
+    Properties:
+      GroupDescription: Rules
+      SecurityGroupIngress:
+        - IpProtocol: tcp
+          FromPort: 443
+          ToPort: 80
+          CidrIp: 10.0.0.0/8
+        - IpProtocol: gre
+          CidrIp: 10.0.0.0/33
+        - IpProtocol: icmp
+          FromPort: -1
+          ToPort: 3
+          CidrIp: 10.0.0.0/8
+          CidrIpv6: ::/0
+        - IpProtocol: "-1"
+          FromPort: 0
+          ToPort: 65535
+          CidrIp: 192.168.0.0/16
+        - IpProtocol: tcp
+          FromPort: 22
+          ToPort: 70000
+        - IpProtocol: "6"
+          FromPort: 8000
+          ToPort: 8080
+          CidrIp: 172.16.0.0/12
+        - IpProtocol: tcp
+          FromPort: 8080
+          ToPort: 9000
+          CidrIp: 172.16.1.0/24
+  Duplicate:
+    Type: AWS::EC2::SecurityGroupIngress
+    Properties:
+      GroupId: !Ref Group
+      IpProtocol: tcp
+      FromPort: 8000
+      ToPort: 8080
+      CidrIp: 172.16.0.0/12
+"#;
+        let path = "Resources/Group/Properties/SecurityGroupIngress";
+        let expected = vec![
+            Diagnostic::error(
+                format!("{}/0", path),
+                "`FromPort` 443 is greater than `ToPort` 80",
+            ),
+            Diagnostic::error(
+                format!("{}/1/CidrIp", path),
+                "`10.0.0.0/33` is not a valid IPv4 CIDR block",
+            ),
+            Diagnostic::error(
+                format!("{}/1/IpProtocol", path),
+                "`gre` is not a valid protocol, expected tcp, udp, icmp, icmpv6, -1 or a protocol number from 0 to 255",
+            ),
+            Diagnostic::error(
+                format!("{}/2", path),
+                "exactly one of `CidrIp`, `CidrIpv6`, `SourceSecurityGroupId`, `SourcePrefixListId` is required",
+            ),
+            Diagnostic::error(
+                format!("{}/2/ToPort", path),
+                "ICMP code must be -1 when the type is -1",
+            ),
+            Diagnostic::warning(
+                format!("{}/3", path),
+                "ports are ignored for protocol -1, which allows all traffic",
+            ),
+            Diagnostic::error(
+                format!("{}/4", path),
+                "exactly one of `CidrIp`, `CidrIpv6`, `SourceSecurityGroupId`, `SourcePrefixListId` is required",
+            ),
+            Diagnostic::error(
+                format!("{}/4/ToPort", path),
+                "port 70000 is not between 0 and 65535",
+            ),
+            Diagnostic::warning(
+                format!("{}/6", path),
+                format!("rule overlaps the rule at `{}/5`", path),
+            ),
+            Diagnostic::warning(
+                "Resources/Duplicate/Properties",
+                format!("rule duplicates the rule at `{}/5`", path),
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_group_rules_keep_computed_rules() {
+        let yaml = r#"
+Resources:
+  WebGroup:
+    Type: AWS::EC2::SecurityGroup
+    Properties:
+      GroupDescription: Web servers
+      SecurityGroupIngress:
+        - IpProtocol: tcp
+          FromPort: 443
+          ToPort: 443
+          CidrIp: 0.0.0.0/0
+        - !If
+          - IsProduction
+          - IpProtocol: tcp
+            FromPort: 443
+            ToPort: 443
+            CidrIp: 0.0.0.0/0
+          - !Ref AWS::NoValue
+      SecurityGroupEgress: !If [IsProduction, [], !Ref AWS::NoValue]
+"#;
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        let groups = group_rules(&template);
+        let rules = &groups["WebGroup"];
+
+        let ingress: Vec<_> = rules
+            .ingress
+            .iter()
+            .map(|(path, rule)| (path.as_str(), rule.is_some()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("Resources/WebGroup/Properties/SecurityGroupIngress/0", true),
+                (
+                    "Resources/WebGroup/Properties/SecurityGroupIngress/1",
+                    false
+                ),
+            ],
+            ingress
+        );
+        assert_eq!(
+            "Resources/WebGroup/Properties/SecurityGroupEgress",
+            rules.egress[0].0
+        );
+        assert!(rules.egress[0].1.is_none());
+        assert_eq!(Vec::<Diagnostic>::new(), template.validate());
+    }
+
+    #[test]
+    fn test_validate_descriptions() {
+        let yaml = r#"
+Resources:
+  Group:
+    Type: AWS::EC2::SecurityGroup
+    Properties:
+      GroupDescription: !Sub "${AWS::StackName} web"
+      SecurityGroupIngress:
+        - IpProtocol: tcp
+          FromPort: 443
+          ToPort: 443
+          CidrIp: 0.0.0.0/0
+          Description: !Sub "HTTPS for ${AWS::StackName}"
+        - IpProtocol: tcp
+          FromPort: 22
+          ToPort: 22
+          CidrIp: 10.0.0.0/8
+          Description: SSH from the office ✓
+      SecurityGroupEgress:
+        - IpProtocol: tcp
+          FromPort: 443
+          ToPort: 443
+          CidrIp: 0.0.0.0/0
+          Description: !Join [" ", [HTTPS, out]]
+  Ingress:
+    Type: AWS::EC2::SecurityGroupIngress
+    Properties:
+      GroupId: !Ref Group
+      IpProtocol: tcp
+      FromPort: 80
+      ToPort: 80
+      CidrIp: 0.0.0.0/0
+      Description: !Sub "HTTP for ${AWS::StackName}"
+  Egress:
+    Type: AWS::EC2::SecurityGroupEgress
+    Properties:
+      GroupId: !GetAtt Group.GroupId
+      IpProtocol: tcp
+      FromPort: 5432
+      ToPort: 5432
+      CidrIp: 10.0.0.0/8
+      Description: !Sub "Databases for ${AWS::StackName}"
+  Long:
+    Type: AWS::EC2::SecurityGroup
+    Properties:
+      GroupDescription: LONG
+"#
+        .replace("LONG", &"a".repeat(256));
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Group/Properties/SecurityGroupIngress/1/Description",
+                "a description cannot contain `✓`",
+            ),
+            Diagnostic::error(
+                "Resources/Long/Properties/GroupDescription",
+                "a description can be at most 255 characters, not 256",
+            ),
+        ];
+
+        let template = Template::parse(&yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
 }
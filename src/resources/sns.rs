@@ -0,0 +1,408 @@
+use super::{
+    policy_document::{check_arn, PolicyDocument, PolicyKind},
+    sqs::{check_fifo_name, check_queue_arn, is_standard},
+    Resource,
+};
+use crate::{
+    diagnostic::Diagnostic,
+    tag::Tag,
+    validate::{check_boolean, check_one_of, Validate},
+    value::{Node, Value},
+    Template,
+};
+use serde::{Deserialize, Serialize};
+
+const PROTOCOLS: &[&str] = &[
+    "application",
+    "email",
+    "email-json",
+    "firehose",
+    "http",
+    "https",
+    "lambda",
+    "sms",
+    "sqs",
+];
+
+/// Protocols that can deliver raw messages instead of the SNS JSON envelope.
+const RAW_MESSAGE_PROTOCOLS: &[&str] = &["firehose", "http", "https", "sqs"];
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Topic {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_based_deduplication: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fifo_topic: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kms_master_key_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription: Option<Node<Vec<Node<TopicSubscription>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic_name: Option<Value>,
+}
+
+/// A subscription written inline in a topic's `Subscription` list.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct TopicSubscription {
+    pub endpoint: Value,
+    pub protocol: Value,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Subscription {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delivery_policy: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_policy: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_policy_scope: Option<Value>,
+    pub protocol: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_message_delivery: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redrive_policy: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_role_arn: Option<Value>,
+    pub topic_arn: Value,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct TopicPolicy {
+    pub policy_document: Node<PolicyDocument>,
+    pub topics: Node<Vec<Value>>,
+}
+
+/// Reports an endpoint that does not fit the subscription protocol: a URL
+/// with the wrong scheme, an address without `@`, or something other than
+/// the ARN of the right kind of resource.
+fn check_endpoint(
+    protocol: &Value,
+    endpoint: &Value,
+    path: &str,
+    template: &Template,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(protocol) = protocol.as_str() else {
+        return;
+    };
+    match (protocol, endpoint) {
+        ("sqs", _) => check_queue_arn(endpoint, path, template, diagnostics),
+        ("lambda", Value::Ref { r#ref }) => {
+            if let Some(Resource::Function(_)) = template.resource(r#ref) {
+                diagnostics.push(Diagnostic::error(
+                    path,
+                    format!(
+                        "`Ref` of function `{}` returns its name, not its ARN; use `!GetAtt {}.Arn`",
+                        r#ref, r#ref
+                    ),
+                ));
+            }
+        }
+        ("application" | "firehose" | "lambda", Value::String(_)) => {
+            check_arn(endpoint, path, diagnostics)
+        }
+        ("http" | "https", Value::String(url)) => {
+            let scheme = format!("{}://", protocol);
+            if !url.starts_with(&scheme) {
+                diagnostics.push(Diagnostic::error(
+                    path,
+                    format!(
+                        "an `{}` endpoint must start with `{}`, not `{}`",
+                        protocol, scheme, url
+                    ),
+                ));
+            }
+        }
+        ("email" | "email-json", Value::String(address)) if !address.contains('@') => diagnostics
+            .push(Diagnostic::error(
+                path,
+                format!("`{}` is not an email address", address),
+            )),
+        _ => {}
+    }
+}
+
+impl Validate for Topic {
+    fn validate(&self, path: &str, template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        check_fifo_name(
+            ("TopicName", &self.topic_name),
+            ("FifoTopic", &self.fifo_topic),
+            "topic",
+            path,
+            diagnostics,
+        );
+        if is_standard(&self.fifo_topic) && self.content_based_deduplication.is_some() {
+            diagnostics.push(Diagnostic::error(
+                format!("{}/ContentBasedDeduplication", path),
+                "`ContentBasedDeduplication` requires `FifoTopic: true`",
+            ));
+        }
+
+        for (index, subscription) in self.subscription.iter().flat_map(Node::literals) {
+            let path = format!("{}/Subscription/{}", path, index);
+            let protocol = &subscription.protocol;
+            check_one_of(
+                protocol,
+                PROTOCOLS,
+                "protocol",
+                &format!("{}/Protocol", path),
+                diagnostics,
+            );
+            check_endpoint(
+                protocol,
+                &subscription.endpoint,
+                &format!("{}/Endpoint", path),
+                template,
+                diagnostics,
+            );
+        }
+    }
+}
+
+impl Validate for Subscription {
+    fn validate(&self, path: &str, template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        check_one_of(
+            &self.protocol,
+            PROTOCOLS,
+            "protocol",
+            &format!("{}/Protocol", path),
+            diagnostics,
+        );
+        if let Some(endpoint) = &self.endpoint {
+            let endpoint_path = format!("{}/Endpoint", path);
+            check_endpoint(
+                &self.protocol,
+                endpoint,
+                &endpoint_path,
+                template,
+                diagnostics,
+            );
+        }
+
+        let Some(protocol) = self.protocol.as_str() else {
+            return;
+        };
+        if let Some(raw) = &self.raw_message_delivery {
+            check_boolean(raw, &format!("{}/RawMessageDelivery", path), diagnostics);
+            if !RAW_MESSAGE_PROTOCOLS.contains(&protocol) {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/RawMessageDelivery", path),
+                    format!(
+                        "`RawMessageDelivery` cannot be used with the `{}` protocol",
+                        protocol
+                    ),
+                ));
+            }
+        }
+        if protocol == "firehose" && self.subscription_role_arn.is_none() {
+            diagnostics.push(Diagnostic::error(
+                path,
+                "`SubscriptionRoleArn` is required for the `firehose` protocol",
+            ));
+        }
+    }
+}
+
+impl Validate for TopicPolicy {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        self.policy_document.validate(
+            &format!("{}/PolicyDocument", path),
+            PolicyKind::Resource,
+            diagnostics,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{diagnostic::Diagnostic, document::Format, Template};
+
+    #[test]
+    fn test_validate_topic_fifo() {
+        let yaml = r#"
+Parameters:
+  Fifo:
+    Type: String
+Resources:
+  Standard:
+    Type: AWS::SNS::Topic
+    Properties:
+      TopicName: alerts.fifo
+      ContentBasedDeduplication: true
+  Fifo:
+    Type: AWS::SNS::Topic
+    Properties:
+      TopicName: orders
+      FifoTopic: true
+  Spelled:
+    Type: AWS::SNS::Topic
+    Properties:
+      FifoTopic: "yes"
+  FromParameter:
+    Type: AWS::SNS::Topic
+    Properties:
+      TopicName: events.fifo
+      FifoTopic: !Ref Fifo
+      ContentBasedDeduplication: true
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Standard/Properties/TopicName",
+                "`alerts.fifo` ends in `.fifo`, which requires `FifoTopic: true`",
+            ),
+            Diagnostic::error(
+                "Resources/Standard/Properties/ContentBasedDeduplication",
+                "`ContentBasedDeduplication` requires `FifoTopic: true`",
+            ),
+            Diagnostic::error(
+                "Resources/Fifo/Properties/TopicName",
+                "the name of a FIFO topic must end in `.fifo`, not `orders`",
+            ),
+            Diagnostic::error(
+                "Resources/Spelled/Properties/FifoTopic",
+                "expected a boolean, `true` or `false`",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_topic_subscription_endpoints() {
+        let yaml = r#"
+Parameters:
+  Protocol:
+    Type: String
+Resources:
+  Queue:
+    Type: AWS::SQS::Queue
+  Handler:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: python3.12
+      Handler: index.handler
+      Role: arn:aws:iam::123456789012:role/lambda
+      Code: {ZipFile: "def handler(event, context): pass"}
+  Alerts:
+    Type: AWS::SNS::Topic
+    Properties:
+      Subscription:
+        - Protocol: email
+          Endpoint: ops.example.com
+        - Protocol: sqs
+          Endpoint: !Ref Queue
+        - Protocol: https
+          Endpoint: http://example.com/hook
+        - Protocol: pager
+          Endpoint: "12345"
+        - Protocol: lambda
+          Endpoint: !Ref Handler
+        - Protocol: application
+          Endpoint: endpoint/APNS/app
+        - Protocol: sqs
+          Endpoint: !GetAtt Queue.Arn
+        - Protocol: !Ref Protocol
+          Endpoint: ops.example.com
+        - !If [HasPager, {Protocol: sms, Endpoint: "+15555550100"}, !Ref AWS::NoValue]
+"#;
+        let path = "Resources/Alerts/Properties/Subscription";
+        let expected = vec![
+            Diagnostic::error(
+                format!("{}/0/Endpoint", path),
+                "`ops.example.com` is not an email address",
+            ),
+            Diagnostic::error(
+                format!("{}/1/Endpoint", path),
+                "`Ref` of queue `Queue` returns its URL, not its ARN; use `!GetAtt Queue.Arn`",
+            ),
+            Diagnostic::error(
+                format!("{}/2/Endpoint", path),
+                "an `https` endpoint must start with `https://`, not `http://example.com/hook`",
+            ),
+            Diagnostic::error(
+                format!("{}/3/Protocol", path),
+                "`pager` is not a valid protocol, expected one of application, email, email-json, firehose, http, https, lambda, sms, sqs",
+            ),
+            Diagnostic::error(
+                format!("{}/4/Endpoint", path),
+                "`Ref` of function `Handler` returns its name, not its ARN; use `!GetAtt Handler.Arn`",
+            ),
+            Diagnostic::error(
+                format!("{}/5/Endpoint", path),
+                "`endpoint/APNS/app` is not an ARN",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_subscription() {
+        let yaml = r#"
+Parameters:
+  Protocol:
+    Type: String
+Resources:
+  Alerts:
+    Type: AWS::SNS::Topic
+  Email:
+    Type: AWS::SNS::Subscription
+    Properties:
+      TopicArn: !Ref Alerts
+      Protocol: email
+      Endpoint: ops@example.com
+      RawMessageDelivery: true
+  Queue:
+    Type: AWS::SNS::Subscription
+    Properties:
+      TopicArn: !Ref Alerts
+      Protocol: sqs
+      Endpoint: arn:aws:sqs:us-east-1:123456789012:alerts
+      RawMessageDelivery: "yes"
+  Firehose:
+    Type: AWS::SNS::Subscription
+    Properties:
+      TopicArn: !Ref Alerts
+      Protocol: firehose
+      Endpoint: arn:aws:firehose:us-east-1:123456789012:deliverystream/alerts
+  FromParameter:
+    Type: AWS::SNS::Subscription
+    Properties:
+      TopicArn: !Ref Alerts
+      Protocol: !Ref Protocol
+      Endpoint: ops.example.com
+      RawMessageDelivery: true
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Email/Properties/RawMessageDelivery",
+                "`RawMessageDelivery` cannot be used with the `email` protocol",
+            ),
+            Diagnostic::error(
+                "Resources/Queue/Properties/RawMessageDelivery",
+                "expected a boolean, `true` or `false`",
+            ),
+            Diagnostic::error(
+                "Resources/Firehose/Properties",
+                "`SubscriptionRoleArn` is required for the `firehose` protocol",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+}
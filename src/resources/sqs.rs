@@ -0,0 +1,679 @@
+use super::{
+    policy_document::{check_arn, PolicyDocument, PolicyKind},
+    Resource, ResourceContainer,
+};
+use crate::{
+    diagnostic::Diagnostic,
+    tag::Tag,
+    validate::{check_boolean, check_one_of, check_range, Validate},
+    value::{Node, Value},
+    Template,
+};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+const DEDUPLICATION_SCOPES: &[&str] = &["messageGroup", "queue"];
+
+const FIFO_THROUGHPUT_LIMITS: &[&str] = &["perMessageGroupId", "perQueue"];
+
+/// The visibility timeout SQS uses when none is set, in seconds.
+const DEFAULT_VISIBILITY_TIMEOUT: i64 = 30;
+
+/// The timeout Lambda uses when none is set, in seconds.
+const DEFAULT_FUNCTION_TIMEOUT: i64 = 3;
+
+const DELAYS: RangeInclusive<i64> = 0..=900;
+
+const MESSAGE_SIZES: RangeInclusive<i64> = 1024..=262144;
+
+const RETENTION_PERIODS: RangeInclusive<i64> = 60..=1209600;
+
+const WAIT_TIMES: RangeInclusive<i64> = 0..=20;
+
+const VISIBILITY_TIMEOUTS: RangeInclusive<i64> = 0..=43200;
+
+const MAX_RECEIVE_COUNTS: RangeInclusive<i64> = 1..=1000;
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Queue {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_based_deduplication: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deduplication_scope: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay_seconds: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fifo_queue: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fifo_throughput_limit: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kms_data_key_reuse_period_seconds: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kms_master_key_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maximum_message_size: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_retention_period: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_name: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receive_message_wait_time_seconds: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redrive_allow_policy: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redrive_policy: Option<Node<RedrivePolicy>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sqs_managed_sse_enabled: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility_timeout: Option<Value>,
+}
+
+/// Where a queue sends messages it failed to process. Unlike the rest of
+/// CloudFormation, the keys are camel case.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RedrivePolicy {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dead_letter_target_arn: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_receive_count: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct QueuePolicy {
+    pub policy_document: Node<PolicyDocument>,
+    pub queues: Node<Vec<Value>>,
+}
+
+/// Reports a FIFO queue or topic whose name does not end in `.fifo`, and a
+/// `.fifo` name on a standard one. `kind` is `queue` or `topic`.
+pub fn check_fifo_name(
+    (name_property, name): (&str, &Option<Value>),
+    (fifo_property, fifo): (&str, &Option<Value>),
+    kind: &str,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if let Some(fifo) = fifo {
+        check_boolean(fifo, &format!("{}/{}", path, fifo_property), diagnostics);
+    }
+    let Some(name) = name.as_ref().and_then(Value::as_str) else {
+        return;
+    };
+    let is_fifo = fifo.as_ref().and_then(Value::as_bool) == Some(true);
+    let path = format!("{}/{}", path, name_property);
+    match (is_fifo, name.ends_with(".fifo")) {
+        (true, false) => diagnostics.push(Diagnostic::error(
+            path,
+            format!(
+                "the name of a FIFO {} must end in `.fifo`, not `{}`",
+                kind, name
+            ),
+        )),
+        (false, true) if is_standard(fifo) => diagnostics.push(Diagnostic::error(
+            path,
+            format!(
+                "`{}` ends in `.fifo`, which requires `{}: true`",
+                name, fifo_property
+            ),
+        )),
+        _ => {}
+    }
+}
+
+/// Whether a `FifoQueue` or `FifoTopic` setting is left out or literally
+/// false.
+pub fn is_standard(fifo: &Option<Value>) -> bool {
+    match fifo {
+        Some(fifo) => fifo.as_bool() == Some(false),
+        None => true,
+    }
+}
+
+/// Reports a `Ref` of a queue where its ARN is needed.
+pub fn check_queue_arn(
+    value: &Value,
+    path: &str,
+    template: &Template,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    match value {
+        Value::Ref { r#ref } => {
+            if let Some(Resource::Queue(_)) = template.resource(r#ref) {
+                diagnostics.push(Diagnostic::error(
+                    path,
+                    format!(
+                        "`Ref` of queue `{}` returns its URL, not its ARN; use `!GetAtt {}.Arn`",
+                        r#ref, r#ref
+                    ),
+                ));
+            }
+        }
+        Value::GetAtt { .. } => {}
+        _ => check_arn(value, path, diagnostics),
+    }
+}
+
+impl Validate for Queue {
+    fn validate(&self, path: &str, template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        check_fifo_name(
+            ("QueueName", &self.queue_name),
+            ("FifoQueue", &self.fifo_queue),
+            "queue",
+            path,
+            diagnostics,
+        );
+        let fifo_only = [
+            (
+                "ContentBasedDeduplication",
+                self.content_based_deduplication.is_some(),
+            ),
+            ("DeduplicationScope", self.deduplication_scope.is_some()),
+            ("FifoThroughputLimit", self.fifo_throughput_limit.is_some()),
+        ];
+        if is_standard(&self.fifo_queue) {
+            for (name, _) in fifo_only.iter().filter(|(_, set)| *set) {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/{}", path, name),
+                    format!("`{}` requires `FifoQueue: true`", name),
+                ));
+            }
+        } else {
+            if let Some(scope) = &self.deduplication_scope {
+                check_one_of(
+                    scope,
+                    DEDUPLICATION_SCOPES,
+                    "deduplication scope",
+                    &format!("{}/DeduplicationScope", path),
+                    diagnostics,
+                );
+            }
+            if let Some(limit) = &self.fifo_throughput_limit {
+                check_one_of(
+                    limit,
+                    FIFO_THROUGHPUT_LIMITS,
+                    "FIFO throughput limit",
+                    &format!("{}/FifoThroughputLimit", path),
+                    diagnostics,
+                );
+            }
+        }
+
+        let ranges = [
+            (
+                "DelaySeconds",
+                &self.delay_seconds,
+                DELAYS,
+                "delay in seconds",
+            ),
+            (
+                "MaximumMessageSize",
+                &self.maximum_message_size,
+                MESSAGE_SIZES,
+                "maximum message size in bytes",
+            ),
+            (
+                "MessageRetentionPeriod",
+                &self.message_retention_period,
+                RETENTION_PERIODS,
+                "message retention period in seconds",
+            ),
+            (
+                "ReceiveMessageWaitTimeSeconds",
+                &self.receive_message_wait_time_seconds,
+                WAIT_TIMES,
+                "receive wait time in seconds",
+            ),
+            (
+                "VisibilityTimeout",
+                &self.visibility_timeout,
+                VISIBILITY_TIMEOUTS,
+                "visibility timeout in seconds",
+            ),
+        ];
+        for (name, value, range, what) in ranges {
+            check_range(
+                value,
+                range,
+                what,
+                &format!("{}/{}", path, name),
+                diagnostics,
+            );
+        }
+
+        if self.kms_master_key_id.is_some() && self.sqs_managed_sse_enabled.is_some() {
+            diagnostics.push(Diagnostic::error(
+                format!("{}/SqsManagedSseEnabled", path),
+                "`SqsManagedSseEnabled` cannot be used with `KmsMasterKeyId`",
+            ));
+        }
+
+        if let Some(redrive_policy) = self.redrive_policy.as_ref().and_then(Node::literal) {
+            let path = format!("{}/RedrivePolicy", path);
+            match &redrive_policy.dead_letter_target_arn {
+                Some(target) => {
+                    let path = format!("{}/deadLetterTargetArn", path);
+                    check_queue_arn(target, &path, template, diagnostics);
+                }
+                None => diagnostics.push(Diagnostic::error(
+                    &path,
+                    "`deadLetterTargetArn` is required",
+                )),
+            }
+            check_range(
+                &redrive_policy.max_receive_count,
+                MAX_RECEIVE_COUNTS,
+                "maximum receive count",
+                &format!("{}/maxReceiveCount", path),
+                diagnostics,
+            );
+        }
+    }
+}
+
+impl Validate for QueuePolicy {
+    fn validate(&self, path: &str, template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        self.policy_document.validate(
+            &format!("{}/PolicyDocument", path),
+            PolicyKind::Resource,
+            diagnostics,
+        );
+        for (index, queue) in self.queues.items().iter().enumerate() {
+            if let Value::GetAtt { get_att } = queue {
+                if let Some(Resource::Queue(_)) = template.resource(&get_att[0]) {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/Queues/{}", path, index),
+                        format!(
+                            "`Queues` takes queue URLs; use `!Ref {}` instead",
+                            get_att[0]
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Reports queues that a Lambda function consumes whose visibility timeout is
+/// shorter than the function's timeout, so messages are delivered again while
+/// they are still being processed.
+pub fn validate_queue_consumers(template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+    for resource in template.resources.values() {
+        let Resource::EventSourceMapping(mapping) = resource else {
+            continue;
+        };
+        let mapping = &mapping.properties;
+        let Some(Value::GetAtt { get_att }) = &mapping.event_source_arn else {
+            continue;
+        };
+        let queue_name = &get_att[0];
+        let Some(Resource::Queue(ResourceContainer { properties, .. })) =
+            template.resource(queue_name)
+        else {
+            continue;
+        };
+        let function_name = match &mapping.function_name {
+            Value::Ref { r#ref } => r#ref,
+            Value::GetAtt { get_att } => &get_att[0],
+            _ => continue,
+        };
+        let Some(Resource::Function(function)) = template.resource(function_name) else {
+            continue;
+        };
+
+        let visibility_timeout = properties
+            .as_ref()
+            .and_then(|queue| queue.visibility_timeout.as_ref());
+        let function_timeout = match &function.properties.timeout {
+            Some(timeout) => timeout.as_integer(),
+            None => Some(DEFAULT_FUNCTION_TIMEOUT),
+        };
+        let (path, visibility_timeout) = match visibility_timeout {
+            Some(timeout) => (
+                format!("Resources/{}/Properties/VisibilityTimeout", queue_name),
+                timeout.as_integer(),
+            ),
+            None => (
+                format!("Resources/{}", queue_name),
+                Some(DEFAULT_VISIBILITY_TIMEOUT),
+            ),
+        };
+        if let (Some(visibility_timeout), Some(function_timeout)) =
+            (visibility_timeout, function_timeout)
+        {
+            if visibility_timeout < function_timeout {
+                diagnostics.push(Diagnostic::error(
+                    path,
+                    format!(
+                        "visibility timeout of {} seconds is shorter than the {} second timeout of function `{}`, which consumes the queue",
+                        visibility_timeout, function_timeout, function_name
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{diagnostic::Diagnostic, document::Format, Template};
+
+    #[test]
+    fn test_validate_queue_fifo_name() {
+        let yaml = r#"
+Parameters:
+  Fifo:
+    Type: String
+Resources:
+  Orders:
+    Type: AWS::SQS::Queue
+    Properties:
+      FifoQueue: true
+      QueueName: orders
+  DeadLetters:
+    Type: AWS::SQS::Queue
+    Properties:
+      QueueName: dead-letters.fifo
+  FromParameter:
+    Type: AWS::SQS::Queue
+    Properties:
+      FifoQueue: !Ref Fifo
+      QueueName: events.fifo
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Orders/Properties/QueueName",
+                "the name of a FIFO queue must end in `.fifo`, not `orders`",
+            ),
+            Diagnostic::error(
+                "Resources/DeadLetters/Properties/QueueName",
+                "`dead-letters.fifo` ends in `.fifo`, which requires `FifoQueue: true`",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_queue_fifo_properties() {
+        let yaml = r#"
+Resources:
+  Standard:
+    Type: AWS::SQS::Queue
+    Properties:
+      ContentBasedDeduplication: true
+      DeduplicationScope: queue
+      FifoThroughputLimit: perQueue
+  Fifo:
+    Type: AWS::SQS::Queue
+    Properties:
+      FifoQueue: true
+      DeduplicationScope: message
+      FifoThroughputLimit: perGroup
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Standard/Properties/ContentBasedDeduplication",
+                "`ContentBasedDeduplication` requires `FifoQueue: true`",
+            ),
+            Diagnostic::error(
+                "Resources/Standard/Properties/DeduplicationScope",
+                "`DeduplicationScope` requires `FifoQueue: true`",
+            ),
+            Diagnostic::error(
+                "Resources/Standard/Properties/FifoThroughputLimit",
+                "`FifoThroughputLimit` requires `FifoQueue: true`",
+            ),
+            Diagnostic::error(
+                "Resources/Fifo/Properties/DeduplicationScope",
+                "`message` is not a valid deduplication scope, expected one of messageGroup, queue",
+            ),
+            Diagnostic::error(
+                "Resources/Fifo/Properties/FifoThroughputLimit",
+                "`perGroup` is not a valid FIFO throughput limit, expected one of perMessageGroupId, perQueue",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_queue_ranges() {
+        let yaml = r#"
+Parameters:
+  Delay:
+    Type: Number
+Resources:
+  Queue:
+    Type: AWS::SQS::Queue
+    Properties:
+      DelaySeconds: 1000
+      MaximumMessageSize: 512
+      MessageRetentionPeriod: 30
+      ReceiveMessageWaitTimeSeconds: 21
+      VisibilityTimeout: 50000
+  FromParameter:
+    Type: AWS::SQS::Queue
+    Properties:
+      DelaySeconds: !Ref Delay
+"#;
+        let path = "Resources/Queue/Properties";
+        let expected = vec![
+            Diagnostic::error(
+                format!("{}/DelaySeconds", path),
+                "delay in seconds must be between 0 and 900, not 1000",
+            ),
+            Diagnostic::error(
+                format!("{}/MaximumMessageSize", path),
+                "maximum message size in bytes must be between 1024 and 262144, not 512",
+            ),
+            Diagnostic::error(
+                format!("{}/MessageRetentionPeriod", path),
+                "message retention period in seconds must be between 60 and 1209600, not 30",
+            ),
+            Diagnostic::error(
+                format!("{}/ReceiveMessageWaitTimeSeconds", path),
+                "receive wait time in seconds must be between 0 and 20, not 21",
+            ),
+            Diagnostic::error(
+                format!("{}/VisibilityTimeout", path),
+                "visibility timeout in seconds must be between 0 and 43200, not 50000",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_queue_encryption() {
+        let yaml = r#"
+Resources:
+  Queue:
+    Type: AWS::SQS::Queue
+    Properties:
+      KmsMasterKeyId: alias/aws/sqs
+      SqsManagedSseEnabled: true
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Queue/Properties/SqsManagedSseEnabled",
+            "`SqsManagedSseEnabled` cannot be used with `KmsMasterKeyId`",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_queue_redrive_policy() {
+        let yaml = r#"
+Parameters:
+  DeadLetterArn:
+    Type: String
+Resources:
+  DeadLetters:
+    Type: AWS::SQS::Queue
+  ByRef:
+    Type: AWS::SQS::Queue
+    Properties:
+      RedrivePolicy:
+        deadLetterTargetArn: !Ref DeadLetters
+        maxReceiveCount: 5
+  Untargeted:
+    Type: AWS::SQS::Queue
+    Properties:
+      RedrivePolicy:
+        maxReceiveCount: 0
+  ByName:
+    Type: AWS::SQS::Queue
+    Properties:
+      RedrivePolicy:
+        deadLetterTargetArn: dead-letters
+        maxReceiveCount: 5
+  ByAttribute:
+    Type: AWS::SQS::Queue
+    Properties:
+      RedrivePolicy:
+        deadLetterTargetArn: !GetAtt DeadLetters.Arn
+        maxReceiveCount: 5
+  FromParameter:
+    Type: AWS::SQS::Queue
+    Properties:
+      RedrivePolicy:
+        deadLetterTargetArn: !Ref DeadLetterArn
+        maxReceiveCount: 5
+  Computed:
+    Type: AWS::SQS::Queue
+    Properties:
+      RedrivePolicy: !If [HasDeadLetters, {deadLetterTargetArn: !Ref DeadLetters}, !Ref AWS::NoValue]
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/ByRef/Properties/RedrivePolicy/deadLetterTargetArn",
+                "`Ref` of queue `DeadLetters` returns its URL, not its ARN; use `!GetAtt DeadLetters.Arn`",
+            ),
+            Diagnostic::error(
+                "Resources/Untargeted/Properties/RedrivePolicy",
+                "`deadLetterTargetArn` is required",
+            ),
+            Diagnostic::error(
+                "Resources/Untargeted/Properties/RedrivePolicy/maxReceiveCount",
+                "maximum receive count must be between 1 and 1000, not 0",
+            ),
+            Diagnostic::error(
+                "Resources/ByName/Properties/RedrivePolicy/deadLetterTargetArn",
+                "`dead-letters` is not an ARN",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_queue_policy_queues() {
+        let yaml = r#"
+Resources:
+  Jobs:
+    Type: AWS::SQS::Queue
+  Policy:
+    Type: AWS::SQS::QueuePolicy
+    Properties:
+      Queues: [!GetAtt Jobs.Arn, !Ref Jobs]
+      PolicyDocument:
+        Version: "2012-10-17"
+        Statement:
+          - Effect: Allow
+            Principal: {Service: sns.amazonaws.com}
+            Action: sqs:SendMessage
+            Resource: !GetAtt Jobs.Arn
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Policy/Properties/Queues/0",
+            "`Queues` takes queue URLs; use `!Ref Jobs` instead",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_queue_consumers() {
+        let yaml = r#"
+Parameters:
+  Timeout:
+    Type: Number
+Resources:
+  Jobs:
+    Type: AWS::SQS::Queue
+  Reports:
+    Type: AWS::SQS::Queue
+    Properties:
+      VisibilityTimeout: 30
+  Emails:
+    Type: AWS::SQS::Queue
+  Worker:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: python3.12
+      Handler: index.handler
+      Role: arn:aws:iam::123456789012:role/lambda
+      Timeout: 60
+      Code: {ZipFile: "def handler(event, context): pass"}
+  Mailer:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: python3.12
+      Handler: index.handler
+      Role: arn:aws:iam::123456789012:role/lambda
+      Code: {ZipFile: "def handler(event, context): pass"}
+  Tunable:
+    Type: AWS::Lambda::Function
+    Properties:
+      Runtime: python3.12
+      Handler: index.handler
+      Role: arn:aws:iam::123456789012:role/lambda
+      Timeout: !Ref Timeout
+      Code: {ZipFile: "def handler(event, context): pass"}
+  JobsMapping:
+    Type: AWS::Lambda::EventSourceMapping
+    Properties:
+      EventSourceArn: !GetAtt Jobs.Arn
+      FunctionName: !Ref Worker
+  ReportsMapping:
+    Type: AWS::Lambda::EventSourceMapping
+    Properties:
+      EventSourceArn: !GetAtt Reports.Arn
+      FunctionName: !GetAtt Worker.Arn
+  EmailsMapping:
+    Type: AWS::Lambda::EventSourceMapping
+    Properties:
+      EventSourceArn: !GetAtt Emails.Arn
+      FunctionName: !Ref Mailer
+  TunableMapping:
+    Type: AWS::Lambda::EventSourceMapping
+    Properties:
+      EventSourceArn: !GetAtt Emails.Arn
+      FunctionName: !Ref Tunable
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Jobs",
+                "visibility timeout of 30 seconds is shorter than the 60 second timeout of function `Worker`, which consumes the queue",
+            ),
+            Diagnostic::error(
+                "Resources/Reports/Properties/VisibilityTimeout",
+                "visibility timeout of 30 seconds is shorter than the 60 second timeout of function `Worker`, which consumes the queue",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+}
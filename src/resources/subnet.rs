@@ -0,0 +1,363 @@
+use super::{referenced_resource, Resource};
+use crate::{
+    cidr::Cidr,
+    diagnostic::Diagnostic,
+    tag::Tag,
+    validate::{check_boolean, Validate},
+    value::{Node, Value},
+    Template,
+};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// The netmask lengths a subnet's IPv4 block can have.
+const PREFIX_LENGTHS: RangeInclusive<u8> = 16..=28;
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Subnet {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assign_ipv6_address_on_creation: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub availability_zone: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub availability_zone_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cidr_block: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_dns64: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv4_ipam_pool_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv4_netmask_length: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv6_cidr_block: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv6_native: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub map_public_ip_on_launch: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outpost_arn: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Node<Vec<Node<Tag>>>>,
+    pub vpc_id: Value,
+}
+
+impl Subnet {
+    /// The IPv4 block of the subnet, when it is a literal.
+    pub fn cidr(&self) -> Option<Cidr> {
+        Cidr::ipv4(self.cidr_block.as_ref()?.as_str()?)
+    }
+
+    /// The logical name of the VPC in this template the subnet belongs to.
+    fn vpc<'a>(&'a self, template: &Template) -> Option<&'a str> {
+        let name = referenced_resource(&self.vpc_id)?;
+        matches!(template.resource(name), Some(Resource::Vpc(_))).then_some(name)
+    }
+}
+
+impl Validate for Subnet {
+    fn validate(&self, path: &str, template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        let booleans = [
+            (
+                "AssignIpv6AddressOnCreation",
+                &self.assign_ipv6_address_on_creation,
+            ),
+            ("EnableDns64", &self.enable_dns64),
+            ("Ipv6Native", &self.ipv6_native),
+            ("MapPublicIpOnLaunch", &self.map_public_ip_on_launch),
+        ];
+        for (name, value) in booleans {
+            if let Some(value) = value {
+                check_boolean(value, &format!("{}/{}", path, name), diagnostics);
+            }
+        }
+
+        let Some(block) = self.cidr_block.as_ref().and_then(Value::as_str) else {
+            return;
+        };
+        let path = format!("{}/CidrBlock", path);
+        let Some(cidr) = Cidr::ipv4(block) else {
+            diagnostics.push(Diagnostic::error(
+                path,
+                format!("`{}` is not a valid IPv4 CIDR block", block),
+            ));
+            return;
+        };
+        if !PREFIX_LENGTHS.contains(&cidr.prefix()) {
+            diagnostics.push(Diagnostic::error(
+                &path,
+                format!(
+                    "a subnet block must be between /{} and /{}, not /{}",
+                    PREFIX_LENGTHS.start(),
+                    PREFIX_LENGTHS.end(),
+                    cidr.prefix()
+                ),
+            ));
+        }
+
+        let Some(vpc_blocks) = self.vpc(template).and_then(|vpc| vpc_blocks(vpc, template)) else {
+            return;
+        };
+        if !vpc_blocks.iter().any(|vpc_cidr| vpc_cidr.contains(&cidr)) {
+            let blocks: Vec<String> = vpc_blocks
+                .iter()
+                .map(|vpc_cidr| format!("`{}`", vpc_cidr.network()))
+                .collect();
+            let noun = if blocks.len() == 1 { "block" } else { "blocks" };
+            diagnostics.push(Diagnostic::error(
+                path,
+                format!(
+                    "`{}` is outside the VPC {} {}",
+                    block,
+                    noun,
+                    blocks.join(", ")
+                ),
+            ));
+        }
+    }
+}
+
+/// The IPv4 blocks of a VPC: its own and those that `AWS::EC2::VPCCidrBlock`
+/// resources add. `None` when one of them is not a literal.
+fn vpc_blocks(vpc: &str, template: &Template) -> Option<Vec<Cidr>> {
+    let mut blocks = Vec::new();
+    for (name, resource) in &template.resources {
+        match resource {
+            Resource::Vpc(resource) if name == vpc => blocks.push(resource.properties.cidr()?),
+            Resource::VpcCidrBlock(association)
+                if referenced_resource(&association.properties.vpc_id) == Some(vpc)
+                    && association.properties.is_ipv4() =>
+            {
+                blocks.push(association.properties.cidr()?)
+            }
+            _ => {}
+        }
+    }
+    Some(blocks)
+}
+
+/// Reports subnets whose blocks overlap an earlier subnet of the same VPC.
+pub fn validate_subnets(template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+    let mut subnets: Vec<(&str, &str, Cidr)> = Vec::new();
+    for (name, resource) in &template.resources {
+        let Resource::Subnet(subnet) = resource else {
+            continue;
+        };
+        let subnet = &subnet.properties;
+        let (Some(vpc), Some(cidr)) = (subnet.vpc(template), subnet.cidr()) else {
+            continue;
+        };
+
+        let earlier = subnets
+            .iter()
+            .find(|(other_vpc, _, other)| *other_vpc == vpc && other.overlaps(&cidr));
+        if let Some((_, other_name, other)) = earlier {
+            diagnostics.push(Diagnostic::error(
+                format!("Resources/{}/Properties/CidrBlock", name),
+                format!("`{}` overlaps `{}` of subnet `{}`", cidr, other, other_name),
+            ));
+        }
+        subnets.push((vpc, name, cidr));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{diagnostic::Diagnostic, document::Format, Template};
+
+    #[test]
+    fn test_validate_subnet_booleans() {
+        let yaml = r#"
+Parameters:
+  Public:
+    Type: String
+Resources:
+  Literal:
+    Type: AWS::EC2::Subnet
+    Properties:
+      VpcId: vpc-123
+      MapPublicIpOnLaunch: "yes"
+  FromParameter:
+    Type: AWS::EC2::Subnet
+    Properties:
+      VpcId: vpc-123
+      MapPublicIpOnLaunch: !Ref Public
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Literal/Properties/MapPublicIpOnLaunch",
+            "expected a boolean, `true` or `false`",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_subnet_block() {
+        let yaml = r#"
+Resources:
+  Invalid:
+    Type: AWS::EC2::Subnet
+    Properties:
+      VpcId: vpc-123
+      CidrBlock: 10.0.0.0/33
+  Tiny:
+    Type: AWS::EC2::Subnet
+    Properties:
+      VpcId: vpc-123
+      CidrBlock: 10.0.1.0/29
+  Computed:
+    Type: AWS::EC2::Subnet
+    Properties:
+      VpcId: vpc-123
+      CidrBlock: !Select [0, !Cidr [10.0.0.0/16, 4, 8]]
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Invalid/Properties/CidrBlock",
+                "`10.0.0.0/33` is not a valid IPv4 CIDR block",
+            ),
+            Diagnostic::error(
+                "Resources/Tiny/Properties/CidrBlock",
+                "a subnet block must be between /16 and /28, not /29",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_subnet_outside_vpc_block() {
+        let yaml = r#"
+Resources:
+  Vpc:
+    Type: AWS::EC2::VPC
+    Properties:
+      CidrBlock: 10.0.0.0/16
+  Inside:
+    Type: AWS::EC2::Subnet
+    Properties:
+      VpcId: !Ref Vpc
+      CidrBlock: 10.0.0.0/24
+  Outside:
+    Type: AWS::EC2::Subnet
+    Properties:
+      VpcId: !GetAtt Vpc.VpcId
+      CidrBlock: 10.1.0.0/24
+  Elsewhere:
+    Type: AWS::EC2::Subnet
+    Properties:
+      VpcId: vpc-123
+      CidrBlock: 10.1.0.0/24
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Outside/Properties/CidrBlock",
+            "`10.1.0.0/24` is outside the VPC block `10.0.0.0/16`",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_subnet_in_secondary_vpc_block() {
+        let yaml = r#"
+Resources:
+  Vpc:
+    Type: AWS::EC2::VPC
+    Properties:
+      CidrBlock: 10.0.0.0/16
+  Secondary:
+    Type: AWS::EC2::VPCCidrBlock
+    Properties:
+      VpcId: !Ref Vpc
+      CidrBlock: 100.64.0.0/16
+  Ipv6:
+    Type: AWS::EC2::VPCCidrBlock
+    Properties:
+      VpcId: !Ref Vpc
+      AmazonProvidedIpv6CidrBlock: true
+  Pods:
+    Type: AWS::EC2::Subnet
+    Properties:
+      VpcId: !Ref Vpc
+      CidrBlock: 100.64.0.0/18
+  Outside:
+    Type: AWS::EC2::Subnet
+    Properties:
+      VpcId: !Ref Vpc
+      CidrBlock: 172.16.0.0/24
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Outside/Properties/CidrBlock",
+            "`172.16.0.0/24` is outside the VPC blocks `10.0.0.0/16`, `100.64.0.0/16`",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_subnet_in_computed_vpc_block() {
+        let yaml = r#"
+Resources:
+  Vpc:
+    Type: AWS::EC2::VPC
+    Properties:
+      CidrBlock: 10.0.0.0/16
+  Secondary:
+    Type: AWS::EC2::VPCCidrBlock
+    Properties:
+      VpcId: !Ref Vpc
+      Ipv4IpamPoolId: !Ref Pool
+      Ipv4NetmaskLength: 20
+  Pods:
+    Type: AWS::EC2::Subnet
+    Properties:
+      VpcId: !Ref Vpc
+      CidrBlock: 100.64.0.0/24
+"#;
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(Vec::<Diagnostic>::new(), template.validate());
+    }
+
+    #[test]
+    fn test_validate_overlapping_subnets() {
+        let yaml = r#"
+Resources:
+  Vpc:
+    Type: AWS::EC2::VPC
+    Properties:
+      CidrBlock: 10.0.0.0/16
+  OtherVpc:
+    Type: AWS::EC2::VPC
+    Properties:
+      CidrBlock: 10.0.0.0/16
+  Public:
+    Type: AWS::EC2::Subnet
+    Properties:
+      VpcId: !Ref Vpc
+      CidrBlock: 10.0.0.0/24
+  Private:
+    Type: AWS::EC2::Subnet
+    Properties:
+      VpcId: !Ref Vpc
+      CidrBlock: 10.0.0.128/25
+  SameBlockOtherVpc:
+    Type: AWS::EC2::Subnet
+    Properties:
+      VpcId: !Ref OtherVpc
+      CidrBlock: 10.0.0.0/24
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Private/Properties/CidrBlock",
+            "`10.0.0.128/25` overlaps `10.0.0.0/24` of subnet `Public`",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+}
@@ -1,29 +1,188 @@
-use crate::{tag::Tag, value::Value};
-use serde::Deserialize;
+use crate::{
+    cidr::Cidr,
+    diagnostic::Diagnostic,
+    tag::Tag,
+    validate::{check_boolean, check_one_of, Validate},
+    value::{Node, Value},
+    Template,
+};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
+/// The netmask lengths a VPC's IPv4 block can have.
+const PREFIX_LENGTHS: RangeInclusive<i64> = 16..=28;
+
+const TENANCIES: &[&str] = &["default", "dedicated", "host"];
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "PascalCase")]
 pub struct Vpc {
-    cidr_block: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cidr_block: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     enable_dns_hostnames: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     enable_dns_support: Option<Value>,
-    instance_tenancy: Option<InstanceTenancy>,
-    ipv4_ipam_pool_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    instance_tenancy: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ipv4_ipam_pool_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     ipv4_netmask_length: Option<Value>,
-    tags: Option<Vec<Tag>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<Node<Vec<Node<Tag>>>>,
+}
+
+/// A secondary block associated with a VPC.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct VpcCidrBlock {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amazon_provided_ipv6_cidr_block: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cidr_block: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv4_ipam_pool_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv4_netmask_length: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv6_cidr_block: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv6_ipam_pool_id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv6_netmask_length: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv6_pool: Option<Value>,
+    pub vpc_id: Value,
+}
+
+impl Vpc {
+    /// The IPv4 block of the VPC, when it is a literal.
+    pub fn cidr(&self) -> Option<Cidr> {
+        Cidr::ipv4(self.cidr_block.as_ref()?.as_str()?)
+    }
+}
+
+impl VpcCidrBlock {
+    /// Whether the association adds an IPv4 block rather than an IPv6 one.
+    pub fn is_ipv4(&self) -> bool {
+        self.cidr_block.is_some() || self.ipv4_ipam_pool_id.is_some()
+    }
+
+    /// The IPv4 block it adds, when it is a literal.
+    pub fn cidr(&self) -> Option<Cidr> {
+        Cidr::ipv4(self.cidr_block.as_ref()?.as_str()?)
+    }
+}
+
+impl Validate for Vpc {
+    fn validate(&self, path: &str, _template: &Template, diagnostics: &mut Vec<Diagnostic>) {
+        match (&self.cidr_block, &self.ipv4_ipam_pool_id) {
+            (Some(_), Some(_)) => diagnostics.push(Diagnostic::error(
+                format!("{}/Ipv4IpamPoolId", path),
+                "`Ipv4IpamPoolId` cannot be used with `CidrBlock`",
+            )),
+            (None, None) => diagnostics.push(Diagnostic::error(
+                path,
+                "either `CidrBlock` or `Ipv4IpamPoolId` is required",
+            )),
+            _ => {}
+        }
+        if let Some(netmask_length) = &self.ipv4_netmask_length {
+            let path = format!("{}/Ipv4NetmaskLength", path);
+            if self.cidr_block.is_some() {
+                diagnostics.push(Diagnostic::error(
+                    &path,
+                    "`Ipv4NetmaskLength` cannot be used with `CidrBlock`",
+                ));
+            } else if self.ipv4_ipam_pool_id.is_none() {
+                diagnostics.push(Diagnostic::error(
+                    &path,
+                    "`Ipv4NetmaskLength` requires `Ipv4IpamPoolId`",
+                ));
+            }
+            if let Some(length) = netmask_length.as_integer() {
+                check_prefix_length(length, &path, diagnostics);
+            }
+        }
+
+        if let Some(block) = self.cidr_block.as_ref().and_then(Value::as_str) {
+            let path = format!("{}/CidrBlock", path);
+            match Cidr::ipv4(block) {
+                Some(cidr) => {
+                    check_prefix_length(cidr.prefix().into(), &path, diagnostics);
+                    if cidr.has_host_bits() {
+                        diagnostics.push(Diagnostic::warning(
+                            &path,
+                            format!(
+                                "`{}` has host bits set; the VPC uses `{}`",
+                                block,
+                                cidr.network()
+                            ),
+                        ));
+                    }
+                }
+                None => diagnostics.push(Diagnostic::error(
+                    path,
+                    format!("`{}` is not a valid IPv4 CIDR block", block),
+                )),
+            }
+        }
+
+        if let Some(tenancy) = &self.instance_tenancy {
+            check_one_of(
+                tenancy,
+                TENANCIES,
+                "tenancy",
+                &format!("{}/InstanceTenancy", path),
+                diagnostics,
+            );
+        }
+
+        let dns_support = self.enable_dns_support.as_ref();
+        let dns_hostnames = self.enable_dns_hostnames.as_ref();
+        for (name, value) in [
+            ("EnableDnsSupport", dns_support),
+            ("EnableDnsHostnames", dns_hostnames),
+        ] {
+            if let Some(value) = value {
+                check_boolean(value, &format!("{}/{}", path, name), diagnostics);
+            }
+        }
+        // DNS support is on unless it is turned off.
+        let support = dns_support.map_or(Some(true), Value::as_bool);
+        if dns_hostnames.and_then(Value::as_bool) == Some(true) && support == Some(false) {
+            diagnostics.push(Diagnostic::error(
+                format!("{}/EnableDnsHostnames", path),
+                "`EnableDnsHostnames` can only be true when `EnableDnsSupport` is true",
+            ));
+        }
+    }
 }
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
-enum InstanceTenancy {
-    Default,
-    Dedicated,
-    Host,
+fn check_prefix_length(length: i64, path: &str, diagnostics: &mut Vec<Diagnostic>) {
+    if !PREFIX_LENGTHS.contains(&length) {
+        diagnostics.push(Diagnostic::error(
+            path,
+            format!(
+                "a VPC block must be between /{} and /{}, not /{}",
+                PREFIX_LENGTHS.start(),
+                PREFIX_LENGTHS.end(),
+                length
+            ),
+        ));
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{resources::vpc::InstanceTenancy, tag::Tag, value::Value};
+    use crate::{
+        diagnostic::Diagnostic,
+        document::Format,
+        tag::Tag,
+        value::{Node, Value},
+        Template,
+    };
 
     use super::Vpc;
 
@@ -41,14 +200,14 @@ mod tests {
     ]
 }"#;
         let expected = Vpc {
-            cidr_block: "10.0.0.0/16".to_string(),
+            cidr_block: Some(Value::String("10.0.0.0/16".to_string())),
             enable_dns_support: Some(Value::String("true".to_string())),
             enable_dns_hostnames: Some(Value::String("true".to_string())),
-            tags: Some(vec![Tag {
+            tags: Some(Node::Literal(vec![Node::Literal(Tag {
                 key: "stack".to_string(),
-                value: "production".to_string(),
-            }]),
-            instance_tenancy: Some(InstanceTenancy::Dedicated),
+                value: Value::String("production".to_string()),
+            })])),
+            instance_tenancy: Some(Value::String("dedicated".to_string())),
             ipv4_ipam_pool_id: None,
             ipv4_netmask_length: Some(Value::String("28".to_string())),
         };
@@ -56,4 +215,121 @@ mod tests {
         let actual = serde_json::from_str(json).unwrap();
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_validate_vpc() {
+        let yaml = r#"
+Resources:
+  Valid:
+    Type: AWS::EC2::VPC
+    Properties:
+      CidrBlock: !Ref VpcCidr
+      EnableDnsSupport: "true"
+      EnableDnsHostnames: True
+  Ipam:
+    Type: AWS::EC2::VPC
+    Properties:
+      Ipv4IpamPoolId: !ImportValue IpamPool
+      Ipv4NetmaskLength: 24
+  Small:
+    Type: AWS::EC2::VPC
+    Properties:
+      CidrBlock: 10.0.0.1/29
+      EnableDnsSupport: false
+      EnableDnsHostnames: "true"
+  Both:
+    Type: AWS::EC2::VPC
+    Properties:
+      CidrBlock: 10.0.0/16
+      Ipv4IpamPoolId: ipam-pool-123
+      Ipv4NetmaskLength: 8
+      EnableDnsSupport: "yes"
+  Neither:
+    Type: AWS::EC2::VPC
+    Properties:
+      Ipv4NetmaskLength: 16
+Parameters:
+  VpcCidr:
+    Type: String
+"#;
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Small/Properties/CidrBlock",
+                "a VPC block must be between /16 and /28, not /29",
+            ),
+            Diagnostic::warning(
+                "Resources/Small/Properties/CidrBlock",
+                "`10.0.0.1/29` has host bits set; the VPC uses `10.0.0.0/29`",
+            ),
+            Diagnostic::error(
+                "Resources/Small/Properties/EnableDnsHostnames",
+                "`EnableDnsHostnames` can only be true when `EnableDnsSupport` is true",
+            ),
+            Diagnostic::error(
+                "Resources/Both/Properties/Ipv4IpamPoolId",
+                "`Ipv4IpamPoolId` cannot be used with `CidrBlock`",
+            ),
+            Diagnostic::error(
+                "Resources/Both/Properties/Ipv4NetmaskLength",
+                "`Ipv4NetmaskLength` cannot be used with `CidrBlock`",
+            ),
+            Diagnostic::error(
+                "Resources/Both/Properties/Ipv4NetmaskLength",
+                "a VPC block must be between /16 and /28, not /8",
+            ),
+            Diagnostic::error(
+                "Resources/Both/Properties/CidrBlock",
+                "`10.0.0/16` is not a valid IPv4 CIDR block",
+            ),
+            Diagnostic::error(
+                "Resources/Both/Properties/EnableDnsSupport",
+                "expected a boolean, `true` or `false`",
+            ),
+            Diagnostic::error(
+                "Resources/Neither/Properties",
+                "either `CidrBlock` or `Ipv4IpamPoolId` is required",
+            ),
+            Diagnostic::error(
+                "Resources/Neither/Properties/Ipv4NetmaskLength",
+                "`Ipv4NetmaskLength` requires `Ipv4IpamPoolId`",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
+
+    #[test]
+    fn test_validate_vpc_tenancy() {
+        let yaml = r#"
+Resources:
+  FromParameter:
+    Type: AWS::EC2::VPC
+    Properties:
+      CidrBlock: 10.0.0.0/16
+      InstanceTenancy: !Ref Tenancy
+  Conditional:
+    Type: AWS::EC2::VPC
+    Properties:
+      CidrBlock: 10.1.0.0/16
+      InstanceTenancy: !If [IsProduction, dedicated, default]
+  Invalid:
+    Type: AWS::EC2::VPC
+    Properties:
+      CidrBlock: 10.2.0.0/16
+      InstanceTenancy: shared
+Parameters:
+  Tenancy:
+    Type: String
+Conditions:
+  IsProduction: !Equals [!Ref "AWS::AccountId", "123456789012"]
+"#;
+        let expected = vec![Diagnostic::error(
+            "Resources/Invalid/Properties/InstanceTenancy",
+            "`shared` is not a valid tenancy, expected one of default, dedicated, host",
+        )];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate());
+    }
 }
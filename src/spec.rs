@@ -0,0 +1,890 @@
+use crate::{diagnostic::Diagnostic, resources::Resource, value::Value, Template};
+use anyhow::{bail, Context, Result};
+use indexmap::IndexMap;
+use serde::Deserialize;
+use std::{
+    borrow::Cow,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Resource and property types from the CloudFormation resource
+/// specification or from registry provider schemas. Resources are checked
+/// against it generically, below the typed models: required properties,
+/// primitive types, list and map items and nested property types.
+#[derive(Debug, Default)]
+pub struct Specification {
+    pub(crate) resource_types: IndexMap<String, ResourceType>,
+    pub(crate) property_types: IndexMap<String, PropertyType>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct ResourceType {
+    #[serde(default)]
+    pub(crate) properties: IndexMap<String, Property>,
+    /// The attributes `Fn::GetAtt` can read, such as `Arn` or
+    /// `Endpoint.Address`.
+    #[serde(default)]
+    pub(crate) attributes: IndexMap<String, Property>,
+    /// The properties a provider schema marks read-only, by their path under
+    /// `Properties`, such as `Endpoint/Address`.
+    #[serde(skip)]
+    pub(crate) read_only_properties: Vec<String>,
+}
+
+/// A property type. Most have `Properties`; a few stand for a primitive or a
+/// collection, described by `alias`.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct PropertyType {
+    pub(crate) properties: Option<IndexMap<String, Property>>,
+    #[serde(flatten)]
+    pub(crate) alias: Property,
+}
+
+/// A property as the specification describes it. `property_type` is `List`,
+/// `Map` or the name of a property type; collections describe their items
+/// with `item_type` or `primitive_item_type`.
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct Property {
+    pub(crate) primitive_type: Option<String>,
+    #[serde(rename = "Type")]
+    pub(crate) property_type: Option<String>,
+    pub(crate) item_type: Option<String>,
+    pub(crate) primitive_item_type: Option<String>,
+    #[serde(default)]
+    pub(crate) required: bool,
+}
+
+/// A specification file. The combined file lists `ResourceTypes`; the files
+/// split per resource type have a single `ResourceType`.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SpecificationFile {
+    #[serde(default)]
+    property_types: IndexMap<String, PropertyType>,
+    #[serde(default)]
+    resource_types: IndexMap<String, ResourceType>,
+    #[serde(default)]
+    resource_type: IndexMap<String, ResourceType>,
+}
+
+impl Property {
+    fn primitive(primitive_type: &str) -> Property {
+        Property {
+            primitive_type: Some(primitive_type.to_string()),
+            ..Property::default()
+        }
+    }
+
+    fn named(property_type: &str) -> Property {
+        Property {
+            property_type: Some(property_type.to_string()),
+            ..Property::default()
+        }
+    }
+
+    /// The description of one item of a list or map.
+    pub(crate) fn item(&self) -> Property {
+        Property {
+            primitive_type: self.primitive_item_type.clone(),
+            property_type: self.item_type.clone(),
+            ..Property::default()
+        }
+    }
+}
+
+impl Specification {
+    /// Loads a specification file or provider schema, or every `.json` file
+    /// in a directory of them.
+    pub fn load(path: &Path) -> Result<Specification> {
+        let mut specification = Specification::default();
+        specification.add_path(path)?;
+        Ok(specification)
+    }
+
+    /// Adds the types from a file or directory, as [`Specification::load`]
+    /// reads them. Later paths can add schemas for private registry types and
+    /// `Custom::` resources to the resource specification.
+    pub fn add_path(&mut self, path: &Path) -> Result<()> {
+        if path.is_dir() {
+            let mut files: Vec<PathBuf> = fs::read_dir(path)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<_, _>>()?;
+            files.retain(|file| {
+                file.extension()
+                    .is_some_and(|extension| extension == "json")
+            });
+            files.sort();
+            for file in files {
+                self.add_file(&file)?;
+            }
+        } else {
+            self.add_file(path)?;
+        }
+        Ok(())
+    }
+
+    fn add_file(&mut self, path: &Path) -> Result<()> {
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        self.add(&source)
+            .with_context(|| format!("failed to load {}", path.display()))
+    }
+
+    /// Adds the types of a specification file or a provider schema, written
+    /// in JSON. Types that are already known are replaced.
+    pub fn add(&mut self, source: &str) -> Result<()> {
+        let document: serde_json::Value = serde_json::from_str(source)?;
+        if document.get("typeName").is_some() {
+            return self.add_provider_schema(&document);
+        }
+
+        let file = SpecificationFile::deserialize(&document)?;
+        if file.resource_types.is_empty()
+            && file.resource_type.is_empty()
+            && file.property_types.is_empty()
+        {
+            bail!("not a resource specification or provider schema");
+        }
+        self.property_types.extend(file.property_types);
+        self.resource_types.extend(file.resource_types);
+        self.resource_types.extend(file.resource_type);
+        Ok(())
+    }
+
+    /// Translates a provider schema's JSON Schema into specification types.
+    /// Its definitions become property types of the resource type, and so do
+    /// objects written inline, named after their path.
+    fn add_provider_schema(&mut self, schema: &serde_json::Value) -> Result<()> {
+        let Some(type_name) = schema["typeName"].as_str() else {
+            bail!("`typeName` must be a string");
+        };
+        let definitions = schema
+            .get("definitions")
+            .and_then(serde_json::Value::as_object);
+        for (name, definition) in definitions.into_iter().flatten() {
+            let property_type = match definition.get("properties") {
+                Some(_) => PropertyType {
+                    properties: Some(self.schema_object(type_name, name, definition)),
+                    alias: Property::default(),
+                },
+                None => PropertyType {
+                    properties: None,
+                    alias: self.schema_property(type_name, name, definition),
+                },
+            };
+            self.property_types
+                .insert(format!("{}.{}", type_name, name), property_type);
+        }
+
+        let properties = self.schema_object(type_name, "", schema);
+        // Read-only properties are set by the provider and read back with
+        // `Fn::GetAtt`, so `/properties/Endpoint/Address` is the attribute
+        // `Endpoint.Address`.
+        let read_only_properties: Vec<String> = schema
+            .get("readOnlyProperties")
+            .and_then(serde_json::Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(serde_json::Value::as_str)
+            .filter_map(|pointer| pointer.strip_prefix("/properties/"))
+            .map(str::to_string)
+            .collect();
+        let attributes = read_only_properties
+            .iter()
+            .map(|pointer| (pointer.replace('/', "."), Property::default()))
+            .collect();
+        self.resource_types.insert(
+            type_name.to_string(),
+            ResourceType {
+                properties,
+                attributes,
+                read_only_properties,
+            },
+        );
+        Ok(())
+    }
+
+    fn schema_object(
+        &mut self,
+        type_name: &str,
+        path: &str,
+        schema: &serde_json::Value,
+    ) -> IndexMap<String, Property> {
+        let required: Vec<&str> = schema
+            .get("required")
+            .and_then(serde_json::Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(serde_json::Value::as_str)
+            .collect();
+        let mut properties = IndexMap::new();
+        let schemas = schema
+            .get("properties")
+            .and_then(serde_json::Value::as_object);
+        for (name, property_schema) in schemas.into_iter().flatten() {
+            let path = match path {
+                "" => name.clone(),
+                _ => format!("{}/{}", path, name),
+            };
+            let mut property = self.schema_property(type_name, &path, property_schema);
+            property.required = required.contains(&name.as_str());
+            properties.insert(name.clone(), property);
+        }
+        properties
+    }
+
+    fn schema_property(
+        &mut self,
+        type_name: &str,
+        path: &str,
+        schema: &serde_json::Value,
+    ) -> Property {
+        if let Some(reference) = schema.get("$ref").and_then(serde_json::Value::as_str) {
+            let name = reference
+                .strip_prefix("#/definitions/")
+                .unwrap_or(reference);
+            return Property::named(name);
+        }
+
+        // Items and values that are themselves collections are not described
+        // any further, as in the resource specification.
+        let collection = |property_type: &str, item: Property| match item.property_type.as_deref() {
+            Some("List" | "Map") => Property {
+                primitive_item_type: Some("Json".to_string()),
+                ..Property::named(property_type)
+            },
+            _ => Property {
+                item_type: item.property_type,
+                primitive_item_type: item.primitive_type,
+                ..Property::named(property_type)
+            },
+        };
+        let values = schema
+            .get("patternProperties")
+            .and_then(serde_json::Value::as_object)
+            .and_then(|patterns| patterns.values().next())
+            .or_else(|| {
+                schema
+                    .get("additionalProperties")
+                    .filter(|value| value.is_object())
+            });
+        match (
+            schema.get("type").and_then(serde_json::Value::as_str),
+            values,
+        ) {
+            (Some("string"), _) => Property::primitive("String"),
+            (Some("integer"), _) => Property::primitive("Integer"),
+            (Some("number"), _) => Property::primitive("Double"),
+            (Some("boolean"), _) => Property::primitive("Boolean"),
+            (Some("array"), _) => {
+                let item = match schema.get("items") {
+                    Some(items) => {
+                        self.schema_property(type_name, &format!("{}/items", path), items)
+                    }
+                    None => Property::primitive("Json"),
+                };
+                collection("List", item)
+            }
+            (Some("object"), _) if schema.get("properties").is_some() => {
+                let properties = self.schema_object(type_name, path, schema);
+                self.property_types.insert(
+                    format!("{}.{}", type_name, path),
+                    PropertyType {
+                        properties: Some(properties),
+                        alias: Property::default(),
+                    },
+                );
+                Property::named(path)
+            }
+            (Some("object"), Some(values)) => {
+                let item = self.schema_property(type_name, &format!("{}/values", path), values);
+                collection("Map", item)
+            }
+            _ => Property::primitive("Json"),
+        }
+    }
+
+    /// Checks the properties of every resource against its type, and the
+    /// attributes that `Fn::GetAtt` reads from it. Resources with a typed
+    /// model are checked by their `Properties` as written, so keys the model
+    /// ignores are still reported. Custom resources without a schema are
+    /// skipped, and other resources of a type the specification does not
+    /// describe are reported as unchecked.
+    pub fn validate(&self, template: &Template) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut types = IndexMap::new();
+        let mut values = Vec::new();
+        for (name, resource) in &template.resources {
+            let Ok(document) = serde_json::to_value(resource) else {
+                continue;
+            };
+            let Some(resource_type) = document.get("Type").and_then(serde_json::Value::as_str)
+            else {
+                continue;
+            };
+            types.insert(name.as_str(), resource_type.to_string());
+
+            let properties = match document.get("Properties") {
+                None | Some(serde_json::Value::Null) => Value::Map(IndexMap::new()),
+                Some(properties) => match serde_json::from_value(properties.clone()) {
+                    Ok(properties) => properties,
+                    Err(_) => continue,
+                },
+            };
+            let path = format!("Resources/{}/Properties", name);
+            match self.resource_types.get(resource_type) {
+                Some(specification) => self.check_resource(
+                    specification,
+                    resource_type,
+                    &properties,
+                    &path,
+                    &mut diagnostics,
+                ),
+                None if is_custom(resource_type) => {}
+                None => {
+                    if let Resource::Other(_) = resource {
+                        diagnostics.push(Diagnostic::warning(
+                            format!("Resources/{}/Type", name),
+                            format!(
+                                "resource type `{}` is not in the resource specification, so its properties are not checked",
+                                resource_type
+                            ),
+                        ));
+                    }
+                }
+            }
+            values.push((path, properties));
+        }
+
+        for (path, value) in &values {
+            self.check_attributes(value, path, &types, &mut diagnostics);
+        }
+        for (name, output) in template.outputs.iter().flatten() {
+            let path = format!("Outputs/{}/Value", name);
+            self.check_attributes(output.value(), &path, &types, &mut diagnostics);
+        }
+        diagnostics
+    }
+
+    fn check_resource(
+        &self,
+        specification: &ResourceType,
+        resource_type: &str,
+        properties: &Value,
+        path: &str,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let mut expected = Cow::Borrowed(&specification.properties);
+        // Every custom resource names its provider with `ServiceToken`, which
+        // the schema of its property contract need not list.
+        if is_custom(resource_type) && !expected.contains_key("ServiceToken") {
+            expected.to_mut().insert(
+                "ServiceToken".to_string(),
+                Property {
+                    required: true,
+                    ..Property::primitive("String")
+                },
+            );
+        }
+        let checker = Checker {
+            specification: self,
+            resource_type,
+        };
+        checker.check_properties(&expected, properties, resource_type, path, diagnostics);
+
+        for pointer in &specification.read_only_properties {
+            let mut value = Some(properties);
+            for segment in pointer.split('/') {
+                value = match value {
+                    Some(Value::Map(map)) => map.get(segment),
+                    _ => None,
+                };
+            }
+            if value.is_some() {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}/{}", path, pointer),
+                    format!(
+                        "`{}` is read-only; `{}` sets it, and `Fn::GetAtt` reads it",
+                        pointer.replace('/', "."),
+                        resource_type
+                    ),
+                ));
+            }
+        }
+    }
+
+    /// Reports `Fn::GetAtt` and `${Name.Attribute}` references to attributes
+    /// that the resource's type does not have. `types` holds the type of
+    /// every resource in the template.
+    fn check_attributes(
+        &self,
+        value: &Value,
+        path: &str,
+        types: &IndexMap<&str, String>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        match value {
+            Value::Map(map) => {
+                for (key, value) in map {
+                    let path = format!("{}/{}", path, key);
+                    self.check_attributes(value, &path, types, diagnostics);
+                }
+                return;
+            }
+            Value::List(items) => {
+                for (index, value) in items.iter().enumerate() {
+                    let path = format!("{}/{}", path, index);
+                    self.check_attributes(value, &path, types, diagnostics);
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        for reference in value.references() {
+            let Some(attribute) = &reference.attribute else {
+                continue;
+            };
+            let Some(resource_type) = types.get(reference.name.as_str()) else {
+                continue;
+            };
+            let Some(specification) = self.resource_types.get(resource_type) else {
+                continue;
+            };
+            // A nested stack has an attribute for each of its outputs.
+            if resource_type == "AWS::CloudFormation::Stack" && attribute.starts_with("Outputs.") {
+                continue;
+            }
+            if specification.attributes.contains_key(attribute) {
+                continue;
+            }
+            let attributes: Vec<&str> = specification
+                .attributes
+                .keys()
+                .map(String::as_str)
+                .collect();
+            let message = match attributes.is_empty() {
+                true => format!(
+                    "resource `{}` of type `{}` has no attributes",
+                    reference.name, resource_type
+                ),
+                false => format!(
+                    "resource `{}` of type `{}` has no attribute `{}`, expected one of {}",
+                    reference.name,
+                    resource_type,
+                    attribute,
+                    attributes.join(", ")
+                ),
+            };
+            diagnostics.push(Diagnostic::error(path, message));
+        }
+    }
+
+    /// Looks up a property type by the name a property uses, first among the
+    /// resource type's own property types and then among the shared ones
+    /// such as `Tag`.
+    pub(crate) fn property_type(
+        &self,
+        resource_type: &str,
+        name: &str,
+    ) -> Option<(&str, &PropertyType)> {
+        self.property_types
+            .get_key_value(&format!("{}.{}", resource_type, name))
+            .or_else(|| self.property_types.get_key_value(name))
+            .map(|(name, property_type)| (name.as_str(), property_type))
+    }
+}
+
+fn is_custom(resource_type: &str) -> bool {
+    resource_type.starts_with("Custom::") || resource_type == "AWS::CloudFormation::CustomResource"
+}
+
+/// Checks the properties of one resource.
+struct Checker<'a> {
+    specification: &'a Specification,
+    resource_type: &'a str,
+}
+
+impl Checker<'_> {
+    /// Checks an object with named properties. `owner` names the resource or
+    /// property type in messages.
+    fn check_properties(
+        &self,
+        properties: &IndexMap<String, Property>,
+        value: &Value,
+        owner: &str,
+        path: &str,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let map = match value {
+            Value::Map(map) => map,
+            _ if value.as_intrinsic().is_some() => return,
+            _ => {
+                diagnostics.push(Diagnostic::error(
+                    path,
+                    format!("expected the properties of `{}`", owner),
+                ));
+                return;
+            }
+        };
+        for (name, property) in properties {
+            if property.required && !map.contains_key(name) {
+                diagnostics.push(Diagnostic::error(path, format!("`{}` is required", name)));
+            }
+        }
+        for (name, value) in map {
+            let path = format!("{}/{}", path, name);
+            match properties.get(name) {
+                Some(property) => self.check_value(property, value, &path, diagnostics),
+                None => diagnostics.push(Diagnostic::error(
+                    path,
+                    format!("`{}` is not a property of `{}`", name, owner),
+                )),
+            }
+        }
+    }
+
+    fn check_value(
+        &self,
+        property: &Property,
+        value: &Value,
+        path: &str,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        // Intrinsic functions are resolved at deploy time.
+        if value.as_intrinsic().is_some() {
+            return;
+        }
+        if let Some(primitive_type) = &property.primitive_type {
+            check_primitive(primitive_type, value, path, diagnostics);
+            return;
+        }
+        match property.property_type.as_deref() {
+            Some("List") => {
+                let Value::List(items) = value else {
+                    diagnostics.push(Diagnostic::error(path, "expected a list"));
+                    return;
+                };
+                let item = property.item();
+                for (index, value) in items.iter().enumerate() {
+                    self.check_value(&item, value, &format!("{}/{}", path, index), diagnostics);
+                }
+            }
+            Some("Map") => {
+                let Value::Map(map) = value else {
+                    diagnostics.push(Diagnostic::error(path, "expected a map"));
+                    return;
+                };
+                let item = property.item();
+                for (key, value) in map {
+                    self.check_value(&item, value, &format!("{}/{}", path, key), diagnostics);
+                }
+            }
+            Some(name) => {
+                let property_type = self.specification.property_type(self.resource_type, name);
+                match property_type {
+                    Some((
+                        owner,
+                        PropertyType {
+                            properties: Some(properties),
+                            ..
+                        },
+                    )) => self.check_properties(properties, value, owner, path, diagnostics),
+                    Some((_, PropertyType { alias, .. })) => {
+                        self.check_value(alias, value, path, diagnostics)
+                    }
+                    None => {}
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+/// Reports a literal that does not fit a primitive type. CloudFormation
+/// converts scalars to strings, and numbers and booleans written as strings
+/// to their type.
+fn check_primitive(
+    primitive_type: &str,
+    value: &Value,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let expected = match primitive_type {
+        "String" | "Timestamp" => "a string",
+        "Integer" | "Long" => "an integer",
+        "Double" => "a number",
+        "Boolean" => "a boolean, `true` or `false`",
+        _ => return,
+    };
+    let valid = match (primitive_type, value) {
+        (
+            "String" | "Timestamp",
+            Value::String(_) | Value::Number(_) | Value::Float(_) | Value::Bool(_),
+        ) => true,
+        ("Integer" | "Long", _) => value.as_integer().is_some(),
+        ("Double", Value::Number(_) | Value::Float(_)) => true,
+        ("Double", Value::String(number)) => number.parse::<f64>().is_ok(),
+        ("Boolean", _) => value.as_bool().is_some(),
+        _ => false,
+    };
+    if !valid {
+        diagnostics.push(Diagnostic::error(path, format!("expected {}", expected)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Specification;
+    use crate::{diagnostic::Diagnostic, document::Format, Template};
+
+    const SPECIFICATION: &str = r#"{
+    "ResourceSpecificationVersion": "1.0.0",
+    "PropertyTypes": {
+        "AWS::Logs::MetricFilter.MetricTransformation": {
+            "Properties": {
+                "MetricName": {"PrimitiveType": "String", "Required": true},
+                "MetricNamespace": {"PrimitiveType": "String", "Required": true},
+                "MetricValue": {"PrimitiveType": "String", "Required": true},
+                "DefaultValue": {"PrimitiveType": "Double", "Required": false}
+            }
+        },
+        "Tag": {
+            "Properties": {
+                "Key": {"PrimitiveType": "String", "Required": true},
+                "Value": {"PrimitiveType": "String", "Required": true}
+            }
+        }
+    },
+    "ResourceTypes": {
+        "AWS::Logs::LogGroup": {
+            "Properties": {
+                "LogGroupName": {"PrimitiveType": "String", "Required": false},
+                "RetentionInDays": {"PrimitiveType": "Integer", "Required": false},
+                "Tags": {"Type": "List", "ItemType": "Tag", "Required": false}
+            }
+        },
+        "AWS::Logs::MetricFilter": {
+            "Properties": {
+                "FilterPattern": {"PrimitiveType": "String", "Required": true},
+                "LogGroupName": {"PrimitiveType": "String", "Required": true},
+                "MetricTransformations": {
+                    "Type": "List",
+                    "ItemType": "MetricTransformation",
+                    "Required": true
+                }
+            }
+        },
+        "AWS::SNS::Topic": {
+            "Properties": {
+                "DisplayName": {"PrimitiveType": "String", "Required": false},
+                "FifoTopic": {"PrimitiveType": "Boolean", "Required": false},
+                "TopicName": {"PrimitiveType": "String", "Required": false}
+            }
+        }
+    }
+}"#;
+
+    const PROVIDER_SCHEMA: &str = r##"{
+    "typeName": "AWS::SSM::Parameter",
+    "definitions": {
+        "Tier": {"type": "string", "enum": ["Standard", "Advanced"]}
+    },
+    "properties": {
+        "Name": {"type": "string"},
+        "Type": {"type": "string"},
+        "Value": {"type": "string"},
+        "Tier": {"$ref": "#/definitions/Tier"},
+        "Tags": {
+            "type": "object",
+            "patternProperties": {"^.{1,128}$": {"type": "string"}}
+        },
+        "Policies": {
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": {"Type": {"type": "string"}, "Days": {"type": "integer"}},
+                "required": ["Type"]
+            }
+        }
+    },
+    "required": ["Type", "Value"]
+}"##;
+
+    #[test]
+    fn test_validate_with_specification() {
+        let yaml = r#"
+Resources:
+  Logs:
+    Type: AWS::Logs::LogGroup
+    Properties:
+      RetentionInDays: a week
+      Tags:
+        - Key: team
+        - Key: owner
+          Value: !Ref AWS::StackName
+      KmsKey: alias/logs
+  Errors:
+    Type: AWS::Logs::MetricFilter
+    Properties:
+      LogGroupName: !Ref Logs
+      MetricTransformations:
+        - MetricName: Errors
+          MetricNamespace: App
+          MetricValue: "1"
+          DefaultValue: none
+  Topic:
+    Type: AWS::SNS::Topic
+    Properties:
+      FifoTopic: "yes"
+      DisplayName: [alerts]
+      TopicNme: alerts
+  Parameter:
+    Type: AWS::SSM::Parameter
+    Properties:
+      Type: String
+      Tier: [Advanced]
+      Tags: {team: platform, cost: 10, owner: [a]}
+      Policies:
+        - Days: 7
+  Pipe:
+    Type: AWS::Pipes::Pipe
+  Certificate:
+    Type: Custom::Certificate
+    Properties:
+      ServiceToken: !GetAtt Function.Arn
+"#;
+        let mut specification = Specification::default();
+        specification.add(SPECIFICATION).unwrap();
+        specification.add(PROVIDER_SCHEMA).unwrap();
+
+        let expected = vec![
+            Diagnostic::error("Resources/Topic/Properties/FifoTopic", "expected a boolean, `true` or `false`"),
+            Diagnostic::error(
+                "Resources/Logs/Properties/RetentionInDays",
+                "expected an integer",
+            ),
+            Diagnostic::error("Resources/Logs/Properties/Tags/0", "`Value` is required"),
+            Diagnostic::error(
+                "Resources/Logs/Properties/KmsKey",
+                "`KmsKey` is not a property of `AWS::Logs::LogGroup`",
+            ),
+            Diagnostic::error("Resources/Errors/Properties", "`FilterPattern` is required"),
+            Diagnostic::error(
+                "Resources/Errors/Properties/MetricTransformations/0/DefaultValue",
+                "expected a number",
+            ),
+            Diagnostic::error(
+                "Resources/Topic/Properties/DisplayName",
+                "expected a string",
+            ),
+            Diagnostic::error(
+                "Resources/Topic/Properties/TopicNme",
+                "`TopicNme` is not a property of `AWS::SNS::Topic`",
+            ),
+            Diagnostic::error("Resources/Parameter/Properties", "`Value` is required"),
+            Diagnostic::error("Resources/Parameter/Properties/Tier", "expected a string"),
+            Diagnostic::error(
+                "Resources/Parameter/Properties/Tags/owner",
+                "expected a string",
+            ),
+            Diagnostic::error(
+                "Resources/Parameter/Properties/Policies/0",
+                "`Type` is required",
+            ),
+            Diagnostic::warning(
+                "Resources/Pipe/Type",
+                "resource type `AWS::Pipes::Pipe` is not in the resource specification, so its properties are not checked",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate_with(&specification));
+    }
+
+    #[test]
+    fn test_validate_registry_and_custom_types() {
+        let firewall = r##"{
+    "typeName": "MyOrg::Network::Firewall",
+    "properties": {
+        "Arn": {"type": "string"},
+        "Endpoint": {
+            "type": "object",
+            "properties": {"Address": {"type": "string"}, "Port": {"type": "integer"}}
+        },
+        "VpcId": {"type": "string"},
+        "Rules": {"type": "array", "items": {"type": "string"}}
+    },
+    "required": ["VpcId"],
+    "readOnlyProperties": ["/properties/Arn", "/properties/Endpoint/Address"]
+}"##;
+        let certificate = r##"{
+    "typeName": "Custom::Certificate",
+    "properties": {
+        "DomainName": {"type": "string"},
+        "CertificateArn": {"type": "string"}
+    },
+    "required": ["DomainName"],
+    "readOnlyProperties": ["/properties/CertificateArn"]
+}"##;
+        let yaml = r#"
+Resources:
+  Firewall:
+    Type: MyOrg::Network::Firewall
+    Properties:
+      VpcId: vpc-1234
+      Rules: !Split [",", !Ref AWS::StackName]
+      Arn: arn:aws:firewall:::firewall/main
+      Endpoint:
+        Address: 10.0.0.1
+        Port: 443
+  Certificate:
+    Type: Custom::Certificate
+    Properties:
+      Domain: example.com
+  Other:
+    Type: Custom::Other
+    Properties:
+      Address: !GetAtt Firewall.Endpoint.Address
+      Certificate: !Sub "${Certificate.Arn}"
+Outputs:
+  Firewall:
+    Value: !GetAtt Firewall.FirewallArn
+"#;
+        let mut specification = Specification::default();
+        specification.add(firewall).unwrap();
+        specification.add(certificate).unwrap();
+
+        let expected = vec![
+            Diagnostic::error(
+                "Resources/Firewall/Properties/Arn",
+                "`Arn` is read-only; `MyOrg::Network::Firewall` sets it, and `Fn::GetAtt` reads it",
+            ),
+            Diagnostic::error(
+                "Resources/Firewall/Properties/Endpoint/Address",
+                "`Endpoint.Address` is read-only; `MyOrg::Network::Firewall` sets it, and `Fn::GetAtt` reads it",
+            ),
+            Diagnostic::error("Resources/Certificate/Properties", "`DomainName` is required"),
+            Diagnostic::error("Resources/Certificate/Properties", "`ServiceToken` is required"),
+            Diagnostic::error(
+                "Resources/Certificate/Properties/Domain",
+                "`Domain` is not a property of `Custom::Certificate`",
+            ),
+            Diagnostic::error(
+                "Resources/Other/Properties/Certificate",
+                "resource `Certificate` of type `Custom::Certificate` has no attribute `Arn`, expected one of CertificateArn",
+            ),
+            Diagnostic::error(
+                "Outputs/Firewall/Value",
+                "resource `Firewall` of type `MyOrg::Network::Firewall` has no attribute `FirewallArn`, expected one of Arn, Endpoint.Address",
+            ),
+        ];
+
+        let template = Template::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(expected, template.validate_with(&specification));
+    }
+}
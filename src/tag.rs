@@ -1,15 +1,17 @@
-use serde::Deserialize;
+use crate::value::Value;
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "PascalCase")]
 pub struct Tag {
     pub key: String,
-    pub value: String,
+    pub value: Value,
 }
 
 #[cfg(test)]
 mod tests {
     use super::Tag;
+    use crate::value::Value;
 
     #[test]
     fn test_deserialize_tags() {
@@ -22,11 +24,11 @@ mod tests {
         let expected = vec![
             Tag {
                 key: "keyname1".to_string(),
-                value: "value1".to_string(),
+                value: Value::String("value1".to_string()),
             },
             Tag {
                 key: "keyname2".to_string(),
-                value: "value2".to_string(),
+                value: Value::String("value2".to_string()),
             },
         ];
 
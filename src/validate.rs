@@ -0,0 +1,129 @@
+use crate::{diagnostic::Diagnostic, value::Value, Template};
+use std::ops::RangeInclusive;
+
+pub const PSEUDO_PARAMETERS: &[&str] = &[
+    "AWS::AccountId",
+    "AWS::NotificationARNs",
+    "AWS::NoValue",
+    "AWS::Partition",
+    "AWS::Region",
+    "AWS::StackId",
+    "AWS::StackName",
+    "AWS::URLSuffix",
+];
+
+/// Checks a part of a template that is found at `path`.
+pub trait Validate {
+    fn validate(&self, path: &str, template: &Template, diagnostics: &mut Vec<Diagnostic>);
+}
+
+/// Reports `Ref`, `Fn::GetAtt` and `Fn::Sub` references to names the template
+/// does not define.
+pub fn check_references(
+    value: &Value,
+    path: &str,
+    template: &Template,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for reference in value.references() {
+        let name = reference.name.as_str();
+        let defined = match reference.attribute {
+            Some(_) => template.resource(name).is_some(),
+            None => {
+                template.resource(name).is_some()
+                    || template.parameter(name).is_some()
+                    || PSEUDO_PARAMETERS.contains(&name)
+            }
+        };
+        if !defined {
+            let kind = match reference.attribute {
+                Some(_) => "resource",
+                None => "resource or parameter",
+            };
+            diagnostics.push(Diagnostic::error(
+                path,
+                format!("references {} `{}` that does not exist", kind, name),
+            ));
+        }
+    }
+}
+
+/// Reports a literal that is not a boolean. Intrinsic functions are left to
+/// CloudFormation.
+pub fn check_boolean(value: &Value, path: &str, diagnostics: &mut Vec<Diagnostic>) {
+    if value.as_intrinsic().is_none() && value.as_bool().is_none() {
+        diagnostics.push(Diagnostic::error(
+            path,
+            "expected a boolean, `true` or `false`",
+        ));
+    }
+}
+
+/// Reports a string literal that is not one of the allowed values. `kind`
+/// names what the value is, such as `tenancy`.
+pub fn check_one_of(
+    value: &Value,
+    allowed: &[&str],
+    kind: &str,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if let Some(value) = value.as_str() {
+        if !allowed.contains(&value) {
+            diagnostics.push(Diagnostic::error(
+                path,
+                format!(
+                    "`{}` is not a valid {}, expected one of {}",
+                    value,
+                    kind,
+                    allowed.join(", ")
+                ),
+            ));
+        }
+    }
+}
+
+/// Reports a literal number outside a range.
+pub fn check_range(
+    value: &Option<Value>,
+    range: RangeInclusive<i64>,
+    what: &str,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(number) = value.as_ref().and_then(Value::as_integer) else {
+        return;
+    };
+    if !range.contains(&number) {
+        diagnostics.push(Diagnostic::error(
+            path,
+            format!(
+                "{} must be between {} and {}, not {}",
+                what,
+                range.start(),
+                range.end(),
+                number
+            ),
+        ));
+    }
+}
+
+/// Whether a value can evaluate to a string: a string literal, or an
+/// intrinsic function that returns one.
+pub fn is_string(value: &Value) -> bool {
+    match value {
+        Value::String(_)
+        | Value::Ref { .. }
+        | Value::GetAtt { .. }
+        | Value::Join { .. }
+        | Value::Sub { .. }
+        | Value::Base64 { .. }
+        | Value::FindInMap { .. }
+        | Value::ImportValue { .. }
+        | Value::Select { .. } => true,
+        Value::If {
+            r#if: (_, if_true, if_false),
+        } => is_string(if_true) || is_string(if_false),
+        _ => false,
+    }
+}
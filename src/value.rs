@@ -1,31 +1,531 @@
-use serde::Deserialize;
+use indexmap::IndexMap;
+use serde::{
+    de::{self, DeserializeOwned, MapAccess, SeqAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::fmt;
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
-#[serde(untagged)]
+pub type Map = IndexMap<String, Value>;
+
+/// Any value that can appear in a template. Intrinsic functions are recognized
+/// structurally: a mapping with a single `Ref` or `Fn::*` key whose argument
+/// has the expected shape. Anything else is kept as a plain `Map` or `List`,
+/// so a document always round-trips without losing data.
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::enum_variant_names)]
 pub enum Value {
     String(String),
     Number(i64),
-    #[serde(rename_all = "PascalCase")]
+    Float(f64),
+    Bool(bool),
+    Null,
+    List(Vec<Value>),
+    Map(Map),
     Ref {
         r#ref: String,
     },
     GetAtt {
-        #[serde(rename = "Fn::GetAtt")]
         get_att: Vec<String>,
     },
     Join {
-        #[serde(rename = "Fn::Join")]
         join: (String, Vec<Value>),
     },
     Sub {
-        #[serde(rename = "Fn::Sub")]
         sub: String,
+        variables: Option<Map>,
+    },
+    Base64 {
+        base64: Box<Value>,
+    },
+    Cidr {
+        cidr: Vec<Value>,
+    },
+    FindInMap {
+        find_in_map: Vec<Value>,
+    },
+    GetAZs {
+        get_azs: Box<Value>,
+    },
+    ImportValue {
+        import_value: Box<Value>,
+    },
+    Select {
+        select: (Box<Value>, Box<Value>),
+    },
+    Split {
+        split: (String, Box<Value>),
     },
+    If {
+        r#if: (String, Box<Value>, Box<Value>),
+    },
+    Equals {
+        equals: (Box<Value>, Box<Value>),
+    },
+    And {
+        and: Vec<Value>,
+    },
+    Or {
+        or: Vec<Value>,
+    },
+    Not {
+        not: Box<Value>,
+    },
+    Condition {
+        condition: String,
+    },
+}
+
+// Template numbers come from JSON or YAML literals and are never NaN.
+impl Eq for Value {}
+
+impl Value {
+    /// Returns the contents of a string literal.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// Returns a literal boolean, also when it is written as the string
+    /// `"true"` or `"false"` as CloudFormation allows.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(boolean) => Some(*boolean),
+            Value::String(string) if string.eq_ignore_ascii_case("true") => Some(true),
+            Value::String(string) if string.eq_ignore_ascii_case("false") => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Returns a literal integer, also when it is written as a string as
+    /// CloudFormation allows.
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Value::Number(number) => Some(*number),
+            Value::String(string) => string.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Whether the value is `Ref: AWS::NoValue`, which removes the property
+    /// it is set on.
+    pub fn is_no_value(&self) -> bool {
+        matches!(self, Value::Ref { r#ref } if r#ref == "AWS::NoValue")
+    }
+
+    /// Returns the long-form key and the argument of an intrinsic function.
+    pub fn as_intrinsic(&self) -> Option<(&'static str, Value)> {
+        let string = |value: &String| Value::String(value.clone());
+        let list = |values: &[&Value]| Value::List(values.iter().copied().cloned().collect());
+
+        let intrinsic = match self {
+            Value::Ref { r#ref } => ("Ref", string(r#ref)),
+            Value::GetAtt { get_att } => (
+                "Fn::GetAtt",
+                Value::List(get_att.iter().map(string).collect()),
+            ),
+            Value::Join {
+                join: (delimiter, values),
+            } => (
+                "Fn::Join",
+                list(&[&string(delimiter), &Value::List(values.clone())]),
+            ),
+            Value::Sub {
+                sub,
+                variables: None,
+            } => ("Fn::Sub", string(sub)),
+            Value::Sub {
+                sub,
+                variables: Some(variables),
+            } => (
+                "Fn::Sub",
+                list(&[&string(sub), &Value::Map(variables.clone())]),
+            ),
+            Value::Base64 { base64 } => ("Fn::Base64", base64.as_ref().clone()),
+            Value::Cidr { cidr } => ("Fn::Cidr", Value::List(cidr.clone())),
+            Value::FindInMap { find_in_map } => ("Fn::FindInMap", Value::List(find_in_map.clone())),
+            Value::GetAZs { get_azs } => ("Fn::GetAZs", get_azs.as_ref().clone()),
+            Value::ImportValue { import_value } => {
+                ("Fn::ImportValue", import_value.as_ref().clone())
+            }
+            Value::Select {
+                select: (index, values),
+            } => ("Fn::Select", list(&[index, values])),
+            Value::Split {
+                split: (delimiter, source),
+            } => ("Fn::Split", list(&[&string(delimiter), source])),
+            Value::If {
+                r#if: (condition, if_true, if_false),
+            } => ("Fn::If", list(&[&string(condition), if_true, if_false])),
+            Value::Equals {
+                equals: (left, right),
+            } => ("Fn::Equals", list(&[left, right])),
+            Value::And { and } => ("Fn::And", Value::List(and.clone())),
+            Value::Or { or } => ("Fn::Or", Value::List(or.clone())),
+            Value::Not { not } => ("Fn::Not", list(&[not])),
+            Value::Condition { condition } => ("Condition", string(condition)),
+            _ => return None,
+        };
+        Some(intrinsic)
+    }
+
+    /// Returns the values nested directly in this one, including the
+    /// arguments of intrinsic functions.
+    pub fn children(&self) -> Vec<&Value> {
+        match self {
+            Value::List(items)
+            | Value::Join { join: (_, items) }
+            | Value::Cidr { cidr: items }
+            | Value::FindInMap { find_in_map: items }
+            | Value::And { and: items }
+            | Value::Or { or: items } => items.iter().collect(),
+            Value::Map(map)
+            | Value::Sub {
+                variables: Some(map),
+                ..
+            } => map.values().collect(),
+            Value::Base64 { base64: value }
+            | Value::GetAZs { get_azs: value }
+            | Value::ImportValue {
+                import_value: value,
+            }
+            | Value::Split { split: (_, value) }
+            | Value::Not { not: value } => vec![value],
+            Value::Select {
+                select: (first, second),
+            }
+            | Value::If {
+                r#if: (_, first, second),
+            }
+            | Value::Equals {
+                equals: (first, second),
+            } => vec![first, second],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the logical names this value refers to through `Ref`,
+    /// `Fn::GetAtt` and the `${Name}` or `${Name.Attribute}` variables of
+    /// `Fn::Sub`, in document order.
+    pub fn references(&self) -> Vec<Reference> {
+        let mut references = Vec::new();
+        self.collect_references(&mut references);
+        references
+    }
+
+    fn collect_references(&self, references: &mut Vec<Reference>) {
+        match self {
+            Value::Ref { r#ref } => references.push(Reference {
+                name: r#ref.clone(),
+                attribute: None,
+            }),
+            Value::GetAtt { get_att } => references.push(Reference {
+                name: get_att[0].clone(),
+                attribute: Some(get_att[1].clone()),
+            }),
+            Value::Sub { sub, variables } => {
+                let defined = |name: &str| variables.as_ref().is_some_and(|v| v.contains_key(name));
+                references.extend(
+                    sub_variables(sub)
+                        .into_iter()
+                        .filter(|reference| !defined(&reference.name)),
+                );
+            }
+            _ => {}
+        }
+        for child in self.children() {
+            child.collect_references(references);
+        }
+    }
+
+    /// Builds a value from a mapping, recognizing intrinsic functions.
+    pub fn from_map(mut map: Map) -> Value {
+        if map.len() != 1 {
+            return Value::Map(map);
+        }
+
+        let (key, argument) = map.pop().expect("map has exactly one entry");
+        match intrinsic(&key, argument) {
+            Ok(value) => value,
+            Err(argument) => {
+                map.insert(key, argument);
+                Value::Map(map)
+            }
+        }
+    }
+}
+
+/// A name that a value depends on. `attribute` is set for `Fn::GetAtt` and
+/// `${Name.Attribute}` references.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub name: String,
+    pub attribute: Option<String>,
+}
+
+/// Extracts the `${...}` variables of an `Fn::Sub` string, skipping `${!...}`
+/// literals.
+fn sub_variables(text: &str) -> Vec<Reference> {
+    let mut references = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        let variable = rest[..end].trim();
+        rest = &rest[end + 1..];
+        if variable.starts_with('!') || variable.is_empty() {
+            continue;
+        }
+
+        let reference = match variable.split_once('.') {
+            Some((name, attribute)) if !name.starts_with("AWS::") => Reference {
+                name: name.to_string(),
+                attribute: Some(attribute.to_string()),
+            },
+            _ => Reference {
+                name: variable.to_string(),
+                attribute: None,
+            },
+        };
+        references.push(reference);
+    }
+    references
+}
+
+fn intrinsic(key: &str, argument: Value) -> Result<Value, Value> {
+    let boxed = |value: &Value| Box::new(value.clone());
+
+    let items = match (key, argument) {
+        ("Ref", Value::String(r#ref)) => return Ok(Value::Ref { r#ref }),
+        ("Fn::Sub", Value::String(sub)) => {
+            return Ok(Value::Sub {
+                sub,
+                variables: None,
+            })
+        }
+        ("Fn::Base64", base64) => {
+            return Ok(Value::Base64 {
+                base64: Box::new(base64),
+            })
+        }
+        ("Fn::GetAZs", get_azs) => {
+            return Ok(Value::GetAZs {
+                get_azs: Box::new(get_azs),
+            })
+        }
+        ("Fn::ImportValue", import_value) => {
+            return Ok(Value::ImportValue {
+                import_value: Box::new(import_value),
+            })
+        }
+        ("Condition", Value::String(condition)) => return Ok(Value::Condition { condition }),
+        (_, Value::List(items)) => items,
+        (_, argument) => return Err(argument),
+    };
+
+    let value = match (key, items.as_slice()) {
+        ("Fn::GetAtt", [Value::String(resource), Value::String(attribute)]) => Value::GetAtt {
+            get_att: vec![resource.clone(), attribute.clone()],
+        },
+        ("Fn::Join", [Value::String(delimiter), Value::List(values)]) => Value::Join {
+            join: (delimiter.clone(), values.clone()),
+        },
+        ("Fn::Sub", [Value::String(sub), Value::Map(variables)]) => Value::Sub {
+            sub: sub.clone(),
+            variables: Some(variables.clone()),
+        },
+        ("Fn::Cidr", [_, _, _]) => Value::Cidr { cidr: items },
+        ("Fn::FindInMap", [_, _, _]) => Value::FindInMap { find_in_map: items },
+        ("Fn::Select", [index, values]) => Value::Select {
+            select: (boxed(index), boxed(values)),
+        },
+        ("Fn::Split", [Value::String(delimiter), source]) => Value::Split {
+            split: (delimiter.clone(), boxed(source)),
+        },
+        ("Fn::If", [Value::String(condition), if_true, if_false]) => Value::If {
+            r#if: (condition.clone(), boxed(if_true), boxed(if_false)),
+        },
+        ("Fn::Equals", [left, right]) => Value::Equals {
+            equals: (boxed(left), boxed(right)),
+        },
+        ("Fn::And", [_, ..]) => Value::And { and: items },
+        ("Fn::Or", [_, ..]) => Value::Or { or: items },
+        ("Fn::Not", [not]) => Value::Not { not: boxed(not) },
+        _ => return Err(Value::List(items)),
+    };
+    Ok(value)
+}
+
+/// A part of a template that is either written out, or computed by an
+/// intrinsic function such as `Fn::If` and left unchecked. A string where a
+/// structure is expected, such as a policy given as JSON text, is also left
+/// unchecked.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Node<T> {
+    Literal(T),
+    Computed(Value),
+}
+
+impl<T> Node<T> {
+    /// Returns the written-out form, or `None` when it is computed.
+    pub fn literal(&self) -> Option<&T> {
+        match self {
+            Node::Literal(literal) => Some(literal),
+            Node::Computed(_) => None,
+        }
+    }
+}
+
+impl<T> Node<Vec<T>> {
+    /// Returns the items of a written-out list. A computed list has none.
+    pub fn items(&self) -> &[T] {
+        self.literal().map_or(&[], Vec::as_slice)
+    }
+}
+
+impl<T> Node<Vec<Node<T>>> {
+    /// Returns the written-out items of a written-out list with their
+    /// indexes, leaving out computed ones.
+    pub fn literals(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.items()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| Some((index, item.literal()?)))
+    }
+
+    /// Returns every item of a written-out list, or `None` when the list or
+    /// any of its items is computed.
+    pub fn all_literals(&self) -> Option<Vec<&T>> {
+        self.literal()?.iter().map(Node::literal).collect()
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Node<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        if value.as_intrinsic().is_some() || matches!(value, Value::String(_)) {
+            return Ok(Node::Computed(value));
+        }
+        serde_json::to_value(&value)
+            .and_then(serde_json::from_value)
+            .map(Node::Literal)
+            .map_err(de::Error::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a template value")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Value, E> {
+        Ok(Value::Bool(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Value, E> {
+        Ok(Value::Number(value))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Value, E> {
+        Ok(i64::try_from(value)
+            .map(Value::Number)
+            .unwrap_or(Value::Float(value as f64)))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Value, E> {
+        Ok(Value::Float(value))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Value, E> {
+        Ok(Value::String(value.to_string()))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Value, E> {
+        Ok(Value::String(value))
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Value::List(items))
+    }
+
+    fn visit_map<A>(self, mut access: A) -> Result<Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut map = Map::new();
+        while let Some((key, value)) = access.next_entry()? {
+            map.insert(key, value);
+        }
+        Ok(Value::from_map(map))
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Value::String(value) => serializer.serialize_str(value),
+            Value::Number(value) => serializer.serialize_i64(*value),
+            Value::Float(value) => serializer.serialize_f64(*value),
+            Value::Bool(value) => serializer.serialize_bool(*value),
+            Value::Null => serializer.serialize_unit(),
+            Value::List(items) => serializer.collect_seq(items),
+            Value::Map(map) => serializer.collect_map(map),
+            intrinsic => {
+                let (key, argument) = intrinsic.as_intrinsic().expect("value is an intrinsic");
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry(key, &argument)?;
+                map.end()
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use super::Value;
+    use super::{Map, Reference, Value};
 
     #[test]
     fn test_deserialize_value_string() {
@@ -97,9 +597,97 @@ mod test {
         let yaml = "Fn::Sub: '${AWS::StackName}-VPCID'";
         let expected = Value::Sub {
             sub: "${AWS::StackName}-VPCID".to_string(),
+            variables: None,
         };
 
         let actual = serde_yaml::from_str(yaml).unwrap();
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_deserialize_value_collections() {
+        let yaml = r#"
+Enabled: true
+Ratio: 0.5
+Nothing: null
+Items: [a, 1]
+Nested:
+  Ref: Name
+  Other: value
+        "#;
+        let expected = Value::Map(Map::from([
+            ("Enabled".to_string(), Value::Bool(true)),
+            ("Ratio".to_string(), Value::Float(0.5)),
+            ("Nothing".to_string(), Value::Null),
+            (
+                "Items".to_string(),
+                Value::List(vec![Value::String("a".to_string()), Value::Number(1)]),
+            ),
+            (
+                "Nested".to_string(),
+                Value::Map(Map::from([
+                    ("Ref".to_string(), Value::String("Name".to_string())),
+                    ("Other".to_string(), Value::String("value".to_string())),
+                ])),
+            ),
+        ]));
+
+        let actual = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_deserialize_value_unrecognized_intrinsic_shape() {
+        let yaml = "Fn::GetAtt: [OnlyOneItem]";
+        let expected = Value::Map(Map::from([(
+            "Fn::GetAtt".to_string(),
+            Value::List(vec![Value::String("OnlyOneItem".to_string())]),
+        )]));
+
+        let actual = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_serialize_value_round_trip() {
+        let json = r#"{"Fn::Join":["",["http://",{"Fn::GetAtt":["ElasticLoadBalancer","DNSName"]},{"Ref":"Path"}]]}"#;
+
+        let value: Value = serde_json::from_str(json).unwrap();
+        let actual = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, actual);
+    }
+
+    #[test]
+    fn test_value_references() {
+        let yaml = r#"
+Fn::Join:
+  - ''
+  - - Ref: Bucket
+    - Fn::GetAtt: [LoadBalancer, DNSName]
+    - Fn::Sub:
+        - '${AWS::Region}-${Queue.Arn}-${!Literal}-${Local}'
+        - Local: value
+"#;
+        let expected = vec![
+            Reference {
+                name: "Bucket".to_string(),
+                attribute: None,
+            },
+            Reference {
+                name: "LoadBalancer".to_string(),
+                attribute: Some("DNSName".to_string()),
+            },
+            Reference {
+                name: "AWS::Region".to_string(),
+                attribute: None,
+            },
+            Reference {
+                name: "Queue".to_string(),
+                attribute: Some("Arn".to_string()),
+            },
+        ];
+
+        let value: Value = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(expected, value.references());
+    }
 }
@@ -0,0 +1,603 @@
+use crate::{
+    diagnostic::Diagnostic,
+    document::{self, Format},
+    output::Output,
+    parameter::Parameter,
+    value::Value,
+};
+use anyhow::{bail, Context, Result};
+use indexmap::IndexMap;
+use serde::de::DeserializeOwned;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// A template deployed as its own stack, reduced to the names it exports and
+/// imports. Names are resolved as far as the template allows: `AWS::StackName`
+/// becomes the stack name, parameters their defaults, and other pseudo
+/// parameters stay as `${AWS::Region}`-style placeholders so that stacks in the
+/// same account and region still match.
+#[derive(Debug)]
+pub struct Stack {
+    pub name: String,
+    exports: Vec<Export>,
+    imports: Vec<Import>,
+    unresolved: Vec<String>,
+}
+
+#[derive(Debug)]
+struct Export {
+    name: String,
+    output: String,
+}
+
+#[derive(Debug)]
+struct Import {
+    name: String,
+    path: String,
+}
+
+impl Stack {
+    pub fn parse(name: &str, source: &str, format: Format) -> Result<Stack> {
+        let document = document::parse(source, format)?;
+        let parameters: IndexMap<String, Parameter> = section(&document, "Parameters")?;
+        let outputs: IndexMap<String, Output> = section(&document, "Outputs")?;
+        let resolver = Resolver {
+            stack: name,
+            parameters: &parameters,
+        };
+
+        let mut stack = Stack {
+            name: name.to_string(),
+            exports: Vec::new(),
+            imports: Vec::new(),
+            unresolved: Vec::new(),
+        };
+        for (output, value) in &outputs {
+            if let Some(export) = value.export_name() {
+                match resolver.resolve(export) {
+                    Some(name) => stack.exports.push(Export {
+                        name,
+                        output: output.clone(),
+                    }),
+                    None => stack
+                        .unresolved
+                        .push(format!("Outputs/{}/Export/Name", output)),
+                }
+            }
+        }
+        stack.collect_imports(&document, String::new(), &resolver);
+        Ok(stack)
+    }
+
+    /// Loads a template file, naming the stack after the file stem.
+    pub fn load(path: &Path) -> Result<Stack> {
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+        Stack::parse(name, &source, Format::detect(path, &source))
+            .with_context(|| format!("failed to load {}", path.display()))
+    }
+
+    fn collect_imports(&mut self, value: &Value, path: String, resolver: &Resolver) {
+        let join = |segment: &str| match path.is_empty() {
+            true => segment.to_string(),
+            false => format!("{}/{}", path, segment),
+        };
+
+        match value {
+            Value::ImportValue { import_value } => match resolver.resolve(import_value) {
+                Some(name) => self.imports.push(Import {
+                    name,
+                    path: path.clone(),
+                }),
+                None => self.unresolved.push(path.clone()),
+            },
+            Value::Map(map) => {
+                for (key, value) in map {
+                    self.collect_imports(value, join(key), resolver);
+                }
+            }
+            Value::List(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.collect_imports(item, join(&index.to_string()), resolver);
+                }
+            }
+            intrinsic => {
+                if let Some((key, argument)) = intrinsic.as_intrinsic() {
+                    self.collect_imports(&argument, join(key), resolver);
+                }
+            }
+        }
+    }
+}
+
+fn section<T: DeserializeOwned + Default>(document: &Value, name: &str) -> Result<T> {
+    match document {
+        Value::Map(map) => match map.get(name) {
+            Some(section) => serde_json::from_value(serde_json::to_value(section)?)
+                .with_context(|| format!("invalid {} section", name)),
+            None => Ok(T::default()),
+        },
+        _ => Ok(T::default()),
+    }
+}
+
+struct Resolver<'a> {
+    stack: &'a str,
+    parameters: &'a IndexMap<String, Parameter>,
+}
+
+impl Resolver<'_> {
+    /// Evaluates a value to a string, or `None` if it depends on something
+    /// only known at deploy time.
+    fn resolve(&self, value: &Value) -> Option<String> {
+        match value {
+            Value::String(text) => Some(text.clone()),
+            Value::Number(number) => Some(number.to_string()),
+            Value::Ref { r#ref } => self.variable(r#ref),
+            Value::Join {
+                join: (delimiter, values),
+            } => values
+                .iter()
+                .map(|value| self.resolve(value))
+                .collect::<Option<Vec<_>>>()
+                .map(|parts| parts.join(delimiter)),
+            Value::Sub { sub, variables } => {
+                let mut output = String::new();
+                let mut rest = sub.as_str();
+                while let Some(start) = rest.find("${") {
+                    output.push_str(&rest[..start]);
+                    rest = &rest[start + 2..];
+                    let end = rest.find('}')?;
+                    let variable = &rest[..end];
+                    rest = &rest[end + 1..];
+
+                    match variable.strip_prefix('!') {
+                        Some(literal) => output.push_str(&format!("${{{}}}", literal)),
+                        None => {
+                            let defined = variables.as_ref().and_then(|map| map.get(variable));
+                            let value = match defined {
+                                Some(value) => self.resolve(value)?,
+                                None => self.variable(variable)?,
+                            };
+                            output.push_str(&value);
+                        }
+                    }
+                }
+                output.push_str(rest);
+                Some(output)
+            }
+            _ => None,
+        }
+    }
+
+    fn variable(&self, name: &str) -> Option<String> {
+        match name {
+            "AWS::StackName" => Some(self.stack.to_string()),
+            "AWS::AccountId" | "AWS::Region" | "AWS::Partition" | "AWS::URLSuffix" => {
+                Some(format!("${{{}}}", name))
+            }
+            _ => self.resolve(self.parameters.get(name)?.default()?),
+        }
+    }
+}
+
+/// A set of stacks whose exports and imports are checked against each other.
+#[derive(Debug)]
+pub struct Workspace {
+    stacks: Vec<Stack>,
+}
+
+impl Workspace {
+    pub fn new(stacks: Vec<Stack>) -> Workspace {
+        Workspace { stacks }
+    }
+
+    /// Loads every template in the given files and directories. Stacks are
+    /// named after their file stem, so two files with the same stem, such as
+    /// `prod/network.yaml` and `dev/network.yaml`, are an error rather than
+    /// one silently standing in for the other.
+    pub fn load(paths: &[PathBuf]) -> Result<Workspace> {
+        let mut files = Vec::new();
+        for path in paths {
+            if path.is_dir() {
+                let mut entries: Vec<PathBuf> = fs::read_dir(path)?
+                    .map(|entry| entry.map(|entry| entry.path()))
+                    .collect::<Result<_, _>>()?;
+                entries.retain(|file| is_template(file));
+                entries.sort();
+                files.extend(entries);
+            } else {
+                files.push(path.clone());
+            }
+        }
+
+        let mut stacks: Vec<Stack> = Vec::new();
+        for file in &files {
+            let stack = Stack::load(file)?;
+            if let Some(other) = stacks.iter().position(|other| other.name == stack.name) {
+                bail!(
+                    "{} and {} are both named stack `{}`",
+                    files[other].display(),
+                    file.display(),
+                    stack.name
+                );
+            }
+            stacks.push(stack);
+        }
+        Ok(Workspace::new(stacks))
+    }
+
+    /// Reports imports without a matching export, exports defined by more
+    /// than one stack, exports nobody imports, and names that cannot be
+    /// resolved statically. Each diagnostic is paired with its stack name.
+    pub fn validate(&self) -> Vec<(String, Diagnostic)> {
+        let mut diagnostics = Vec::new();
+        for stack in &self.stacks {
+            for path in &stack.unresolved {
+                diagnostics.push((
+                    stack.name.clone(),
+                    Diagnostic::warning(path, "export or import name cannot be resolved"),
+                ));
+            }
+
+            for import in &stack.imports {
+                if self.exporter(&import.name).is_none() {
+                    diagnostics.push((
+                        stack.name.clone(),
+                        Diagnostic::error(
+                            &import.path,
+                            format!("imports `{}` which no stack exports", import.name),
+                        ),
+                    ));
+                }
+            }
+
+            for export in &stack.exports {
+                let path = format!("Outputs/{}/Export/Name", export.output);
+                match self.exporter(&export.name) {
+                    Some(first) if first.name != stack.name => {
+                        diagnostics.push((
+                            stack.name.clone(),
+                            Diagnostic::error(
+                                path,
+                                format!(
+                                    "export `{}` is also exported by stack `{}`",
+                                    export.name, first.name
+                                ),
+                            ),
+                        ));
+                    }
+                    _ if self.importers(&export.name).is_empty() => {
+                        diagnostics.push((
+                            stack.name.clone(),
+                            Diagnostic::warning(
+                                path,
+                                format!("export `{}` is not imported by any stack", export.name),
+                            ),
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        diagnostics
+    }
+
+    /// Orders the stacks so that every stack comes after the stacks it imports
+    /// from. Stacks that do not depend on each other keep their load order.
+    /// Fails with the stacks of each cycle when imports form cycles; stacks
+    /// that only depend on a cycle are left out.
+    pub fn deployment_order(&self) -> Result<Vec<&str>, Vec<Vec<&str>>> {
+        let dependencies: Vec<Vec<usize>> = self
+            .stacks
+            .iter()
+            .map(|stack| {
+                let mut dependencies: Vec<usize> = stack
+                    .imports
+                    .iter()
+                    .filter_map(|import| self.exporter_index(&import.name))
+                    .filter(|exporter| self.stacks[*exporter].name != stack.name)
+                    .collect();
+                dependencies.sort();
+                dependencies.dedup();
+                dependencies
+            })
+            .collect();
+
+        let mut order = Vec::new();
+        let mut deployed = vec![false; self.stacks.len()];
+        while order.len() < self.stacks.len() {
+            let next = (0..self.stacks.len()).find(|index| {
+                !deployed[*index]
+                    && dependencies[*index]
+                        .iter()
+                        .all(|dependency| deployed[*dependency])
+            });
+            match next {
+                Some(index) => {
+                    deployed[index] = true;
+                    order.push(self.stacks[index].name.as_str());
+                }
+                None => return Err(self.cycles(&dependencies, &deployed)),
+            }
+        }
+        Ok(order)
+    }
+
+    /// Finds the strongly connected components of the stacks that could not be
+    /// deployed, which are the cycles that block them.
+    fn cycles(&self, dependencies: &[Vec<usize>], deployed: &[bool]) -> Vec<Vec<&str>> {
+        let reachable: Vec<Vec<bool>> = (0..self.stacks.len())
+            .map(|start| {
+                let mut seen = vec![false; self.stacks.len()];
+                let mut pending = dependencies[start].clone();
+                while let Some(index) = pending.pop() {
+                    if !deployed[index] && !seen[index] {
+                        seen[index] = true;
+                        pending.extend(&dependencies[index]);
+                    }
+                }
+                seen
+            })
+            .collect();
+
+        let mut cycles = Vec::new();
+        let mut assigned = deployed.to_vec();
+        for start in 0..self.stacks.len() {
+            if assigned[start] || !reachable[start][start] {
+                continue;
+            }
+            let members: Vec<usize> = (0..self.stacks.len())
+                .filter(|index| reachable[start][*index] && reachable[*index][start])
+                .collect();
+            for index in &members {
+                assigned[*index] = true;
+            }
+            cycles.push(
+                members
+                    .into_iter()
+                    .map(|index| self.stacks[index].name.as_str())
+                    .collect(),
+            );
+        }
+        cycles
+    }
+
+    /// Compares this workspace with the `baseline` it replaces and reports
+    /// exports that were deleted or renamed while another stack still imports
+    /// them. CloudFormation rejects such an update.
+    pub fn breaking_changes(&self, baseline: &Workspace) -> Vec<(String, Diagnostic)> {
+        let mut diagnostics = Vec::new();
+        for previous in &baseline.stacks {
+            let current = self.stacks.iter().find(|stack| stack.name == previous.name);
+            for export in &previous.exports {
+                let still_exported = current.is_some_and(|stack| {
+                    stack.exports.iter().any(|other| other.name == export.name)
+                });
+                let importers: Vec<&str> = self
+                    .importers(&export.name)
+                    .into_iter()
+                    .filter(|importer| *importer != previous.name)
+                    .collect();
+                if still_exported || importers.is_empty() {
+                    continue;
+                }
+
+                let renamed = current.and_then(|stack| {
+                    stack
+                        .exports
+                        .iter()
+                        .find(|other| other.output == export.output)
+                });
+                let change = match renamed {
+                    Some(other) => format!("is renamed to `{}`", other.name),
+                    None => "is deleted".to_string(),
+                };
+                diagnostics.push((
+                    previous.name.clone(),
+                    Diagnostic::error(
+                        format!("Outputs/{}/Export/Name", export.output),
+                        format!(
+                            "export `{}` {} but is still imported by {}",
+                            export.name,
+                            change,
+                            importers.join(", ")
+                        ),
+                    ),
+                ));
+            }
+        }
+        diagnostics
+    }
+
+    fn exporter_index(&self, name: &str) -> Option<usize> {
+        self.stacks
+            .iter()
+            .position(|stack| stack.exports.iter().any(|export| export.name == name))
+    }
+
+    fn exporter(&self, name: &str) -> Option<&Stack> {
+        self.exporter_index(name).map(|index| &self.stacks[index])
+    }
+
+    fn importers(&self, name: &str) -> Vec<&str> {
+        self.stacks
+            .iter()
+            .filter(|stack| stack.imports.iter().any(|import| import.name == name))
+            .map(|stack| stack.name.as_str())
+            .collect()
+    }
+}
+
+fn is_template(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|extension| extension.to_str()),
+        Some("json" | "yaml" | "yml" | "template")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Stack, Workspace};
+    use crate::{diagnostic::Diagnostic, document::Format};
+    use std::fs;
+
+    const NETWORK: &str = r#"
+Resources:
+  Vpc:
+    Type: AWS::EC2::VPC
+    Properties:
+      CidrBlock: 10.0.0.0/16
+Outputs:
+  VpcId:
+    Value: !Ref Vpc
+    Export:
+      Name: !Sub ${AWS::StackName}-VpcId
+  SubnetIds:
+    Value: subnet-1,subnet-2
+    Export:
+      Name: !Join [':', [!Ref AWS::Region, subnets]]
+"#;
+
+    const APP: &str = r#"
+Parameters:
+  NetworkStack:
+    Type: String
+    Default: network
+Resources:
+  Group:
+    Type: AWS::EC2::SecurityGroup
+    Properties:
+      GroupDescription: App
+      VpcId: !ImportValue
+        Fn::Sub: ${NetworkStack}-VpcId
+  Queue:
+    Type: AWS::SQS::Queue
+    Properties:
+      QueueName: !ImportValue shared-QueueName
+"#;
+
+    fn build(templates: &[(&str, &str)]) -> Workspace {
+        Workspace::new(
+            templates
+                .iter()
+                .map(|(name, source)| Stack::parse(name, source, Format::Yaml).unwrap())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_validate_workspace() {
+        let workspace = build(&[("app", APP), ("network", NETWORK)]);
+        let expected = vec![
+            (
+                "app".to_string(),
+                Diagnostic::error(
+                    "Resources/Queue/Properties/QueueName",
+                    "imports `shared-QueueName` which no stack exports",
+                ),
+            ),
+            (
+                "network".to_string(),
+                Diagnostic::warning(
+                    "Outputs/SubnetIds/Export/Name",
+                    "export `${AWS::Region}:subnets` is not imported by any stack",
+                ),
+            ),
+        ];
+
+        assert_eq!(expected, workspace.validate());
+    }
+
+    #[test]
+    fn test_deployment_order() {
+        let workspace = build(&[("app", APP), ("network", NETWORK)]);
+        assert_eq!(Ok(vec!["network", "app"]), workspace.deployment_order());
+
+        let network = format!(
+            "{}  Queue:\n    Value: !ImportValue shared-QueueName\n",
+            NETWORK
+        );
+        let shared = r#"
+Resources: {}
+Outputs:
+  QueueName:
+    Value: !ImportValue network-VpcId
+    Export:
+      Name: shared-QueueName
+"#;
+        let workspace = build(&[("app", APP), ("network", &network), ("shared", shared)]);
+        assert_eq!(
+            Err(vec![vec!["network", "shared"]]),
+            workspace.deployment_order()
+        );
+    }
+
+    #[test]
+    fn test_breaking_changes() {
+        let baseline = build(&[("network", NETWORK), ("app", APP)]);
+        let renamed = NETWORK.replace("-VpcId", "-VpcIdentifier");
+        let current = build(&[("network", &renamed), ("app", APP)]);
+        let expected = vec![(
+            "network".to_string(),
+            Diagnostic::error(
+                "Outputs/VpcId/Export/Name",
+                "export `network-VpcId` is renamed to `network-VpcIdentifier` but is still imported by app",
+            ),
+        )];
+
+        assert_eq!(expected, current.breaking_changes(&baseline));
+
+        let deleted = r#"
+Resources: {}
+Outputs:
+  SubnetIds:
+    Value: subnet-1,subnet-2
+    Export:
+      Name: !Join [':', [!Ref AWS::Region, subnets]]
+"#;
+        let current = build(&[("network", deleted), ("app", APP)]);
+        let expected = vec![(
+            "network".to_string(),
+            Diagnostic::error(
+                "Outputs/VpcId/Export/Name",
+                "export `network-VpcId` is deleted but is still imported by app",
+            ),
+        )];
+
+        assert_eq!(expected, current.breaking_changes(&baseline));
+    }
+
+    #[test]
+    fn test_load_rejects_stacks_with_the_same_name() {
+        let root = std::env::temp_dir().join(format!("cfn-validator-{}", std::process::id()));
+        for environment in ["dev", "prod"] {
+            fs::create_dir_all(root.join(environment)).unwrap();
+            fs::write(root.join(environment).join("network.yaml"), NETWORK).unwrap();
+        }
+        fs::write(root.join("prod").join("app.yaml"), APP).unwrap();
+
+        let loaded = Workspace::load(&[root.join("prod")]);
+        let duplicated = Workspace::load(&[root.join("dev"), root.join("prod")]);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            Ok(vec!["network", "app"]),
+            loaded.unwrap().deployment_order()
+        );
+        assert_eq!(
+            format!(
+                "{} and {} are both named stack `network`",
+                root.join("dev").join("network.yaml").display(),
+                root.join("prod").join("network.yaml").display()
+            ),
+            duplicated.unwrap_err().to_string()
+        );
+    }
+}
@@ -0,0 +1,877 @@
+use crate::{
+    diagnostic::{Diagnostic, Diagnostics, Location},
+    value::{Map, Value},
+};
+use anyhow::{anyhow, bail, Result};
+use std::collections::HashMap;
+use yaml_rust2::{
+    parser::{Event, MarkedEventReceiver, Parser, Tag},
+    scanner::{Marker, Scanner, TScalarStyle, Token, TokenType},
+};
+
+/// Parses a YAML document, expanding short-form intrinsics such as `!Ref` and
+/// `!GetAtt` into the same values their long form produces.
+///
+/// Duplicate mapping keys are reported together as [`Diagnostics`], each
+/// naming the locations of both occurrences.
+pub fn from_str(source: &str) -> Result<Value> {
+    let mut loader = Loader::default();
+    Parser::new_from_str(source)
+        .load(&mut loader, false)
+        .map_err(|error| anyhow!("{}", error))?;
+
+    if let Some(error) = loader.error {
+        return Err(error);
+    }
+    if !loader.duplicates.is_empty() {
+        return Err(Diagnostics(loader.duplicates).into());
+    }
+    Ok(loader.document.unwrap_or(Value::Null))
+}
+
+/// Renders a value as block-style YAML with two-space indentation, writing
+/// intrinsic functions in their short form.
+pub fn to_string(value: &Value) -> String {
+    let mut emitter = Emitter::default();
+    match value {
+        Value::Map(map) if !map.is_empty() => emitter.write_map(map, 0, false),
+        Value::List(items) if !items.is_empty() => emitter.write_list(items, 0, false),
+        value => {
+            emitter.write_value(value, 0);
+            emitter.output.remove(0);
+        }
+    }
+    emitter.output
+}
+
+/// Finds the first comment in a YAML document and returns its line, counting
+/// from 1.
+///
+/// Comments can only sit between tokens, so each token's own text is skipped
+/// before looking for a `#` that follows whitespace. Block scalars need care:
+/// a `#` line indented as their content is content, not a comment.
+pub fn find_comment(source: &str) -> Result<Option<usize>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut scanner = Scanner::new(source.chars());
+    while let Some(Token(mark, token)) =
+        scanner.next_token().map_err(|error| anyhow!("{}", error))?
+    {
+        let style = match token {
+            TokenType::Scalar(style, _) => Some(style),
+            _ => None,
+        };
+        tokens.push((mark.index(), style));
+    }
+    // A simple key shares its start with the scalar it introduces; the
+    // scalar decides how far the text reaches.
+    tokens.sort_by_key(|(start, style)| (*start, style.is_some()));
+
+    let mut gaps = vec![(
+        0,
+        tokens.first().map_or(chars.len(), |(start, _)| *start),
+        None,
+    )];
+    for (index, (start, style)) in tokens.iter().enumerate() {
+        let end = tokens.get(index + 1).map_or(chars.len(), |(next, _)| *next);
+        if end > *start {
+            gaps.push((*start, end, *style));
+        }
+    }
+    let comment = gaps
+        .into_iter()
+        .find_map(|(start, end, style)| comment_between(&chars, start, end, style));
+    Ok(comment.map(|index| chars[..index].iter().filter(|char| **char == '\n').count() + 1))
+}
+
+/// Finds a comment in the text from a token to the next one.
+fn comment_between(
+    chars: &[char],
+    start: usize,
+    end: usize,
+    style: Option<TScalarStyle>,
+) -> Option<usize> {
+    let after_token = match style {
+        Some(TScalarStyle::SingleQuoted | TScalarStyle::DoubleQuoted) => {
+            after_quoted(chars, start, end)
+        }
+        Some(TScalarStyle::Literal | TScalarStyle::Folded) => {
+            return comment_in_block_scalar(chars, start, end)
+        }
+        _ => start,
+    };
+    (after_token..end).find(|index| is_comment_start(chars, *index))
+}
+
+/// The index just past the closing quote of a quoted scalar.
+fn after_quoted(chars: &[char], start: usize, end: usize) -> usize {
+    let quote = chars[start];
+    let mut index = start + 1;
+    while index < end {
+        match chars[index] {
+            '\\' if quote == '"' => index += 1,
+            '\'' if quote == '\'' && chars.get(index + 1) == Some(&'\'') => index += 1,
+            char if char == quote => return index + 1,
+            _ => {}
+        }
+        index += 1;
+    }
+    end
+}
+
+/// Finds a comment on a line after a block scalar's content that is indented
+/// less than the content. The scanner marks a block scalar where its content
+/// starts, so a comment after the `|` or `>` header falls in the gap before.
+fn comment_in_block_scalar(chars: &[char], start: usize, end: usize) -> Option<usize> {
+    let line_start = |index: usize| {
+        (0..index)
+            .rev()
+            .find(|before| chars[*before] == '\n')
+            .map_or(0, |newline| newline + 1)
+    };
+    let content_indent = start - line_start(start);
+    (start..end)
+        .filter(|index| is_comment_start(chars, *index))
+        .find(|index| {
+            let line_start = line_start(*index);
+            index - line_start < content_indent
+                && chars[line_start..*index].iter().all(|char| *char == ' ')
+        })
+}
+
+/// Whether a `#` at `index` starts a comment, which YAML requires to follow
+/// whitespace or start a line.
+fn is_comment_start(chars: &[char], index: usize) -> bool {
+    chars[index] == '#' && (index == 0 || matches!(chars[index - 1], ' ' | '\t' | '\n' | '\r'))
+}
+
+enum Frame {
+    List(Vec<Value>, Option<Tag>),
+    Map(MapFrame),
+}
+
+struct MapFrame {
+    map: Map,
+    key: Option<String>,
+    tag: Option<Tag>,
+    locations: HashMap<String, Location>,
+}
+
+#[derive(Default)]
+struct Loader {
+    stack: Vec<Frame>,
+    document: Option<Value>,
+    duplicates: Vec<Diagnostic>,
+    error: Option<anyhow::Error>,
+}
+
+impl MarkedEventReceiver for Loader {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        if self.error.is_some() {
+            return;
+        }
+        let location = Location {
+            line: mark.line(),
+            column: mark.col() + 1,
+        };
+        if let Err(error) = self.handle(event, location) {
+            self.error = Some(anyhow!("{} at {}", error, location));
+        }
+    }
+}
+
+impl Loader {
+    fn handle(&mut self, event: Event, location: Location) -> Result<()> {
+        match event {
+            Event::Scalar(value, style, _, tag) => {
+                if let Some(Frame::Map(frame @ MapFrame { key: None, .. })) = self.stack.last() {
+                    if let Some(first) = frame.locations.get(&value) {
+                        let message =
+                            format!("duplicate key `{}`, first defined at {}", value, first);
+                        let path = self.path(&value);
+                        self.duplicates
+                            .push(Diagnostic::error(path, message).at(location));
+                    }
+                    if let Some(Frame::Map(frame)) = self.stack.last_mut() {
+                        frame.locations.entry(value.clone()).or_insert(location);
+                        frame.key = Some(value);
+                    }
+                    return Ok(());
+                }
+                let value = scalar(value, style, tag.as_ref())?;
+                self.push(value, tag)
+            }
+            Event::SequenceStart(_, tag) => {
+                self.expect_value()?;
+                self.stack.push(Frame::List(Vec::new(), tag));
+                Ok(())
+            }
+            Event::MappingStart(_, tag) => {
+                self.expect_value()?;
+                self.stack.push(Frame::Map(MapFrame {
+                    map: Map::new(),
+                    key: None,
+                    tag,
+                    locations: HashMap::new(),
+                }));
+                Ok(())
+            }
+            Event::SequenceEnd => match self.stack.pop() {
+                Some(Frame::List(items, tag)) => self.push(Value::List(items), tag),
+                _ => bail!("unexpected end of sequence"),
+            },
+            Event::MappingEnd => match self.stack.pop() {
+                Some(Frame::Map(MapFrame {
+                    map,
+                    key: None,
+                    tag,
+                    ..
+                })) => self.push(Value::from_map(map), tag),
+                _ => bail!("unexpected end of mapping"),
+            },
+            Event::Alias(_) => bail!("YAML aliases are not supported in templates"),
+            _ => Ok(()),
+        }
+    }
+
+    fn expect_value(&self) -> Result<()> {
+        match self.stack.last() {
+            Some(Frame::Map(MapFrame { key: None, .. })) => {
+                bail!("only scalar mapping keys are supported")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the path of `key` in the mapping that is being read.
+    fn path(&self, key: &str) -> String {
+        let mut segments: Vec<String> = self
+            .stack
+            .iter()
+            .filter_map(|frame| match frame {
+                Frame::List(items, _) => Some(items.len().to_string()),
+                Frame::Map(frame) => frame.key.clone(),
+            })
+            .collect();
+        segments.push(key.to_string());
+        segments.join("/")
+    }
+
+    fn push(&mut self, value: Value, tag: Option<Tag>) -> Result<()> {
+        let value = match tag {
+            Some(tag) => apply_tag(value, &tag)?,
+            None => value,
+        };
+
+        match self.stack.last_mut() {
+            Some(Frame::List(items, _)) => items.push(value),
+            Some(Frame::Map(frame)) => {
+                let key = frame.key.take().expect("mapping key was read first");
+                frame.map.insert(key, value);
+            }
+            None => self.document = Some(value),
+        }
+        Ok(())
+    }
+}
+
+/// The handle the parser expands `!!` to.
+const CORE_HANDLE: &str = "tag:yaml.org,2002:";
+
+fn is_core(tag: &Tag) -> bool {
+    tag.handle == "!!" || tag.handle == CORE_HANDLE
+}
+
+fn scalar(value: String, style: TScalarStyle, tag: Option<&Tag>) -> Result<Value> {
+    if let Some(tag) = tag.filter(|tag| is_core(tag)) {
+        return match tag.suffix.as_str() {
+            "str" => Ok(Value::String(value)),
+            "int" | "float" | "bool" | "null" => Ok(resolve(value)),
+            suffix => bail!("unsupported tag `!!{}`", suffix),
+        };
+    }
+
+    match style {
+        TScalarStyle::Plain => Ok(resolve(value)),
+        _ => Ok(Value::String(value)),
+    }
+}
+
+/// Resolves a plain scalar using the YAML 1.2 core schema. Integers with
+/// leading zeros stay strings so identifiers like account IDs survive.
+fn resolve(value: String) -> Value {
+    match value.as_str() {
+        "" | "~" | "null" | "Null" | "NULL" => return Value::Null,
+        "true" | "True" | "TRUE" => return Value::Bool(true),
+        "false" | "False" | "FALSE" => return Value::Bool(false),
+        _ => {}
+    }
+
+    if is_integer(&value) {
+        if let Ok(number) = value.parse() {
+            return Value::Number(number);
+        }
+    }
+    if is_float(&value) {
+        if let Ok(number) = value.parse() {
+            return Value::Float(number);
+        }
+    }
+    Value::String(value)
+}
+
+fn is_integer(value: &str) -> bool {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    !digits.is_empty()
+        && digits.bytes().all(|byte| byte.is_ascii_digit())
+        && (digits == "0" || !digits.starts_with('0'))
+}
+
+fn is_float(value: &str) -> bool {
+    let unsigned = value.strip_prefix('-').unwrap_or(value);
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (unsigned, None),
+    };
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+    let digits = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+    let valid_exponent = exponent.is_none_or(|exponent| {
+        let exponent = exponent.strip_prefix(['-', '+']).unwrap_or(exponent);
+        !exponent.is_empty() && digits(exponent)
+    });
+
+    (!whole.is_empty() || !fraction.is_empty())
+        && digits(whole)
+        && digits(fraction)
+        && (whole.len() <= 1 || !whole.starts_with('0'))
+        && (mantissa.contains('.') || exponent.is_some())
+        && valid_exponent
+}
+
+fn apply_tag(value: Value, tag: &Tag) -> Result<Value> {
+    if is_core(tag) {
+        return Ok(value);
+    }
+    match (tag.handle.as_str(), tag.suffix.as_str()) {
+        ("!", "GetAtt") => {
+            let value = match value {
+                Value::String(name) => match name.split_once('.') {
+                    Some((resource, attribute)) => Value::List(vec![
+                        Value::String(resource.to_string()),
+                        Value::String(attribute.to_string()),
+                    ]),
+                    None => Value::String(name),
+                },
+                value => value,
+            };
+            Ok(Value::from_map(Map::from([(
+                "Fn::GetAtt".to_string(),
+                value,
+            )])))
+        }
+        ("!", "Ref") => Ok(Value::from_map(Map::from([("Ref".to_string(), value)]))),
+        ("!", "Condition") => Ok(Value::from_map(Map::from([(
+            "Condition".to_string(),
+            value,
+        )]))),
+        ("!", name) => Ok(Value::from_map(Map::from([(
+            format!("Fn::{}", name),
+            value,
+        )]))),
+        (handle, suffix) => bail!("unsupported tag `{}{}`", handle, suffix),
+    }
+}
+
+#[derive(Default)]
+struct Emitter {
+    output: String,
+}
+
+impl Emitter {
+    fn indent(&mut self, indent: usize) {
+        self.output.extend(std::iter::repeat_n(' ', indent));
+    }
+
+    fn write_map(&mut self, map: &Map, indent: usize, inline_first: bool) {
+        for (index, (key, value)) in map.iter().enumerate() {
+            if index > 0 || !inline_first {
+                self.indent(indent);
+            }
+            self.output.push_str(&key_string(key));
+            self.output.push(':');
+            self.write_value(value, indent);
+        }
+    }
+
+    fn write_list(&mut self, items: &[Value], indent: usize, inline_first: bool) {
+        for (index, item) in items.iter().enumerate() {
+            if index > 0 || !inline_first {
+                self.indent(indent);
+            }
+            self.output.push('-');
+            match item {
+                _ if short_form(item).is_some() => self.write_value(item, indent),
+                Value::Map(map) if !map.is_empty() => {
+                    self.output.push(' ');
+                    self.write_map(map, indent + 2, true);
+                }
+                Value::List(items) if !items.is_empty() => {
+                    self.output.push(' ');
+                    self.write_list(items, indent + 2, true);
+                }
+                item => self.write_value(item, indent),
+            }
+        }
+    }
+
+    /// Writes a value that follows a `key:` or `-` indicator. Nested blocks are
+    /// indented two spaces past `indent`.
+    fn write_value(&mut self, value: &Value, indent: usize) {
+        if let Some(inline) = inline(value) {
+            self.output.push(' ');
+            self.output.push_str(&inline);
+            self.output.push('\n');
+            return;
+        }
+
+        if let Some((key, argument)) = short_form(value) {
+            self.output.push(' ');
+            self.output.push_str(&short_tag(&key));
+            // A node carries at most one tag, so a nested intrinsic that
+            // directly follows another one is written in long form.
+            match short_form(&argument) {
+                Some((key, argument)) => {
+                    self.output.push('\n');
+                    self.write_map(&Map::from([(key, argument)]), indent + 2, false);
+                }
+                None => self.write_value(&argument, indent),
+            }
+            return;
+        }
+
+        match value {
+            Value::Map(map) => {
+                self.output.push('\n');
+                self.write_map(map, indent + 2, false);
+            }
+            Value::List(items) => {
+                self.output.push('\n');
+                self.write_list(items, indent + 2, false);
+            }
+            Value::String(text) => self.write_literal(text, indent + 2),
+            _ => unreachable!("scalars are always inline"),
+        }
+    }
+
+    fn write_literal(&mut self, text: &str, indent: usize) {
+        let content = text.trim_end_matches('\n');
+        let trailing = text.len() - content.len();
+        self.output.push_str(match trailing {
+            0 => " |-\n",
+            1 => " |\n",
+            _ => " |+\n",
+        });
+
+        for line in content.split('\n') {
+            if !line.is_empty() {
+                self.indent(indent);
+                self.output.push_str(line);
+            }
+            self.output.push('\n');
+        }
+        for _ in 1..trailing {
+            self.output.push('\n');
+        }
+    }
+}
+
+fn short_tag(key: &str) -> String {
+    format!("!{}", key.strip_prefix("Fn::").unwrap_or(key))
+}
+
+/// Returns the long-form key and argument of a value written with a short-form
+/// tag: a recognized intrinsic, or any other single `Fn::` entry mapping.
+fn short_form(value: &Value) -> Option<(String, Value)> {
+    if let Some((key, argument)) = value.as_intrinsic() {
+        return Some((key.to_string(), argument));
+    }
+    match value {
+        Value::Map(map) if map.len() == 1 => map
+            .first()
+            .filter(|(key, _)| key.starts_with("Fn::"))
+            .map(|(key, argument)| (key.clone(), argument.clone())),
+        _ => None,
+    }
+}
+
+/// Returns the single-line form of a value, or `None` if it needs a block.
+fn inline(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => string(text, false),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Float(number) => Some(format!("{:?}", number)),
+        Value::Bool(value) => Some(value.to_string()),
+        Value::Null => Some("null".to_string()),
+        Value::List(items) if items.is_empty() => Some("[]".to_string()),
+        Value::Map(map) if map.is_empty() => Some("{}".to_string()),
+        value => {
+            let (key, argument) = short_form(value)?;
+            let argument = match &argument {
+                Value::List(items) if key == "Fn::GetAtt" => {
+                    dotted(items).or_else(|| flow_list(items))?
+                }
+                Value::List(items) => flow_list(items)?,
+                argument if short_form(argument).is_some() => return None,
+                argument => inline(argument)?,
+            };
+            Some(format!("{} {}", short_tag(&key), argument))
+        }
+    }
+}
+
+/// Writes `Fn::GetAtt` arguments as `Resource.Attribute`. Logical IDs are
+/// alphanumeric, so the first dot always separates the two parts.
+fn dotted(items: &[Value]) -> Option<String> {
+    match items {
+        [Value::String(resource), Value::String(attribute)] if !resource.contains('.') => {
+            string(&format!("{}.{}", resource, attribute), false)
+        }
+        _ => None,
+    }
+}
+
+fn flow_list(items: &[Value]) -> Option<String> {
+    let items = items
+        .iter()
+        .map(|item| match item {
+            Value::String(text) => string(text, true),
+            Value::Number(_) | Value::Float(_) | Value::Bool(_) | Value::Null => inline(item),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(format!("[{}]", items.join(", ")))
+}
+
+fn key_string(key: &str) -> String {
+    string(key, false).unwrap_or_else(|| double_quoted(key))
+}
+
+/// Returns the plain or quoted form of a string, or `None` if it spans
+/// several lines and reads better as a literal block.
+fn string(text: &str, flow: bool) -> Option<String> {
+    if is_plain(text, flow) {
+        return Some(text.to_string());
+    }
+
+    let control = |char: char| char.is_control() && char != '\t';
+    let multiline = text.contains('\n');
+    if multiline
+        && !flow
+        && !text.starts_with([' ', '\t', '\n'])
+        && !text.chars().any(|char| char != '\n' && control(char))
+    {
+        return None;
+    }
+    if multiline || text.chars().any(control) {
+        return Some(double_quoted(text));
+    }
+    Some(format!("'{}'", text.replace('\'', "''")))
+}
+
+fn double_quoted(text: &str) -> String {
+    serde_json::to_string(text).expect("strings always serialize")
+}
+
+fn is_plain(text: &str, flow: bool) -> bool {
+    const INDICATORS: &[char] = &[
+        '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%', '@',
+        '`',
+    ];
+    const AMBIGUOUS: &[&str] = &[
+        "y", "Y", "yes", "Yes", "YES", "n", "N", "no", "No", "NO", "on", "On", "ON", "off", "Off",
+        "OFF", ".inf", ".nan",
+    ];
+
+    let starts_with_indicator = match text.strip_prefix(['-', '?', ':']) {
+        Some(rest) => rest.is_empty() || rest.starts_with(' '),
+        None => text.starts_with(INDICATORS),
+    };
+
+    !text.is_empty()
+        && text.trim() == text
+        && !starts_with_indicator
+        && !text.contains(": ")
+        && !text.contains(" #")
+        && !text.ends_with(':')
+        && !text.chars().any(|char| char.is_control())
+        && !(flow && text.contains([',', '[', ']', '{', '}']))
+        && !AMBIGUOUS.contains(&text)
+        && !looks_numeric(text)
+        && matches!(resolve(text.to_string()), Value::String(_))
+}
+
+/// Catches strings that other YAML parsers read as numbers, dates or
+/// sexagesimals, such as `0123`, `0x1F`, `1_000` or `2010-09-09`.
+fn looks_numeric(text: &str) -> bool {
+    let unsigned = text.strip_prefix(['-', '+', '.']).unwrap_or(text);
+    unsigned.starts_with(|char: char| char.is_ascii_digit())
+        && text
+            .chars()
+            .all(|char| char.is_ascii_hexdigit() || "xXoO_:.+-".contains(char))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_comment, from_str, to_string};
+    use crate::{
+        diagnostic::{Diagnostic, Diagnostics, Location},
+        value::{Map, Value},
+    };
+
+    #[test]
+    fn test_from_str_resolves_plain_scalars() {
+        let test_cases = [
+            ("~", Value::Null),
+            ("null", Value::Null),
+            ("True", Value::Bool(true)),
+            ("false", Value::Bool(false)),
+            ("-42", Value::Number(-42)),
+            ("1.5e3", Value::Float(1500.0)),
+            ("0123456789012", Value::String("0123456789012".to_string())),
+            ("yes", Value::String("yes".to_string())),
+            ("'42'", Value::String("42".to_string())),
+            ("!!str 42", Value::String("42".to_string())),
+            ("!!int 42", Value::Number(42)),
+        ];
+        for (yaml, expected) in test_cases {
+            let actual = from_str(yaml).unwrap();
+            assert_eq!(expected, actual, "{}", yaml);
+        }
+    }
+
+    #[test]
+    fn test_find_comment() {
+        let test_cases = [
+            ("# Top\nA: 1\n", Some(1)),
+            ("A: 1\nB: 2 # inline\n", Some(2)),
+            ("A:\n  # indented\n  B: 1\n", Some(2)),
+            ("A: [1, # flow\n  2]\n", Some(1)),
+            ("A: 'x' # after quote\n", Some(1)),
+            ("A: |\n  text\n# trailing\nB: 1\n", Some(3)),
+            ("A: | # header\n  text\n", Some(1)),
+            ("A: a#b\n", None),
+            ("A: 'a # b'\nB: \"c # \\\" d\"\n", None),
+            ("A: 'it''s # here'\n", None),
+            ("A: |\n  #!/bin/bash\n  echo # done\nB: 1\n", None),
+            ("A: >\n    text\n    # more text\n", None),
+            ("A: !Sub '${X} # y'\n", None),
+        ];
+        for (source, expected) in test_cases {
+            assert_eq!(expected, find_comment(source).unwrap(), "{:?}", source);
+        }
+    }
+
+    #[test]
+    fn test_from_str_keeps_key_order() {
+        let yaml = "Zeta: 1\nAlpha: [a, 2]\nMu:\n  Nested: true\n";
+        let expected = Value::Map(Map::from([
+            ("Zeta".to_string(), Value::Number(1)),
+            (
+                "Alpha".to_string(),
+                Value::List(vec![Value::String("a".to_string()), Value::Number(2)]),
+            ),
+            (
+                "Mu".to_string(),
+                Value::Map(Map::from([("Nested".to_string(), Value::Bool(true))])),
+            ),
+        ]));
+
+        let actual = from_str(yaml).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_from_str_empty_document() {
+        assert_eq!(Value::Null, from_str("").unwrap());
+    }
+
+    #[test]
+    fn test_from_str_rejects_aliases_and_complex_keys() {
+        let error = from_str("A: &name value\nB: *name\n").unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("YAML aliases are not supported in templates"));
+
+        let error = from_str("? [a, b]\n: value\n").unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("only scalar mapping keys are supported"));
+
+        let error = from_str("A: !!binary aGVsbG8=\n").unwrap_err();
+        assert!(error.to_string().starts_with("unsupported tag `!!binary`"));
+    }
+
+    #[test]
+    fn test_to_string_block_style() {
+        let value = Value::Map(Map::from([
+            ("Name".to_string(), Value::String("web".to_string())),
+            (
+                "Ports".to_string(),
+                Value::List(vec![Value::Number(80), Value::Number(443)]),
+            ),
+            (
+                "Tags".to_string(),
+                Value::List(vec![Value::Map(Map::from([
+                    ("Key".to_string(), Value::String("team".to_string())),
+                    ("Value".to_string(), Value::String("platform".to_string())),
+                ]))]),
+            ),
+            ("Empty".to_string(), Value::Map(Map::new())),
+        ]));
+        let expected = "Name: web\nPorts:\n  - 80\n  - 443\nTags:\n  - Key: team\n    Value: platform\nEmpty: {}\n";
+
+        assert_eq!(expected, to_string(&value));
+    }
+
+    #[test]
+    fn test_from_str_expands_short_forms() {
+        let yaml = "A: !Ref Name\nB: !GetAtt Balancer.DNSName\nC: !Sub '${AWS::StackName}-vpc'\nD: !Condition IsProduction\n";
+        let expected = Value::Map(Map::from([
+            (
+                "A".to_string(),
+                Value::Ref {
+                    r#ref: "Name".to_string(),
+                },
+            ),
+            (
+                "B".to_string(),
+                Value::GetAtt {
+                    get_att: vec!["Balancer".to_string(), "DNSName".to_string()],
+                },
+            ),
+            (
+                "C".to_string(),
+                Value::Sub {
+                    sub: "${AWS::StackName}-vpc".to_string(),
+                    variables: None,
+                },
+            ),
+            (
+                "D".to_string(),
+                Value::Condition {
+                    condition: "IsProduction".to_string(),
+                },
+            ),
+        ]));
+
+        let actual = from_str(yaml).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_from_str_rejects_unknown_tag_handles() {
+        let error = from_str("A: !<tag:example.com,2024:thing> value\n").unwrap_err();
+        assert!(error.to_string().starts_with("unsupported tag"));
+    }
+
+    #[test]
+    fn test_to_string_quotes_ambiguous_strings() {
+        let test_cases = [
+            ("plain", "plain"),
+            ("yes", "'yes'"),
+            ("42", "'42'"),
+            ("0123", "'0123'"),
+            ("0x1F", "'0x1F'"),
+            ("2010-09-09", "'2010-09-09'"),
+            ("1_000", "'1_000'"),
+            ("null", "'null'"),
+            ("", "''"),
+            ("- item", "'- item'"),
+            ("a: b", "'a: b'"),
+            ("it's", "it's"),
+            ("'quoted'", "'''quoted'''"),
+            (" padded", "' padded'"),
+            ("tab\there", "'tab\there'"),
+            ("bell\u{7}", "\"bell\\u0007\""),
+        ];
+        for (text, expected) in test_cases {
+            let actual = to_string(&Value::String(text.to_string()));
+            assert_eq!(format!("{}\n", expected), actual, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_to_string_quotes_keys() {
+        let value = Value::Map(Map::from([
+            ("on".to_string(), Value::Number(1)),
+            ("multi\nline".to_string(), Value::Number(2)),
+        ]));
+        let expected = "'on': 1\n\"multi\\nline\": 2\n";
+
+        assert_eq!(expected, to_string(&value));
+    }
+
+    #[test]
+    fn test_to_string_literal_blocks() {
+        let value = Value::Map(Map::from([
+            ("Strip".to_string(), Value::String("a\nb".to_string())),
+            ("Clip".to_string(), Value::String("a\n\nb\n".to_string())),
+            ("Keep".to_string(), Value::String("a\n\n".to_string())),
+            ("Indented".to_string(), Value::String(" a\nb".to_string())),
+        ]));
+        let expected =
+            "Strip: |-\n  a\n  b\nClip: |\n  a\n\n  b\nKeep: |+\n  a\n\nIndented: \" a\\nb\"\n";
+
+        assert_eq!(expected, to_string(&value));
+    }
+
+    #[test]
+    fn test_to_string_short_forms() {
+        let yaml = r#"A: !Ref Name
+B: !GetAtt Balancer.DNSName
+C: !Join
+  - ','
+  - - a
+    - b
+D: !Select
+  - 0
+  - !GetAZs ''
+E:
+  - !Ref Name
+  - !If [IsProduction, large, small]
+F: !Base64
+  Fn::Sub: '#!/bin/bash'
+G: !Transform
+  Name: AWS::Include
+"#;
+
+        let value = from_str(yaml).unwrap();
+        assert_eq!(yaml, to_string(&value));
+        assert_eq!(value, from_str(&to_string(&value)).unwrap());
+    }
+
+    #[test]
+    fn test_from_str_reports_duplicate_keys() {
+        let yaml = "A:\n  - B: 1\n    B: 2\nA: 3\n";
+        let expected = vec![
+            Diagnostic::error(
+                "A/0/B",
+                "duplicate key `B`, first defined at line 2, column 5",
+            )
+            .at(Location { line: 3, column: 5 }),
+            Diagnostic::error("A", "duplicate key `A`, first defined at line 1, column 1")
+                .at(Location { line: 4, column: 1 }),
+        ];
+
+        let error = from_str(yaml).unwrap_err();
+        let actual = error.downcast::<Diagnostics>().unwrap();
+        assert_eq!(expected, actual.0);
+    }
+
+    #[test]
+    fn test_from_str_allows_same_key_in_sibling_maps() {
+        let yaml = "A:\n  Name: 1\nB:\n  Name: 2\n";
+        assert!(from_str(yaml).is_ok());
+    }
+}
@@ -0,0 +1,43 @@
+use anyhow::{bail, Context, Result};
+use cfn_validator::Specification;
+use std::{env, fs, path::Path, process::Command};
+
+const USAGE: &str = "usage: cargo xtask codegen <specification>";
+
+/// Where the generated models live, relative to the workspace root.
+const GENERATED: &str = "src/resources/generated.rs";
+
+fn main() -> Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("codegen") => codegen(&args[1..]),
+        _ => bail!(USAGE),
+    }
+}
+
+/// Regenerates the resource models from a specification file or a directory
+/// of them, then formats the result with `rustfmt`.
+fn codegen(args: &[String]) -> Result<()> {
+    let [path] = args else {
+        bail!(USAGE);
+    };
+
+    let specification = Specification::load(Path::new(path))?;
+    let output = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .context("xtask is not inside the workspace")?
+        .join(GENERATED);
+    fs::write(&output, cfn_validator::generate(&specification))
+        .with_context(|| format!("failed to write {}", output.display()))?;
+
+    let status = Command::new("rustfmt")
+        .args(["--edition", "2021"])
+        .arg(&output)
+        .status()
+        .context("failed to run rustfmt")?;
+    if !status.success() {
+        bail!("rustfmt failed on {}", output.display());
+    }
+    println!("{}", output.display());
+    Ok(())
+}